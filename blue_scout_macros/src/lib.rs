@@ -4,7 +4,8 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use std::fmt::Write as _;
 use syn::{
-    Expr, Ident, LitStr, Result, Token, Type, TypePath,
+    Attribute, Expr, ExprArray, ExprLit, GenericArgument, Ident, Lit, LitStr, PathArguments,
+    Result, Token, Type, TypePath,
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
@@ -17,22 +18,284 @@ mod json;
 struct FieldDecl {
     name: Ident,
     ty: Type,
+    kind: FieldKind,
     pretty_name: Option<LitStr>,
     filter_type: Option<Ident>,
+    previous: Vec<PreviousField>,
+    validation: Validation,
+    form: FormDecl,
+}
+
+// How a field is shown on the scouting form, declared with
+// `#[form(widget = counter, section = "Auto", group = "Coral", placeholder = "...")]`
+#[derive(Default)]
+struct FormDecl {
+    widget: Option<Ident>,
+    section: Option<LitStr>,
+    group: Option<LitStr>,
+    placeholder: Option<LitStr>,
+}
+
+// The widgets accepted by `#[form(widget = ...)]` and their `Widget` variants
+const WIDGETS: &[(&str, &str)] = &[
+    ("text", "Text"),
+    ("number", "Number"),
+    ("counter", "Counter"),
+    ("checkbox", "Checkbox"),
+    ("radio", "Radio"),
+    ("select", "Select"),
+    ("textarea", "TextArea"),
+    ("hidden", "Hidden"),
+];
+
+// Parse the arguments of a `#[form(...)]` attribute into `form`
+fn parse_form(attr: &Attribute, form: &mut FormDecl) -> Result<()> {
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("widget") {
+            let widget: Ident = meta.value()?.parse()?;
+            if !WIDGETS.iter().any(|&(name, _)| widget == name) {
+                let names = WIDGETS
+                    .iter()
+                    .map(|&(name, _)| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(syn::Error::new_spanned(
+                    widget,
+                    format!("Unknown widget, expected one of {names}"),
+                ));
+            }
+            form.widget = Some(widget);
+        } else if meta.path.is_ident("section") {
+            form.section = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("group") {
+            form.group = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("placeholder") {
+            form.placeholder = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error(
+                "Unknown form option, expected `widget`, `section`, `group` or `placeholder`",
+            ));
+        }
+        Ok(())
+    })
+}
+
+// The constraints of a field, declared with
+// `#[validate(min = 1, max = 10, required, regex = "...", allowed = ["a", "b"])]`
+#[derive(Default)]
+struct Validation {
+    min: Option<Bound>,
+    max: Option<Bound>,
+    required: bool,
+    regex: Option<LitStr>,
+    allowed: Vec<LitStr>,
+}
+
+// A numeric bound of a field, optionally negative
+struct Bound {
+    negative: bool,
+    lit: Lit,
+}
+
+impl Bound {
+    // Parse a bound from the value of a `min` or `max` constraint
+    fn parse(input: ParseStream) -> Result<Self> {
+        let negative = input.peek(Token![-]);
+        if negative {
+            input.parse::<Token![-]>()?;
+        }
+
+        let lit: Lit = input.parse()?;
+        if !matches!(lit, Lit::Int(_) | Lit::Float(_)) {
+            return Err(syn::Error::new_spanned(lit, "Expected a number"));
+        }
+
+        Ok(Bound { negative, lit })
+    }
+
+    // The bound as a literal, typed by the compared value
+    fn tokens(&self) -> proc_macro2::TokenStream {
+        let lit = &self.lit;
+        if self.negative {
+            quote! { -#lit }
+        } else {
+            quote! { #lit }
+        }
+    }
+
+    // The bound as text, as used in HTML attributes and error messages
+    fn text(&self) -> String {
+        let digits = match &self.lit {
+            Lit::Int(lit) => lit.base10_digits().to_string(),
+            Lit::Float(lit) => lit.base10_digits().to_string(),
+            _ => unreachable!("Bounds are checked to be numbers when parsed"),
+        };
+        if self.negative {
+            format!("-{digits}")
+        } else {
+            digits
+        }
+    }
+}
+
+// Parse the arguments of a `#[validate(...)]` attribute into `validation`
+fn parse_validation(attr: &Attribute, validation: &mut Validation) -> Result<()> {
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("min") {
+            validation.min = Some(Bound::parse(meta.value()?)?);
+        } else if meta.path.is_ident("max") {
+            validation.max = Some(Bound::parse(meta.value()?)?);
+        } else if meta.path.is_ident("required") {
+            validation.required = true;
+        } else if meta.path.is_ident("regex") {
+            validation.regex = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("allowed") {
+            let values: ExprArray = meta.value()?.parse()?;
+            for value in values.elems {
+                match value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit), ..
+                    }) => validation.allowed.push(lit),
+                    other => {
+                        return Err(syn::Error::new_spanned(other, "Expected a string literal"));
+                    }
+                }
+            }
+        } else {
+            return Err(meta.error("Unknown constraint, expected `min`, `max`, `required`, `regex` or `allowed`"));
+        }
+        Ok(())
+    })
+}
+
+// A previous name and/or type of a field, declared with
+// `#[previously(old_name)]` or `#[previously(old_name: OldType)]`
+struct PreviousField {
+    name: Ident,
+    ty: Option<Type>,
+}
+
+impl Parse for PreviousField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+
+        let ty = if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(PreviousField { name, ty })
+    }
+}
+
+// How the values of a field are stored, given by its type and by the
+// `#[sql_enum]` or `#[text]` attribute
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    // A type `DataType` knows, with its `DataType` variant and SQL type
+    Primitive {
+        variant: &'static str,
+        sql_type: &'static str,
+    },
+    // An enum defined with `define_enum!`, stored as a DuckDB enum
+    Enum,
+    // A type stored as text, converted with `Display` and `FromStr`
+    Text,
+}
+
+impl FieldKind {
+    // The `DataType` variant holding the values of the field
+    fn variant(self, span: proc_macro2::Span) -> Ident {
+        let variant = match self {
+            Self::Primitive { variant, .. } => variant,
+            Self::Enum => "Enum",
+            Self::Text => "String",
+        };
+        Ident::new(variant, span)
+    }
+}
+
+// The marker attributes of a field, `#[sql_enum]` and `#[text]`
+#[derive(Default)]
+struct KindMarkers {
+    sql_enum: Option<Attribute>,
+    text: Option<Attribute>,
+}
+
+// Parse the `#[previously(...)]`, `#[validate(...)]`, `#[form(...)]`,
+// `#[sql_enum]` and `#[text]` attributes of a field
+fn parse_field_attributes(
+    attrs: &[Attribute],
+) -> Result<(Vec<PreviousField>, Validation, FormDecl, KindMarkers)> {
+    let mut previous = Vec::new();
+    let mut validation = Validation::default();
+    let mut form = FormDecl::default();
+    let mut markers = KindMarkers::default();
+    for attr in attrs {
+        if attr.path().is_ident("previously") {
+            previous.push(attr.parse_args()?);
+        } else if attr.path().is_ident("validate") {
+            parse_validation(attr, &mut validation)?;
+        } else if attr.path().is_ident("form") {
+            parse_form(attr, &mut form)?;
+        } else if attr.path().is_ident("sql_enum") {
+            attr.meta.require_path_only()?;
+            markers.sql_enum = Some(attr.clone());
+        } else if attr.path().is_ident("text") {
+            attr.meta.require_path_only()?;
+            markers.text = Some(attr.clone());
+        } else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "Unknown field attribute, expected `previously`, `validate`, `form`, `sql_enum` or `text`",
+            ));
+        }
+    }
+    Ok((previous, validation, form, markers))
+}
+
+// Decide how the values of a field are stored. Types other than the ones
+// `DataType` knows must be marked, so a typo can't silently become an enum.
+fn field_kind(ty: &Type, markers: &KindMarkers) -> Result<FieldKind> {
+    let primitive = get_primitive_type(ty);
+    match (primitive, &markers.sql_enum, &markers.text) {
+        (_, Some(sql_enum), Some(_)) => Err(syn::Error::new_spanned(
+            sql_enum,
+            "A field can't be both `sql_enum` and `text`",
+        )),
+        (Some(_), Some(marker), None) | (Some(_), None, Some(marker)) => Err(syn::Error::new_spanned(
+            marker,
+            "Only types `DataType` doesn't know can be marked `sql_enum` or `text`",
+        )),
+        (Some((variant, sql_type)), None, None) => Ok(FieldKind::Primitive { variant, sql_type }),
+        (None, Some(_), None) => Ok(FieldKind::Enum),
+        (None, None, Some(_)) => Ok(FieldKind::Text),
+        (None, None, None) => Err(syn::Error::new_spanned(
+            ty,
+            "Unsupported field type. Mark enums defined with `define_enum!` with `#[sql_enum]`, and types stored as text with `Display` and `FromStr` with `#[text]`",
+        )),
+    }
 }
 
 // Define the overall input structure for the struct definition
 struct StructInput {
     name: Ident,
+    table: LitStr,
     fields: Punctuated<FieldDecl, Comma>,
 }
 
 // Implement parsing for field declaration with pretty name support
 impl Parse for FieldDecl {
     fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let (previous, validation, form, markers) = parse_field_attributes(&attrs)?;
+
         let name = input.parse()?;
         input.parse::<Token![:]>()?;
         let ty = input.parse()?;
+        let kind = field_kind(&ty, &markers)?;
 
         // Check if there's a pretty name (=>)
         let pretty_name = if input.peek(Token![=>]) {
@@ -52,8 +315,12 @@ impl Parse for FieldDecl {
         Ok(FieldDecl {
             name,
             ty,
+            kind,
             pretty_name,
             filter_type,
+            previous,
+            validation,
+            form,
         })
     }
 }
@@ -64,71 +331,136 @@ impl Parse for StructInput {
         let name = input.parse()?;
         input.parse::<Token![,]>()?;
 
+        // Parse the table name (table = "name")
+        let table_key: Ident = input.parse()?;
+        if table_key != "table" {
+            return Err(syn::Error::new_spanned(
+                table_key,
+                "Expected the table name, as `table = \"name\"`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let table = input.parse()?;
+        input.parse::<Token![,]>()?;
+
         let fields = Punctuated::parse_terminated(input)?;
 
-        Ok(StructInput { name, fields })
+        Ok(StructInput {
+            name,
+            table,
+            fields,
+        })
+    }
+}
+
+// Helper function to get the inner type of an `Option<T>` field type, or
+// `None` if the field is not nullable
+fn get_option_inner_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path(TypePath { path, .. }) = ty
+        && let Some(segment) = path.segments.last()
+        && segment.ident == "Option"
+        && let PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(GenericArgument::Type(inner)) = args.args.first()
+    {
+        Some(inner)
+    } else {
+        None
     }
 }
 
-// Helper function to determine the DataType variant for a field type
-fn get_data_type_variant(ty: &Type) -> Option<Ident> {
-    if let Type::Path(TypePath { path, .. }) = ty {
-        if let Some(segment) = path.segments.last() {
-            let type_name = segment.ident.to_string();
-            let variant = match type_name.as_str() {
-                "u16" => "U16",
-                "u32" => "U32",
-                "u64" => "U64",
-                "i16" => "I16",
-                "i32" => "I32",
-                "i64" => "I64",
-                "String" => "String",
-                "bool" => "Bool",
-                "f32" => "Float",
-                _ => "Unknown",
-            };
+// Helper function to get the type of the values of a field, unwrapping
+// `Option<T>`
+fn get_value_type(ty: &Type) -> &Type {
+    get_option_inner_type(ty).unwrap_or(ty)
+}
 
-            return Some(Ident::new(variant, segment.ident.span()));
-        }
+// Helper function to check if a field type is the given type, ignoring
+// `Option<T>`
+fn is_value_type(ty: &Type, name: &str) -> bool {
+    if let Type::Path(TypePath { path, .. }) = get_value_type(ty)
+        && let Some(segment) = path.segments.last()
+    {
+        segment.ident == name
+    } else {
+        false
     }
+}
+
+// Helper function to get the `DataType` variant and SQL type of the types
+// `DataType` knows, ignoring `Option<T>`. The SQL types match the ones DuckDB
+// reports in `information_schema.columns`, so they can be compared.
+fn get_primitive_type(ty: &Type) -> Option<(&'static str, &'static str)> {
+    let Type::Path(TypePath { path, .. }) = get_value_type(ty) else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    let primitive = match segment.ident.to_string().as_str() {
+        "u16" => ("U16", "USMALLINT"),
+        "u32" => ("U32", "UINTEGER"),
+        "u64" => ("U64", "UBIGINT"),
+        "i16" => ("I16", "SMALLINT"),
+        "i32" => ("I32", "INTEGER"),
+        "i64" => ("I64", "BIGINT"),
+        "String" => ("String", "VARCHAR"),
+        "bool" => ("Bool", "BOOLEAN"),
+        "f32" => ("Float", "FLOAT"),
+        "f64" => ("Unknown", "DOUBLE"),
+        "u8" | "i8" | "u128" | "i128" | "usize" | "isize" | "char" => ("Unknown", "VARCHAR"),
+        _ => return None,
+    };
+    Some(primitive)
+}
 
-    None
-}
-
-// Helper function to map Rust type to SQL type
-fn map_to_sql_type(ty: &Type) -> &'static str {
-    if let Type::Path(TypePath { path, .. }) = ty {
-        if let Some(segment) = path.segments.last() {
-            let type_name = segment.ident.to_string();
-            match type_name.as_str() {
-                "u16" => "USMALLINT",
-                "u32" => "UINTEGER",
-                "u64" => "UBIGINT",
-                "i16" => "SMALLINT",
-                "i32" => "INTEGER",
-                "i64" => "BIGINT",
-                "String" => "TEXT",
-                "bool" => "BOOL",
-                "f32" => "FLOAT",
-                "f64" => "DOUBLE",
-                _ => "TEXT",
+// Helper function to convert a type name to the name of its DuckDB type
+// (e.g. ClimbResult -> climb_result)
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake_case.push('_');
             }
+            snake_case.extend(c.to_lowercase());
         } else {
-            "TEXT"
+            snake_case.push(c);
         }
-    } else {
-        "TEXT"
     }
+    snake_case
 }
 
-// Generate SQL CREATE TABLE statement for the struct
+// Helper function to map a field to the SQL type of its column. Enums are
+// mapped to the DuckDB type created by `define_enum!`.
+fn field_sql_type(field: &FieldDecl) -> String {
+    match field.kind {
+        FieldKind::Primitive { sql_type, .. } => sql_type.to_owned(),
+        FieldKind::Enum => enum_sql_type(get_value_type(&field.ty)),
+        FieldKind::Text => "VARCHAR".to_owned(),
+    }
+}
+
+// Helper function to get the name of the DuckDB type of an enum
+fn enum_sql_type(ty: &Type) -> String {
+    match ty {
+        Type::Path(TypePath { path, .. }) => path
+            .segments
+            .last()
+            .map_or_else(String::new, |segment| to_snake_case(&segment.ident.to_string())),
+        _ => String::new(),
+    }
+}
+
+// Generate SQL CREATE TABLE statement for the struct. The statements are
+// format strings with a `{table}` argument, so the struct can be stored in
+// the table of any season.
 fn generate_sql_create_table(fields: &Punctuated<FieldDecl, Comma>) -> String {
-    let mut sql = "CREATE TABLE IF NOT EXISTS scout_entries (\n    id INTEGER PRIMARY KEY DEFAULT nextval('scout_entries_id_seq'),\n".to_string();
+    let mut sql = String::from(
+        "CREATE TABLE IF NOT EXISTS {table} (\n    id INTEGER PRIMARY KEY DEFAULT nextval('{table}_id_seq'),\n",
+    );
 
     // Add all other fields
     for field in fields {
         let field_name = field.name.to_string();
-        let sql_type = map_to_sql_type(&field.ty);
+        let sql_type = field_sql_type(field);
         writeln!(sql, "    {} {},", field_name, sql_type).unwrap();
     }
 
@@ -142,6 +474,185 @@ fn generate_sql_create_table(fields: &Punctuated<FieldDecl, Comma>) -> String {
     sql
 }
 
+// Generate the SQL INSERT statement for the struct, returning the id of the
+// new row. The parameters are bound in field order.
+fn generate_sql_insert(fields: &Punctuated<FieldDecl, Comma>) -> String {
+    let columns = fields
+        .iter()
+        .map(|field| field.name.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = vec!["?"; fields.len()].join(", ");
+
+    format!("INSERT INTO {{table}} ({columns}) VALUES ({placeholders}) RETURNING id")
+}
+
+// Generate the SQL statement inserting a row with a given id, or replacing the
+// fields of the existing row with that id. The id is bound first, followed by
+// the fields in order.
+fn generate_sql_upsert(fields: &Punctuated<FieldDecl, Comma>) -> String {
+    let columns = fields
+        .iter()
+        .map(|field| field.name.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = vec!["?"; fields.len() + 1].join(", ");
+    let assignments = fields
+        .iter()
+        .map(|field| format!("{0} = excluded.{0}", field.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "INSERT INTO {{table}} (id, {columns}) VALUES ({placeholders}) ON CONFLICT (id) DO UPDATE SET {assignments}"
+    )
+}
+
+// Generate the SQL UPDATE statement replacing the fields of the row with a
+// given id. The id is bound first, followed by the fields in order.
+fn generate_sql_update(fields: &Punctuated<FieldDecl, Comma>) -> String {
+    let assignments = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| format!("{} = ?{}", field.name, i + 2))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("UPDATE {{table}} SET {assignments} WHERE id = ?1")
+}
+
+// Generate the code checking the constraints of a field, returning a
+// `BlueScoutError::Validation` for the first one that is violated
+fn generate_validation(field: &FieldDecl) -> Result<proc_macro2::TokenStream> {
+    let name = &field.name;
+    let name_str = name.to_string();
+    let pretty_name = field
+        .pretty_name
+        .as_ref()
+        .map_or_else(|| name_str.clone(), LitStr::value);
+    let validation = &field.validation;
+    let is_option = get_option_inner_type(&field.ty).is_some();
+    let is_string = is_value_type(&field.ty, "String");
+    let is_number = !is_string
+        && !is_value_type(&field.ty, "bool")
+        && matches!(field.kind, FieldKind::Primitive { variant, .. } if variant != "Unknown");
+
+    let error = |message: proc_macro2::TokenStream| {
+        quote! {
+            return Err(BlueScoutError::validation(#name_str, #message));
+        }
+    };
+
+    let mut checks = Vec::new();
+
+    for (bound, is_min) in [(&validation.min, true), (&validation.max, false)] {
+        let Some(bound) = bound else {
+            continue;
+        };
+        let (tokens, text) = (bound.tokens(), bound.text());
+        let comparison = if is_min { quote!(<) } else { quote!(>) };
+        let limit = if is_min { "at least" } else { "at most" };
+        if is_string {
+            let message = format!("{pretty_name} must be {limit} {text} characters long");
+            let error = error(quote!(#message));
+            checks.push(quote! {
+                if value.chars().count() #comparison #tokens {
+                    #error
+                }
+            });
+        } else if is_number {
+            let message = format!("{pretty_name} must be {limit} {text}");
+            let error = error(quote!(#message));
+            checks.push(quote! {
+                if *value #comparison #tokens {
+                    #error
+                }
+            });
+        } else {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`min` and `max` only apply to number and String fields",
+            ));
+        }
+    }
+
+    if let Some(regex) = &validation.regex {
+        if !is_string {
+            return Err(syn::Error::new_spanned(
+                regex,
+                "`regex` only applies to String fields",
+            ));
+        }
+        let message = format!("{pretty_name} has an invalid format");
+        let error = error(quote!(#message));
+        let expect_message = format!("The regex of {name_str} should be valid");
+        checks.push(quote! {
+            static REGEX: std::sync::LazyLock<regex::Regex> =
+                std::sync::LazyLock::new(|| regex::Regex::new(#regex).expect(#expect_message));
+            if !REGEX.is_match(value) {
+                #error
+            }
+        });
+    }
+
+    if !validation.allowed.is_empty() {
+        let allowed = &validation.allowed;
+        let message = format!(
+            "{pretty_name} must be one of: {}",
+            allowed.iter().map(LitStr::value).collect::<Vec<_>>().join(", ")
+        );
+        let error = error(quote!(#message));
+        checks.push(quote! {
+            if ![#(#allowed),*].contains(&value.to_string().as_str()) {
+                #error
+            }
+        });
+    }
+
+    let mut output = proc_macro2::TokenStream::new();
+
+    if validation.required {
+        let message = format!("{pretty_name} is required");
+        let error = error(quote!(#message));
+        let missing = match (is_option, is_string) {
+            (true, true) => quote! { self.#name.as_deref().is_none_or(|value| value.trim().is_empty()) },
+            (true, false) => quote! { self.#name.is_none() },
+            (false, true) => quote! { self.#name.trim().is_empty() },
+            (false, false) => {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "`required` only applies to String and Option fields",
+                ));
+            }
+        };
+        output.extend(quote! {
+            if #missing {
+                #error
+            }
+        });
+    }
+
+    if !checks.is_empty() {
+        // Missing values of nullable fields are not checked
+        output.extend(if is_option {
+            quote! {
+                if let Some(value) = self.#name.as_ref() {
+                    #(#checks)*
+                }
+            }
+        } else {
+            quote! {
+                {
+                    let value = &self.#name;
+                    #(#checks)*
+                }
+            }
+        });
+    }
+
+    Ok(output)
+}
+
 #[proc_macro]
 pub fn define_struct(input: TokenStream) -> TokenStream {
     // Parse the input tokens
@@ -168,8 +679,52 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
         struct_name.span(),
     );
 
-    // Generate the SQL CREATE TABLE statement
+    let sql_types_const_name = Ident::new(
+        &format!("{}_SQL_TYPES", struct_name.to_string().to_uppercase()),
+        struct_name.span(),
+    );
+
+    let previous_fields_const_name = Ident::new(
+        &format!("{}_PREVIOUS_FIELDS", struct_name.to_string().to_uppercase()),
+        struct_name.span(),
+    );
+
+    let nullable_const_name = Ident::new(
+        &format!("{}_NULLABLE_FIELDS", struct_name.to_string().to_uppercase()),
+        struct_name.span(),
+    );
+
+    let table_const_name = Ident::new(
+        &format!("{}_TABLE_NAME", struct_name.to_string().to_uppercase()),
+        struct_name.span(),
+    );
+    let table = input.table.value();
+
+    // Generate the SQL statements
+    let sql_create_sequence = "CREATE SEQUENCE IF NOT EXISTS {table}_id_seq START 1;";
     let sql_create_table = generate_sql_create_table(&input.fields);
+    let sql_insert = generate_sql_insert(&input.fields);
+    let sql_upsert = generate_sql_upsert(&input.fields);
+    let sql_update = generate_sql_update(&input.fields);
+    let column_list = input
+        .fields
+        .iter()
+        .map(|field| field.name.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Enum columns are read back as their labels
+    let select_list = input
+        .fields
+        .iter()
+        .map(|field| {
+            if field.kind == FieldKind::Enum {
+                format!("CAST({0} AS VARCHAR) AS {0}", field.name)
+            } else {
+                field.name.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
 
     // Create a constant name for the SQL statement
     let sql_const_name = Ident::new("CREATE_TABLE_SQL", struct_name.span());
@@ -185,14 +740,11 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
     });
 
     // Generate the field definitions for the InsertDataArgs, which replaces bool
-    // with Option<String>
+    // and Option<T> with Option<String>, as submitted by the form
     let insert_data_args_fields = input.fields.iter().map(|field| {
         let name = &field.name;
         let ty = &field.ty;
-        if let Type::Path(TypePath { path, .. }) = ty
-            && let Some(segment) = path.segments.last()
-            && segment.ident == "bool"
-        {
+        if is_value_type(ty, "bool") || get_option_inner_type(ty).is_some() {
             quote! { pub #name: Option<String> }
         } else {
             quote! { pub #name: #ty }
@@ -202,34 +754,71 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
     let insert_data_args_map = input.fields.iter().map(|field| {
         let name = &field.name;
         let ty = &field.ty;
-        if let Type::Path(TypePath { path, .. }) = ty
-            && let Some(segment) = path.segments.last()
-            && segment.ident == "bool"
-        {
-            quote! { #name: extract_checkbox(self.#name) }
+        if get_option_inner_type(ty).is_none() {
+            if is_value_type(ty, "bool") {
+                quote! { #name: extract_checkbox(self.#name) }
+            } else {
+                quote! { #name: self.#name }
+            }
+        } else if is_value_type(ty, "bool") {
+            quote! { #name: extract_optional_checkbox(self.#name) }
+        } else if is_value_type(ty, "String") {
+            // A blank input means the value is missing
+            quote! { #name: self.#name.filter(|x| !x.trim().is_empty()) }
         } else {
-            quote! { #name: self.#name }
+            // A blank input means the value is missing, any other input must
+            // be a valid value
+            let name_str = name.to_string();
+            let pretty_name = field
+                .pretty_name
+                .as_ref()
+                .map_or_else(|| name_str.clone(), LitStr::value);
+            quote! {
+                #name: match self.#name.as_deref().map(str::trim) {
+                    None | Some("") => None,
+                    Some(value) => Some(value.parse().map_err(|_| {
+                        BlueScoutError::validation(
+                            #name_str,
+                            format!("{value:?} is not a valid value for {}", #pretty_name),
+                        )
+                    })?),
+                }
+            }
         }
     });
 
     let insert_data_args_map_fn = quote! {
-        pub fn map_insert_data_args(self) -> #struct_name {
+        /// Converts the submitted form into the struct.
+        ///
+        /// # Errors
+        ///
+        /// Returns a `BlueScoutError::Validation` for the first optional
+        /// field whose input is neither blank nor a valid value.
+        pub fn map_insert_data_args(self) -> Result<#struct_name, BlueScoutError> {
             #[inline]
             #[allow(dead_code)]
             fn extract_checkbox(value: Option<String>) -> bool {
                 value.map(|x| x == "on").unwrap_or(false)
             }
-            #struct_name {
-                #(#insert_data_args_map),*
+            #[inline]
+            #[allow(dead_code)]
+            fn extract_optional_checkbox(value: Option<String>) -> Option<bool> {
+                match value.as_deref() {
+                    Some("on" | "true") => Some(true),
+                    Some("off" | "false") => Some(false),
+                    _ => None,
+                }
             }
+            Ok(#struct_name {
+                #(#insert_data_args_map),*
+            })
         }
     };
 
     // Get field names and types for the constant
     let field_name_types = input.fields.iter().map(|field| {
         let name = field.name.to_string();
-        let type_variant =
-            get_data_type_variant(&field.ty).unwrap_or(Ident::new("Unknown", field.name.span()));
+        let type_variant = field.kind.variant(field.name.span());
 
         quote! { (#name, DataTypeName::#type_variant) }
     });
@@ -256,12 +845,186 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
         let name = field.name.to_string();
         let filter_type = match &field.filter_type {
             Some(ident) => quote! { FilterType::#ident },
+            // Enums have a few options to select from
+            None if field.kind == FieldKind::Enum => quote! { FilterType::Select },
             None => quote! { FilterType::Normal }, // Default to Normal if not provided
         };
 
         quote! { (#name, #filter_type) }
     });
 
+    // Generate the options of the enum fields
+    let field_options_arms = input
+        .fields
+        .iter()
+        .filter(|field| field.kind == FieldKind::Enum)
+        .map(|field| {
+            let name = field.name.to_string();
+            let value_type = get_value_type(&field.ty);
+            quote! { #name => Some(<#value_type as SqlEnum>::LABELS) }
+        });
+
+    // Generate the DuckDB types of the enum fields, once per enum
+    let mut enum_types = Vec::new();
+    for field in &input.fields {
+        let value_type = get_value_type(&field.ty);
+        if field.kind == FieldKind::Enum
+            && !enum_types
+                .iter()
+                .any(|ty: &&Type| quote!(#ty).to_string() == quote!(#value_type).to_string())
+        {
+            enum_types.push(value_type);
+        }
+    }
+    let enum_types_sql = enum_types.iter().map(|ty| {
+        quote! { (<#ty as SqlEnum>::SQL_TYPE, <#ty as SqlEnum>::create_type_sql()) }
+    });
+    let enum_types_labels = enum_types.iter().map(|ty| {
+        quote! { (<#ty as SqlEnum>::SQL_TYPE, <#ty as SqlEnum>::LABELS) }
+    });
+
+    // Generate the validation of the fields
+    let validations = match input
+        .fields
+        .iter()
+        .map(generate_validation)
+        .collect::<Result<Vec<_>>>()
+    {
+        Ok(validations) => validations,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let constraints_const_name = Ident::new(
+        &format!("{}_CONSTRAINTS", struct_name.to_string().to_uppercase()),
+        struct_name.span(),
+    );
+
+    // Generate field names and constraints
+    let field_constraints = input.fields.iter().map(|field| {
+        let name = field.name.to_string();
+        let validation = &field.validation;
+        let bound = |bound: &Option<Bound>| match bound {
+            Some(bound) => {
+                let text = bound.text();
+                quote! { Some(#text) }
+            }
+            None => quote! { None },
+        };
+        let (min, max) = (bound(&validation.min), bound(&validation.max));
+        let required = validation.required;
+        let regex = match &validation.regex {
+            Some(regex) => quote! { Some(#regex) },
+            None => quote! { None },
+        };
+        let allowed = &validation.allowed;
+
+        quote! {
+            (#name, FieldConstraints {
+                min: #min,
+                max: #max,
+                required: #required,
+                regex: #regex,
+                allowed: &[#(#allowed),*],
+            })
+        }
+    });
+
+    let form_fields_const_name = Ident::new(
+        &format!("{}_FORM_FIELDS", struct_name.to_string().to_uppercase()),
+        struct_name.span(),
+    );
+
+    // Generate the form metadata of the fields. Fields without a section are
+    // in the section of the previous field.
+    let mut section = None;
+    let form_fields = input.fields.iter().map(|field| {
+        let name = field.name.to_string();
+        let pretty_name = field
+            .pretty_name
+            .as_ref()
+            .map_or_else(|| name.clone(), LitStr::value);
+        let form = &field.form;
+        let is_option = get_option_inner_type(&field.ty).is_some();
+
+        let widget = match &form.widget {
+            Some(widget) => widget.to_string(),
+            None if is_value_type(&field.ty, "bool") => {
+                // A checkbox can't be left blank
+                if is_option { "select" } else { "checkbox" }.to_owned()
+            }
+            None if field.kind == FieldKind::Enum => {
+                if is_option { "select" } else { "radio" }.to_owned()
+            }
+            None if is_value_type(&field.ty, "String") || field.kind == FieldKind::Text => {
+                "text".to_owned()
+            }
+            None => "number".to_owned(),
+        };
+        let widget = WIDGETS
+            .iter()
+            .find(|&&(name, _)| name == widget)
+            .map(|&(_, variant)| Ident::new(variant, field.name.span()))
+            .expect("Widgets are checked when parsed");
+
+        if form.section.is_some() {
+            section.clone_from(&form.section);
+        }
+        let optional = |value: Option<&LitStr>| match value {
+            Some(value) => quote! { Some(#value) },
+            None => quote! { None },
+        };
+        let field_section = optional(section.as_ref());
+        let group = optional(form.group.as_ref());
+        let placeholder = optional(form.placeholder.as_ref());
+
+        quote! {
+            FormField {
+                name: #name,
+                pretty_name: #pretty_name,
+                widget: Widget::#widget,
+                section: #field_section,
+                group: #group,
+                placeholder: #placeholder,
+            }
+        }
+    }).collect::<Vec<_>>();
+
+    // Generate the names of the nullable fields
+    let nullable_field_names = input
+        .fields
+        .iter()
+        .filter(|field| get_option_inner_type(&field.ty).is_some())
+        .map(|field| field.name.to_string());
+
+    // Generate field names and SQL column types
+    let field_sql_types = input.fields.iter().map(|field| {
+        let name = field.name.to_string();
+        let sql_type = field_sql_type(field);
+
+        quote! { (#name, #sql_type) }
+    });
+
+    // Generate the previous names and SQL types of the fields
+    let previous_fields = input.fields.iter().flat_map(|field| {
+        let name = field.name.to_string();
+        field.previous.iter().map(move |previous| {
+            let previous_name = previous.name.to_string();
+            let previous_sql_type = match &previous.ty {
+                Some(ty) => {
+                    // Previous types that `DataType` doesn't know were enums
+                    let sql_type = get_primitive_type(ty).map_or_else(
+                        || enum_sql_type(get_value_type(ty)),
+                        |(_, sql_type)| sql_type.to_owned(),
+                    );
+                    quote! { Some(#sql_type) }
+                }
+                None => quote! { None },
+            };
+
+            quote! { (#name, #previous_name, #previous_sql_type) }
+        })
+    });
+
     // Generate row mapping field assignments (index-based)
     let row_field_assignments = input.fields.iter().enumerate().map(|(i, field)| {
         let name = &field.name;
@@ -270,18 +1033,27 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
     });
 
     let field_count = input.fields.len();
+    let field_count_with_id = field_count + 1;
 
     // Generate to_sql field references
-    let to_sql_field_refs = input.fields.iter().map(|field| {
-        let name = &field.name;
-        quote! { let #name: &dyn ToSql = &self.#name; }
-    });
+    let to_sql_field_refs = input
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            quote! { let #name: &dyn duckdb::ToSql = &self.#name; }
+        })
+        .collect::<Vec<_>>();
 
     // Generate to_sql array elements
-    let to_sql_vector_elements = input.fields.iter().map(|field| {
-        let name = &field.name;
-        quote! { #name }
-    });
+    let to_sql_vector_elements = input
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            quote! { #name }
+        })
+        .collect::<Vec<_>>();
 
     // Generate the row mapper function
     let row_mapper_fn = quote! {
@@ -301,20 +1073,50 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
     let get_field_arms = input.fields.iter().map(|field| {
         let name = &field.name;
         let name_str = name.to_string();
-        let type_variant =
-            get_data_type_variant(&field.ty).unwrap_or(Ident::new("Unknown", name.span()));
-
-        match type_variant.to_string().as_str() {
-            "U16" => quote! { #name_str => Some(DataType::U16(self.#name)) },
-            "U32" => quote! { #name_str => Some(DataType::U32(self.#name)) },
-            "U64" => quote! { #name_str => Some(DataType::U64(self.#name)) },
-            "I16" => quote! { #name_str => Some(DataType::I16(self.#name)) },
-            "I32" => quote! { #name_str => Some(DataType::I32(self.#name)) },
-            "I64" => quote! { #name_str => Some(DataType::I64(self.#name)) },
-            "String" => quote! { #name_str => Some(DataType::String(self.#name.clone())) },
-            "Bool" => quote! { #name_str => Some(DataType::Bool(self.#name)) },
-            "Float" => quote! { #name_str => Some(DataType::Float(self.#name)) },
-            _ => quote! { #name_str => None },
+        let type_variant = field.kind.variant(name.span());
+
+        if type_variant == "Unknown" {
+            return quote! { #name_str => None };
+        }
+
+        if field.kind == FieldKind::Enum {
+            let value_type = get_value_type(&field.ty);
+            return if get_option_inner_type(&field.ty).is_some() {
+                quote! {
+                    #name_str => Some(self.#name.map_or(DataType::Null, |value| {
+                        DataType::Enum(<#value_type as SqlEnum>::label(value).to_owned())
+                    }))
+                }
+            } else {
+                quote! {
+                    #name_str => Some(DataType::Enum(<#value_type as SqlEnum>::label(self.#name).to_owned()))
+                }
+            };
+        }
+
+        // Text types are checked when parsed, so they are given as text
+        if field.kind == FieldKind::Text {
+            return if get_option_inner_type(&field.ty).is_some() {
+                quote! {
+                    #name_str => Some(self.#name.map_or(DataType::Null, |value| {
+                        DataType::String(value.to_string())
+                    }))
+                }
+            } else {
+                quote! { #name_str => Some(DataType::String(self.#name.to_string())) }
+            };
+        }
+
+        let value = if type_variant == "String" {
+            quote! { self.#name.clone() }
+        } else {
+            quote! { self.#name }
+        };
+
+        if get_option_inner_type(&field.ty).is_some() {
+            quote! { #name_str => Some(#value.map_or(DataType::Null, DataType::#type_variant)) }
+        } else {
+            quote! { #name_str => Some(DataType::#type_variant(#value)) }
         }
     });
 
@@ -322,83 +1124,100 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
     let set_field_arms = input.fields.iter().map(|field| {
         let name = &field.name;
         let name_str = name.to_string();
-        let type_variant =
-            get_data_type_variant(&field.ty).unwrap_or(Ident::new("Unknown", name.span()));
+        let type_variant = field.kind.variant(name.span());
 
-        match type_variant.to_string().as_str() {
-            "U16" => quote! {
-                #name_str => {
-                    if let DataType::U16(value) = value {
-                        self.#name = value;
-                        true
-                    } else { false }
-                }
-            },
-            "U32" => quote! {
-                #name_str => {
-                    if let DataType::U32(value) = value {
-                        self.#name = value;
-                        true
-                    } else { false }
-                }
-            },
-            "U64" => quote! {
-                #name_str => {
-                    if let DataType::U64(value) = value {
-                        self.#name = value;
-                        true
-                    } else { false }
+        if type_variant == "Unknown" {
+            return quote! { #name_str => false };
+        }
+
+        if field.kind == FieldKind::Enum {
+            let value_type = get_value_type(&field.ty);
+            return if get_option_inner_type(&field.ty).is_some() {
+                quote! {
+                    #name_str => match value {
+                        DataType::Enum(label) => {
+                            let Some(value) = <#value_type as SqlEnum>::from_label(&label) else {
+                                return false;
+                            };
+                            self.#name = Some(value);
+                            true
+                        }
+                        DataType::Null => {
+                            self.#name = None;
+                            true
+                        }
+                        _ => false,
+                    }
                 }
-            },
-            "I16" => quote! {
-                #name_str => {
-                    if let DataType::I16(value) = value {
-                        self.#name = value;
-                        true
-                    } else { false }
+            } else {
+                quote! {
+                    #name_str => {
+                        if let DataType::Enum(label) = value
+                            && let Some(value) = <#value_type as SqlEnum>::from_label(&label)
+                        {
+                            self.#name = value;
+                            true
+                        } else { false }
+                    }
                 }
-            },
-            "I32" => quote! {
-                #name_str => {
-                    if let DataType::I32(value) = value {
-                        self.#name = value;
-                        true
-                    } else { false }
+            };
+        }
+
+        if field.kind == FieldKind::Text {
+            return if get_option_inner_type(&field.ty).is_some() {
+                quote! {
+                    #name_str => match value {
+                        DataType::String(text) => {
+                            let Ok(value) = text.parse() else {
+                                return false;
+                            };
+                            self.#name = Some(value);
+                            true
+                        }
+                        DataType::Null => {
+                            self.#name = None;
+                            true
+                        }
+                        _ => false,
+                    }
                 }
-            },
-            "I64" => quote! {
-                #name_str => {
-                    if let DataType::I64(value) = value {
-                        self.#name = value;
-                        true
-                    } else { false }
+            } else {
+                quote! {
+                    #name_str => {
+                        if let DataType::String(text) = value
+                            && let Ok(value) = text.parse()
+                        {
+                            self.#name = value;
+                            true
+                        } else { false }
+                    }
                 }
-            },
-            "String" => quote! {
-                #name_str => {
-                    if let DataType::String(value) = value {
-                        self.#name = value;
+            };
+        }
+
+        if get_option_inner_type(&field.ty).is_some() {
+            quote! {
+                #name_str => match value {
+                    DataType::#type_variant(value) => {
+                        self.#name = Some(value);
                         true
-                    } else { false }
-                }
-            },
-            "Bool" => quote! {
-                #name_str => {
-                    if let DataType::Bool(value) = value {
-                        self.#name = value;
+                    }
+                    DataType::Null => {
+                        self.#name = None;
                         true
-                    } else { false }
+                    }
+                    _ => false,
                 }
-            },
-            "Float" => quote! {
+            }
+        } else {
+            quote! {
                 #name_str => {
-                    if let DataType::Float(value) = value {
+                    if let DataType::#type_variant(value) = value {
                         self.#name = value;
                         true
                     } else { false }
                 }
-            },
-            _ => quote! { #name_str => false },
+            }
         }
     });
 
@@ -430,7 +1249,26 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
         // Define a constant with field name and filter type pairs
         pub const #filter_type_const_name: &[(&str, FilterType)] = &[#(#field_filter_types),*];
 
-        // Define a constant with the SQL CREATE TABLE statement
+        // Define a constant with field name and constraints pairs
+        pub const #constraints_const_name: &[(&str, FieldConstraints)] = &[#(#field_constraints),*];
+
+        // Define a constant with the form metadata of the fields
+        pub const #form_fields_const_name: &[FormField] = &[#(#form_fields),*];
+
+        // Define a constant with the names of the nullable fields
+        pub const #nullable_const_name: &[&str] = &[#(#nullable_field_names),*];
+
+        // Define a constant with field name and SQL column type pairs
+        pub const #sql_types_const_name: &[(&str, &str)] = &[#(#field_sql_types),*];
+
+        // Define a constant with the previous names and SQL types of the fields
+        pub const #previous_fields_const_name: &[(&str, &str, Option<&str>)] = &[#(#previous_fields),*];
+
+        // Define a constant with the name of the database table
+        pub const #table_const_name: &str = #table;
+
+        // Define a constant with the SQL CREATE TABLE statement, formatted
+        // with the name of the table
         pub const #sql_const_name: &str = #sql_create_table;
 
         // Implement dynamic field access
@@ -466,6 +1304,18 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
                 #filter_type_const_name
             }
 
+            /// Get the field SQL column types (name, sql_type pairs)
+            pub fn field_sql_types() -> &'static [(&'static str, &'static str)] {
+                #sql_types_const_name
+            }
+
+            /// Get the previous names and SQL types of the fields
+            /// (name, previous_name, previous_sql_type triples), in declaration
+            /// order
+            pub fn field_history() -> &'static [(&'static str, &'static str, Option<&'static str>)] {
+                #previous_fields_const_name
+            }
+
             /// Get just the field names
             pub fn field_names() -> &'static [&'static str] {
                 &[#(#field_names,)*]
@@ -492,9 +1342,113 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
                     .map(|(_, filter_type)| *filter_type)
             }
 
-            /// Get the SQL CREATE TABLE statement for this struct
-            pub fn get_create_table_sql() -> &'static str {
-                #sql_const_name
+            /// Get the form metadata of the fields, in declaration order
+            pub const fn form_fields() -> &'static [FormField] {
+                #form_fields_const_name
+            }
+
+            /// Get the constraints of a field by name
+            pub fn field_constraints(field_name: &str) -> Option<FieldConstraints> {
+                #constraints_const_name.iter()
+                    .find(|(name, _)| *name == field_name)
+                    .map(|(_, constraints)| *constraints)
+            }
+
+            /// Check the fields against the constraints declared with
+            /// `#[validate(...)]`
+            ///
+            /// # Errors
+            ///
+            /// Returns a `BlueScoutError::Validation` for the first field
+            /// violating its constraints
+            pub fn validate(&self) -> Result<(), BlueScoutError> {
+                #(#validations)*
+                Ok(())
+            }
+
+            /// Get the names of the nullable (`Option<T>`) fields
+            pub fn nullable_fields() -> &'static [&'static str] {
+                #nullable_const_name
+            }
+
+            /// Check if a field is nullable (`Option<T>`) by name
+            pub fn is_field_nullable(field_name: &str) -> bool {
+                #nullable_const_name.contains(&field_name)
+            }
+
+            /// Get the SQL column type of field by name
+            pub fn get_field_sql_type(field_name: &str) -> Option<&'static str> {
+                #sql_types_const_name.iter()
+                    .find(|(name, _)| *name == field_name)
+                    .map(|(_, sql_type)| *sql_type)
+            }
+
+            /// Get the name of the database table declared with this struct
+            pub fn table_name() -> &'static str {
+                #table_const_name
+            }
+
+            /// Get the SQL statement creating the sequence of the ids of a
+            /// table storing this struct
+            pub fn get_create_sequence_sql(table: &str) -> String {
+                format!(#sql_create_sequence, table = table)
+            }
+
+            /// Get the SQL CREATE TABLE statement of a table storing this
+            /// struct
+            pub fn get_create_table_sql(table: &str) -> String {
+                format!(#sql_create_table, table = table)
+            }
+
+            /// Get the comma separated column names of the fields, in order
+            pub fn get_column_list() -> &'static str {
+                #column_list
+            }
+
+            /// Get the comma separated columns to select to read the fields
+            /// back, in order. Enum columns are cast to their labels.
+            pub fn get_select_list() -> &'static str {
+                #select_list
+            }
+
+            /// Get the options of an enum field by name
+            pub fn field_options(field_name: &str) -> Option<&'static [&'static str]> {
+                match field_name {
+                    #(#field_options_arms,)*
+                    _ => None,
+                }
+            }
+
+            /// Get the DuckDB enum types used by the fields (type name,
+            /// CREATE TYPE statement pairs)
+            pub fn enum_types_sql() -> Vec<(&'static str, String)> {
+                vec![#(#enum_types_sql),*]
+            }
+
+            /// Get the labels of the DuckDB enum types used by the fields
+            /// (type name, labels pairs)
+            pub fn enum_types_labels() -> &'static [(&'static str, &'static [&'static str])] {
+                &[#(#enum_types_labels),*]
+            }
+
+            /// Get the SQL INSERT statement of this struct into a table,
+            /// returning the id of the new row. Bind the parameters with
+            /// `to_sql`.
+            pub fn get_insert_sql(table: &str) -> String {
+                format!(#sql_insert, table = table)
+            }
+
+            /// Get the SQL statement inserting this struct into a table with
+            /// a given id, or replacing the row with that id if it exists.
+            /// Bind the parameters with `to_sql_with_id`.
+            pub fn get_upsert_sql(table: &str) -> String {
+                format!(#sql_upsert, table = table)
+            }
+
+            /// Get the SQL UPDATE statement replacing the row of a table with
+            /// a given id. Bind the parameters with `to_sql_with_id`.
+            pub fn get_update_sql(table: &str) -> String {
+                format!(#sql_update, table = table)
             }
 
             /// Convert the struct to a vector of SQL parameters
@@ -509,6 +1463,20 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
                 ]
             }
 
+            /// Convert the struct to a vector of SQL parameters, preceded by
+            /// the id of its row
+            #[cfg(feature = "ssr")]
+            pub fn to_sql_with_id<'a>(&'a self, id: &'a i32) -> [&'a dyn duckdb::ToSql; #field_count_with_id] {
+                // Create references to each field as ToSql trait objects
+                #(#to_sql_field_refs)*
+
+                // Return an array of the id and all field references
+                [
+                    id,
+                    #(#to_sql_vector_elements),*
+                ]
+            }
+
             // Generate the row mapper function
             #row_mapper_fn
         }
@@ -518,6 +1486,135 @@ pub fn define_struct(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+// Define the input structure for an enum definition
+struct EnumInput {
+    name: Ident,
+    variants: Punctuated<EnumVariantDecl, Comma>,
+}
+
+// A variant of an enum, with an optional label (Variant => "Label")
+struct EnumVariantDecl {
+    name: Ident,
+    label: Option<LitStr>,
+}
+
+impl Parse for EnumVariantDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+
+        let label = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(EnumVariantDecl { name, label })
+    }
+}
+
+impl Parse for EnumInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let variants = Punctuated::parse_terminated(input)?;
+
+        Ok(EnumInput { name, variants })
+    }
+}
+
+#[proc_macro]
+pub fn define_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as EnumInput);
+    let enum_name = &input.name;
+    let enum_name_str = enum_name.to_string();
+    let sql_type = to_snake_case(&enum_name_str);
+
+    // Default the labels to the variant names
+    let labels = input
+        .variants
+        .iter()
+        .map(|variant| match &variant.label {
+            Some(label) => label.value(),
+            None => variant.name.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let variant_names = input
+        .variants
+        .iter()
+        .map(|variant| &variant.name)
+        .collect::<Vec<_>>();
+
+    let variant_decls = variant_names.iter().zip(&labels).map(|(name, label)| {
+        quote! {
+            #[serde(rename = #label)]
+            #name
+        }
+    });
+
+    let label_arms = variant_names.iter().zip(&labels).map(|(name, label)| {
+        quote! { Self::#name => #label }
+    });
+
+    let output = quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+        #[non_exhaustive]
+        pub enum #enum_name {
+            #(#variant_decls),*
+        }
+
+        impl SqlEnum for #enum_name {
+            const SQL_TYPE: &'static str = #sql_type;
+            const LABELS: &'static [&'static str] = &[#(#labels),*];
+            const VARIANTS: &'static [Self] = &[#(Self::#variant_names),*];
+
+            fn label(self) -> &'static str {
+                match self {
+                    #(#label_arms),*
+                }
+            }
+        }
+
+        impl core::fmt::Display for #enum_name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(SqlEnum::label(*self))
+            }
+        }
+
+        impl core::str::FromStr for #enum_name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                <Self as SqlEnum>::from_label(s)
+                    .ok_or_else(|| format!("Invalid {} value: {}", #enum_name_str, s))
+            }
+        }
+
+        #[cfg(feature = "ssr")]
+        impl duckdb::ToSql for #enum_name {
+            fn to_sql(&self) -> duckdb::Result<duckdb::types::ToSqlOutput<'_>> {
+                Ok(duckdb::types::ToSqlOutput::from(SqlEnum::label(*self)))
+            }
+        }
+
+        #[cfg(feature = "ssr")]
+        impl duckdb::types::FromSql for #enum_name {
+            fn column_result(value: duckdb::types::ValueRef<'_>) -> duckdb::types::FromSqlResult<Self> {
+                let label = value.as_str()?;
+                <Self as SqlEnum>::from_label(label).ok_or_else(|| {
+                    duckdb::types::FromSqlError::Other(
+                        format!("Invalid {} value: {}", #enum_name_str, label).into(),
+                    )
+                })
+            }
+        }
+    };
+
+    output.into()
+}
+
 #[proc_macro]
 pub fn define_reduced_columns(input: TokenStream) -> TokenStream {
     // Parse the struct name and reduced column definitions
@@ -663,22 +1760,213 @@ impl Parse for ReducedColumnsInput {
 
 #[proc_macro]
 pub fn define_team_data(input: TokenStream) -> TokenStream {
-    // Parse the struct name and reduced column definitions
+    // Parse the struct names and metric definitions
     let parsed = parse_macro_input!(input as TeamDataInput);
     let struct_name = parsed.struct_name;
+    let summary_name = parsed.summary_name;
 
-    let team_data_closures = parsed.columns.iter().map(|col| {
-        let expr = &col.expr;
-        let column_name = col.name.value();
-        quote! { leptos::html::p().child(format!("{}: {}", #column_name, (#expr)(data))) }
+    let metrics_const_name = Ident::new(
+        &format!("{}_METRICS", summary_name.to_string().to_uppercase()),
+        summary_name.span(),
+    );
+
+    // Expand the metrics with statistics into one summary field per statistic
+    let mut distributions = Vec::new();
+    let mut summary_metrics = Vec::new();
+    for metric in &parsed.metrics {
+        let name = &metric.name;
+        let expr = &metric.expr;
+        if metric.stats.is_empty() {
+            summary_metrics.push(SummaryMetric {
+                name: name.clone(),
+                ty: metric.ty.clone(),
+                pretty_name: metric.pretty_name.value(),
+                value: quote! { (#expr)(data) },
+            });
+            continue;
+        }
+
+        // The values of the entries, as expected by `Distribution::new`
+        let values = match &metric.ty {
+            Type::Path(TypePath { path, .. }) if path.is_ident("f32") => {
+                quote! { data.iter().map(|x| Some((#expr)(x))) }
+            }
+            ty if get_option_inner_type(ty)
+                .is_some_and(|inner| matches!(inner, Type::Path(TypePath { path, .. }) if path.is_ident("f32"))) =>
+            {
+                quote! { data.iter().map(#expr) }
+            }
+            ty => {
+                return syn::Error::new_spanned(
+                    ty,
+                    "Metrics with statistics compute a value per entry, expected `f32` or `Option<f32>`",
+                )
+                .to_compile_error()
+                .into();
+            }
+        };
+        let distribution = format_ident!("{}_distribution", name);
+        distributions.push(quote! {
+            let #distribution = Distribution::new(#values);
+        });
+
+        for stat in &metric.stats {
+            let &(suffix, variant, label) = STATISTICS
+                .iter()
+                .find(|&&(suffix, _, _)| stat.kind == suffix)
+                .expect("Statistics are checked when parsed");
+            let variant = Ident::new(variant, stat.kind.span());
+            let (statistic, label) = match &stat.count {
+                Some(count) => (
+                    quote! { Statistic::#variant(#count) },
+                    label.replace("{}", count.base10_digits()),
+                ),
+                None => (quote! { Statistic::#variant }, label.to_owned()),
+            };
+            summary_metrics.push(SummaryMetric {
+                name: format_ident!("{}_{}", name, suffix),
+                ty: syn::parse_quote! { Option<f32> },
+                pretty_name: format!("{} ({label})", metric.pretty_name.value()),
+                value: quote! { #distribution.get(#statistic) },
+            });
+        }
+    }
+
+    // Generate the fields of the summary struct
+    let summary_fields = summary_metrics.iter().map(|metric| {
+        let name = &metric.name;
+        let ty = &metric.ty;
+        quote! { pub #name: #ty }
+    });
+
+    // Compute every metric from the data of the team
+    let summary_values = summary_metrics.iter().map(|metric| {
+        let name = &metric.name;
+        let value = &metric.value;
+        quote! { #name: #value }
+    });
+
+    // Generate the metadata of the metrics
+    let metric_infos = summary_metrics
+        .iter()
+        .map(|metric| {
+            let name = metric.name.to_string();
+            let pretty_name = &metric.pretty_name;
+            let Some((type_variant, _)) =
+                get_primitive_type(&metric.ty).filter(|&(variant, _)| variant != "Unknown")
+            else {
+                return Err(syn::Error::new_spanned(
+                    &metric.ty,
+                    "Unsupported metric type, expected a type `DataType` can hold",
+                ));
+            };
+
+            let type_variant = Ident::new(type_variant, metric.name.span());
+            Ok(quote! {
+                MetricInfo {
+                    name: #name,
+                    pretty_name: #pretty_name,
+                    data_type: DataTypeName::#type_variant,
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>();
+    let metric_infos = match metric_infos {
+        Ok(metric_infos) => metric_infos,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Generate the conversion of the metrics to `DataType`
+    let metric_values = summary_metrics.iter().map(|metric| {
+        let name = &metric.name;
+        let name_str = name.to_string();
+        let (variant, _) = get_primitive_type(&metric.ty).expect("Metric types are checked above");
+        let variant = Ident::new(variant, name.span());
+        let convert = if variant == "String" {
+            quote! { DataType::String(value.clone()) }
+        } else {
+            quote! { DataType::#variant(*value) }
+        };
+        let value = if get_option_inner_type(&metric.ty).is_some() {
+            quote! { self.#name.as_ref().map_or(DataType::Null, |value| #convert) }
+        } else {
+            quote! {{
+                let value = &self.#name;
+                #convert
+            }}
+        };
+
+        quote! { #name_str => Some(#value) }
     });
 
     // Generate the implementation
     let output = quote! {
+        /// The metrics of a team, computed from its scouting data.
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[non_exhaustive]
+        pub struct #summary_name {
+            #(#summary_fields),*
+        }
+
+        // Define a constant with the metadata of the metrics
+        pub const #metrics_const_name: &[MetricInfo] = &[#(#metric_infos),*];
+
+        impl #summary_name {
+            /// Compute the metrics from the scouting data of a team, in match
+            /// order
+            pub fn from_data(data: &[#struct_name]) -> Self {
+                #(#distributions)*
+                Self {
+                    #(#summary_values),*
+                }
+            }
+
+            /// Get the metadata of the metrics, in declaration order
+            pub const fn metrics() -> &'static [MetricInfo] {
+                #metrics_const_name
+            }
+
+            /// Get the value of a metric by name
+            pub fn get_metric(&self, metric_name: &str) -> Option<DataType> {
+                match metric_name {
+                    #(#metric_values,)*
+                    _ => None,
+                }
+            }
+
+            /// Get the metrics along with their values, in declaration order
+            pub fn metric_values(&self) -> Vec<(&'static MetricInfo, DataType)> {
+                #metrics_const_name
+                    .iter()
+                    .map(|metric| {
+                        let value = self.get_metric(metric.name).expect("Metric should exist");
+                        (metric, value)
+                    })
+                    .collect()
+            }
+
+            /// Render the metrics as a list of `name: value` paragraphs
+            pub fn view(&self) -> leptos::prelude::AnyView {
+                use leptos::prelude::*;
+                let metrics = self
+                    .metric_values()
+                    .into_iter()
+                    .map(|(metric, value)| {
+                        leptos::html::p().child(format!("{}: {}", metric.pretty_name, metric.format(&value)))
+                    })
+                    .collect::<Vec<_>>();
+                view!{ <div class="team-data">{metrics.into_any()}</div> }.into_any()
+            }
+        }
+
         impl #struct_name {
+            /// Compute the metrics of a team from its scouting data
+            pub fn summarize(data: &[#struct_name]) -> #summary_name {
+                #summary_name::from_data(data)
+            }
+
             pub fn view_team_data(data: &[#struct_name]) -> leptos::prelude::AnyView {
-                use leptos::prelude::*;
-                view!{ <div class="team-data">{(vec![#(#team_data_closures),*]).into_any()}</div> }.into_any()
+                Self::summarize(data).view()
             }
         }
     };
@@ -686,35 +1974,125 @@ pub fn define_team_data(input: TokenStream) -> TokenStream {
     output.into()
 }
 
-// Define structures for parsing reduced columns input
+// Define structures for parsing team data input
 struct TeamDataInput {
     struct_name: Ident,
-    columns: Vec<TeamDataDef>,
+    summary_name: Ident,
+    metrics: Vec<TeamDataDef>,
 }
 
+// A metric declared as `name: Type => "Pretty Name" = |data: &[T]| ...`, or
+// `#[stats(...)] name: Type => "Pretty Name" = |x: &T| ...` to compute
+// statistics of a value per entry
 struct TeamDataDef {
-    name: LitStr,
+    name: Ident,
+    ty: Type,
+    pretty_name: LitStr,
     expr: Expr,
+    stats: Vec<StatDecl>,
+}
+
+// A field of the generated summary struct
+struct SummaryMetric {
+    name: Ident,
+    ty: Type,
+    pretty_name: String,
+    value: proc_macro2::TokenStream,
+}
+
+// The statistics accepted by `#[stats(...)]`, with their `Statistic` variants
+// and the labels added to the pretty name of the metric
+const STATISTICS: &[(&str, &str, &str)] = &[
+    ("mean", "Mean", "Avg"),
+    ("median", "Median", "Median"),
+    ("min", "Min", "Min"),
+    ("max", "Max", "Max"),
+    ("std_dev", "StdDev", "Std Dev"),
+    ("recent", "Recent", "Last {} Avg"),
+];
+
+// A statistic of a metric, declared as `median` or `recent = 3`
+struct StatDecl {
+    kind: Ident,
+    count: Option<syn::LitInt>,
+}
+
+// Parse the arguments of a `#[stats(...)]` attribute
+fn parse_stats(attr: &Attribute) -> Result<Vec<StatDecl>> {
+    let mut stats = Vec::new();
+    attr.parse_nested_meta(|meta| {
+        let Some(kind) = meta.path.get_ident().cloned() else {
+            return Err(meta.error("Expected the name of a statistic"));
+        };
+        if !STATISTICS.iter().any(|&(name, _, _)| kind == name) {
+            let names = STATISTICS
+                .iter()
+                .map(|&(name, _, _)| format!("`{name}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(meta.error(format!("Unknown statistic, expected one of {names}")));
+        }
+        let count = if kind == "recent" {
+            let count: syn::LitInt = meta.value()?.parse()?;
+            if count.base10_parse::<usize>()? == 0 {
+                return Err(syn::Error::new_spanned(count, "Expected at least one match"));
+            }
+            Some(count)
+        } else {
+            None
+        };
+        stats.push(StatDecl { kind, count });
+        Ok(())
+    })?;
+
+    if stats.is_empty() {
+        return Err(syn::Error::new_spanned(attr, "Expected at least one statistic"));
+    }
+    Ok(stats)
 }
 
 impl Parse for TeamDataInput {
     fn parse(input: ParseStream) -> Result<Self> {
         let struct_name = input.parse()?;
         input.parse::<Token![,]>()?;
+        let summary_name = input.parse()?;
+        input.parse::<Token![,]>()?;
 
-        let mut columns = Vec::new();
+        let mut metrics = Vec::new();
 
         while !input.is_empty() {
-            // Parse column name (string literal)
-            let name: LitStr = input.parse()?;
+            let mut stats = Vec::new();
+            for attr in input.call(Attribute::parse_outer)? {
+                if attr.path().is_ident("stats") {
+                    stats = parse_stats(&attr)?;
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "Unknown metric attribute, expected `stats`",
+                    ));
+                }
+            }
 
-            // Parse arrow
+            // Parse the field name and type of the metric
+            let name = input.parse()?;
+            input.parse::<Token![:]>()?;
+            let ty = input.parse()?;
+
+            // Parse the pretty name
             input.parse::<Token![=>]>()?;
+            let pretty_name = input.parse()?;
 
             // Parse expression
+            input.parse::<Token![=]>()?;
             let expr = input.parse()?;
 
-            columns.push(TeamDataDef { name, expr });
+            metrics.push(TeamDataDef {
+                name,
+                ty,
+                pretty_name,
+                expr,
+                stats,
+            });
 
             // Skip comma if present
             if input.peek(Token![,]) {
@@ -726,7 +2104,8 @@ impl Parse for TeamDataInput {
 
         Ok(TeamDataInput {
             struct_name,
-            columns,
+            summary_name,
+            metrics,
         })
     }
 }
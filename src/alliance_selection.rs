@@ -0,0 +1,313 @@
+//! The alliance selection of an event, tracked live as captains pick teams.
+//!
+//! Picks and declines are marked by the scouts in the `alliance_selection`
+//! table until TBA publishes the final alliances, which then replace the
+//! marks.
+
+use serde::{Deserialize, Serialize};
+use tbaapi::models::EliminationAlliance;
+
+use crate::{team::TeamNumber, BlueScoutError};
+
+/// The most alliances an event can form, to catch mistakes when marking
+/// picks.
+pub const MAX_ALLIANCES: u8 = 16;
+
+/// What happened to a team during alliance selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum SelectionStatus {
+    /// The team joined an alliance, numbered from 1, as its captain or a pick.
+    Picked(u8),
+    /// The team declined an invitation, so it can't be picked anymore.
+    Declined,
+}
+
+/// A team marked during alliance selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SelectionMark {
+    /// The team number.
+    pub team_number: TeamNumber,
+    /// What happened to the team.
+    pub status: SelectionStatus,
+}
+
+/// A ranked team of an event and what happened to it during alliance
+/// selection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SelectionTeam {
+    /// The qualification rank of the team.
+    pub rank: i32,
+    /// The team number.
+    pub team_number: TeamNumber,
+    /// The team name (optional).
+    pub team_name: Option<String>,
+    /// What happened to the team, or `None` if it is still available.
+    pub status: Option<SelectionStatus>,
+}
+
+/// The state of the alliance selection of an event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AllianceSelection {
+    /// The ranked teams, by rank.
+    pub teams: Vec<SelectionTeam>,
+    /// The teams of every alliance, captain first.
+    pub alliances: Vec<Vec<TeamNumber>>,
+    /// Whether the alliances are the final ones published by TBA instead of
+    /// the ones marked by the scouts.
+    pub published: bool,
+}
+
+/// Groups the picked teams by alliance, in the order they were marked.
+///
+/// # Arguments
+///
+/// * `marks` - The picks and declines of the alliance selection.
+/// * `alliance_count` - The number of alliances of the event, if known, like
+///   once TBA publishes them. Otherwise the alliances are counted up to the
+///   highest one marked, since events form different numbers of alliances.
+///
+/// # Returns
+///
+/// The teams of every alliance, captain first.
+#[must_use]
+pub fn group_alliances(
+    marks: &[SelectionMark],
+    alliance_count: Option<u8>,
+) -> Vec<Vec<TeamNumber>> {
+    let alliance_count = alliance_count.unwrap_or_else(|| {
+        marks
+            .iter()
+            .filter_map(|mark| match mark.status {
+                SelectionStatus::Picked(alliance) => Some(alliance),
+                SelectionStatus::Declined => None,
+            })
+            .max()
+            .unwrap_or(0)
+    });
+    (1..=alliance_count)
+        .map(|alliance| {
+            marks
+                .iter()
+                .filter(|mark| mark.status == SelectionStatus::Picked(alliance))
+                .map(|mark| mark.team_number)
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns whether a team can't be picked anymore: it joined an alliance or
+/// declined an invitation.
+#[must_use]
+pub fn is_unavailable(marks: &[SelectionMark], team_number: TeamNumber) -> bool {
+    marks.iter().any(|mark| mark.team_number == team_number)
+}
+
+/// Returns what happened to a team during alliance selection, or `None` if it
+/// is still available.
+#[must_use]
+pub fn team_status(marks: &[SelectionMark], team_number: TeamNumber) -> Option<SelectionStatus> {
+    marks
+        .iter()
+        .rev()
+        .find(|mark| mark.team_number == team_number)
+        .map(|mark| mark.status)
+}
+
+/// Marks what happened to a team, replacing its previous mark. The team is
+/// moved to the end of the marks, like when it is marked again in the
+/// `alliance_selection` table.
+///
+/// # Arguments
+///
+/// * `marks` - The picks and declines of the alliance selection.
+/// * `team_number` - The team to mark.
+/// * `status` - What happened to the team, or `None` to clear its mark.
+///
+/// # Errors
+///
+/// Returns an error if the team is picked by an alliance that isn't between 1
+/// and `MAX_ALLIANCES`.
+pub fn mark_team(
+    marks: &mut Vec<SelectionMark>,
+    team_number: TeamNumber,
+    status: Option<SelectionStatus>,
+) -> Result<(), BlueScoutError> {
+    if let Some(SelectionStatus::Picked(alliance)) = status
+        && !(1..=MAX_ALLIANCES).contains(&alliance)
+    {
+        return Err(BlueScoutError::validation(
+            "alliance",
+            format!("The alliance should be between 1 and {MAX_ALLIANCES}"),
+        ));
+    }
+
+    marks.retain(|mark| mark.team_number != team_number);
+    if let Some(status) = status {
+        marks.push(SelectionMark {
+            team_number,
+            status,
+        });
+    }
+    Ok(())
+}
+
+/// Converts the playoff alliances published by TBA to marks, replacing the
+/// ones of the scouts.
+///
+/// # Returns
+///
+/// The picks of every alliance in order, captain first, followed by the teams
+/// that declined it.
+#[must_use]
+pub fn published_marks(alliances: &[EliminationAlliance]) -> Vec<SelectionMark> {
+    let mut marks = Vec::new();
+    for (alliance, published) in (1..).zip(alliances) {
+        marks.extend(
+            published
+                .picks
+                .iter()
+                .filter_map(|team_key| TeamNumber::from_key(team_key))
+                .map(|team_number| SelectionMark {
+                    team_number,
+                    status: SelectionStatus::Picked(alliance),
+                }),
+        );
+        marks.extend(
+            published
+                .declines
+                .iter()
+                .filter_map(|team_key| TeamNumber::from_key(team_key))
+                .map(|team_number| SelectionMark {
+                    team_number,
+                    status: SelectionStatus::Declined,
+                }),
+        );
+    }
+    marks
+}
+
+#[cfg(test)]
+mod tests {
+    use tbaapi::models::EliminationAlliance;
+
+    use super::{
+        group_alliances, is_unavailable, mark_team, published_marks, team_status, SelectionMark,
+        SelectionStatus, MAX_ALLIANCES,
+    };
+    use crate::team::TeamNumber;
+
+    /// Builds the marks of an alliance selection by marking teams in order.
+    fn marks(statuses: &[(u32, SelectionStatus)]) -> Vec<SelectionMark> {
+        let mut marks = Vec::new();
+        for &(number, status) in statuses {
+            mark_team(&mut marks, TeamNumber::new(number), Some(status))
+                .expect("Teams should be marked");
+        }
+        marks
+    }
+
+    /// Returns the numbers of the teams of every alliance.
+    fn numbers(alliances: &[Vec<TeamNumber>]) -> Vec<Vec<u32>> {
+        alliances
+            .iter()
+            .map(|teams| teams.iter().map(|team| team.number).collect())
+            .collect()
+    }
+
+    #[test]
+    fn marks_picks_and_declines() {
+        let mut marks = marks(&[
+            (254, SelectionStatus::Picked(1)),
+            (1678, SelectionStatus::Picked(2)),
+            (971, SelectionStatus::Declined),
+        ]);
+        let team = TeamNumber::new(4682);
+
+        assert!(!is_unavailable(&marks, team));
+        mark_team(&mut marks, team, Some(SelectionStatus::Picked(1)))
+            .expect("Team should be picked");
+        assert!(is_unavailable(&marks, team));
+        assert_eq!(team_status(&marks, team), Some(SelectionStatus::Picked(1)));
+        assert_eq!(
+            team_status(&marks, TeamNumber::new(971)),
+            Some(SelectionStatus::Declined)
+        );
+        assert_eq!(
+            numbers(&group_alliances(&marks, None)),
+            [vec![254, 4682], vec![1678]]
+        );
+    }
+
+    #[test]
+    fn replaces_and_clears_marks() {
+        let mut marks = marks(&[
+            (254, SelectionStatus::Picked(1)),
+            (4682, SelectionStatus::Picked(1)),
+        ]);
+        let team = TeamNumber::new(4682);
+
+        // The team declined after all, so it leaves the alliance
+        mark_team(&mut marks, team, Some(SelectionStatus::Declined)).expect("Team should decline");
+        assert_eq!(marks.len(), 2);
+        assert_eq!(team_status(&marks, team), Some(SelectionStatus::Declined));
+        assert_eq!(numbers(&group_alliances(&marks, None)), [vec![254]]);
+
+        mark_team(&mut marks, team, None).expect("Mark should be cleared");
+        assert!(!is_unavailable(&marks, team));
+        assert_eq!(team_status(&marks, team), None);
+    }
+
+    #[test]
+    fn rejects_invalid_alliances() {
+        let mut marks = marks(&[(254, SelectionStatus::Picked(1))]);
+
+        for alliance in [0, MAX_ALLIANCES + 1] {
+            assert!(mark_team(
+                &mut marks,
+                TeamNumber::new(4682),
+                Some(SelectionStatus::Picked(alliance))
+            )
+            .is_err());
+        }
+        assert_eq!(marks.len(), 1);
+        assert!(!is_unavailable(&marks, TeamNumber::new(4682)));
+    }
+
+    #[test]
+    fn merges_published_alliances() {
+        let alliances = [
+            EliminationAlliance::new(
+                vec!["frc971".to_owned()],
+                vec!["frc254".to_owned(), "frc1678".to_owned()],
+            ),
+            EliminationAlliance::new(
+                Vec::new(),
+                vec!["frc4682".to_owned(), "frc9999B".to_owned()],
+            ),
+            EliminationAlliance::new(Vec::new(), Vec::new()),
+        ];
+
+        let marks = published_marks(&alliances);
+        assert_eq!(
+            team_status(&marks, TeamNumber::new(971)),
+            Some(SelectionStatus::Declined)
+        );
+        assert_eq!(
+            team_status(&marks, TeamNumber::new(1678)),
+            Some(SelectionStatus::Picked(1))
+        );
+        assert!(!is_unavailable(&marks, TeamNumber::new(5000)));
+
+        // Every published alliance is listed, even the ones without teams
+        let alliances = group_alliances(&marks, Some(3));
+        assert_eq!(alliances.len(), 3);
+        assert_eq!(numbers(&alliances[..1]), [vec![254, 1678]]);
+        assert_eq!(alliances[1][0], TeamNumber::new(4682));
+        assert_eq!(alliances[1].len(), 2);
+        assert!(alliances[2].is_empty());
+    }
+}
@@ -8,20 +8,143 @@
 use std::collections::HashMap;
 
 use chrono::Datelike as _;
-use frozen_collections::MapQuery as _;
 use tbaapi::{
-    apis::{event_api::get_events_by_year, match_api::get_event_matches_simple},
-    models::{match_simple::CompLevel, Event},
+    apis::urlencode,
+    models::{
+        match_simple::CompLevel, EliminationAlliance, Event, EventRanking, MatchAlliance,
+        MatchSimple, TeamEventStatus,
+    },
 };
 
-use crate::{api_config, data::DataPoint, db::DB, BlueScoutError, MatchInfo, TeamInfo, TEAM_NAMES};
+use crate::{
+    alliance_selection::{
+        group_alliances, is_unavailable, published_marks, team_status, AllianceSelection,
+        SelectionMark, SelectionTeam,
+    },
+    data::{DataType, ScoutEntry},
+    db::{entries_table, entry_columns, get_data, get_pick_list, get_selection_marks, DB},
+    game::{average_points, game, team_metrics},
+    match_key::{MatchKey, MatchLevel},
+    tba_cache::cached_get,
+    team::TeamNumber,
+    team_names::team_name,
+    trend::{compute_trend, trend_series},
+    BlueScoutError, CacheAge, ComparedTeam, ComparisonRow, MatchInfo, PickListRow, TeamComparison,
+    TeamDetails, TeamEventRecord,
+};
+
+/// Returns the key of a match of the schedule of TBA.
+#[must_use]
+pub fn schedule_match_key(schedule_match: &MatchSimple) -> MatchKey {
+    let level = match schedule_match.comp_level {
+        CompLevel::Qm => MatchLevel::Qualification,
+        CompLevel::Ef => MatchLevel::EighthFinal,
+        CompLevel::Qf => MatchLevel::QuarterFinal,
+        CompLevel::Sf => MatchLevel::SemiFinal,
+        CompLevel::F => MatchLevel::Final,
+    };
+    MatchKey::new(
+        level,
+        u32::try_from(schedule_match.set_number).unwrap_or_default(),
+        u32::try_from(schedule_match.match_number).unwrap_or_default(),
+    )
+}
+
+/// Returns whether the playoffs of a schedule are a double elimination
+/// bracket. Its matches are the sets of the semifinal level, of which the
+/// first round alone has 4, while older brackets have at most 2.
+fn is_double_elimination(matches: &[MatchSimple]) -> bool {
+    matches
+        .iter()
+        .any(|x| x.comp_level == CompLevel::Sf && x.set_number > 2)
+}
+
+/// Parses the three teams of an alliance of a match, like `frc254` or
+/// `frc254B`.
+///
+/// # Errors
+///
+/// Returns an error if a team key is invalid or if the alliance doesn't have
+/// three teams.
+fn alliance_teams(
+    alliance: &MatchAlliance,
+    color: &str,
+) -> Result<[TeamNumber; 3], BlueScoutError> {
+    let teams = alliance
+        .team_keys
+        .iter()
+        .map(|team_key| {
+            TeamNumber::from_key(team_key)
+                .ok_or_else(|| BlueScoutError::api_error(format!("Invalid team key {team_key}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let count = teams.len();
+
+    teams.try_into().map_err(|_| {
+        BlueScoutError::api_error(format!(
+            "Invalid {color} team data. Expected 3 teams but found {count}"
+        ))
+    })
+}
+
+/// Returns the playoff alliance of the teams of a side of a match, numbered
+/// from 1, including the backup teams that joined an alliance.
+fn alliance_number(alliances: &[EliminationAlliance], team_keys: &[String]) -> Option<u8> {
+    (1..)
+        .zip(alliances)
+        .find(|&(_, alliance)| {
+            team_keys.iter().any(|team_key| {
+                alliance.picks.contains(team_key)
+                    || alliance
+                        .backup
+                        .as_ref()
+                        .and_then(Option::as_ref)
+                        .is_some_and(|backup| &backup.r#in == team_key)
+            })
+        })
+        .map(|(number, _)| number)
+}
+
+/// Fetches the next match of a team at an event that has not been played yet,
+/// qualification or playoff.
+///
+/// # Returns
+///
+/// A `Result` containing the key of the match, or `None` if the team has no
+/// match left.
+///
+/// # Errors
+///
+/// This function returns an error if the matches of the team can't be
+/// fetched from TBA.
+pub async fn get_next_team_match(
+    team_number: TeamNumber,
+    event: &str,
+    include_played: bool,
+) -> Result<Option<MatchKey>, BlueScoutError> {
+    let (matches, _) = cached_get::<Vec<MatchSimple>>(&format!(
+        "/team/frc{team_number}/event/{}/matches/simple",
+        urlencode(event)
+    ))
+    .await
+    .map_err(BlueScoutError::api_error)?;
 
-/// Fetches match information for a given match number and event.
+    // Playoff sets are numbered in the order they are played
+    Ok(matches
+        .iter()
+        .filter(|x| x.actual_time.is_none() || include_played)
+        .map(schedule_match_key)
+        .min())
+}
+
+/// Fetches match information for a given match and event.
 ///
 /// # Arguments
 ///
-/// * `match_number` - The match number to fetch information for.
+/// * `match_key` - The match to fetch information for.
 /// * `event` - The event key.
+/// * `all_events` - Whether to include scouting data recorded at other events
+///   in the team stats instead of only the data from `event`.
 ///
 /// # Returns
 ///
@@ -34,159 +157,491 @@ use crate::{api_config, data::DataPoint, db::DB, BlueScoutError, MatchInfo, Team
 ///
 /// # Panics
 ///
-/// Panics if the database connection is not initialized or if the team numbers
-/// are not integers.
-pub async fn get_match_info(match_number: i32, event: &str) -> Result<MatchInfo, BlueScoutError> {
-    let matches = get_event_matches_simple(api_config(), event)
-        .await
-        .map_err(BlueScoutError::api_error)?;
+/// Panics if the database connection is not initialized.
+pub async fn get_match_info(
+    match_key: MatchKey,
+    event: &str,
+    all_events: bool,
+) -> Result<MatchInfo, BlueScoutError> {
+    let (matches, schedule_age) =
+        cached_get::<Vec<MatchSimple>>(&format!("/event/{}/matches/simple", urlencode(event)))
+            .await
+            .map_err(BlueScoutError::api_error)?;
 
     let target_match = matches
         .iter()
-        .find(|x| x.match_number == match_number && x.comp_level == CompLevel::Qm)
-        .ok_or_else(|| anyhow::anyhow!("Match number not found"))?;
+        .find(|&x| schedule_match_key(x) == match_key)
+        .ok_or_else(|| anyhow::anyhow!("Match {match_key} not found"))?;
 
-    let red_team: Vec<usize> = target_match
-        .alliances
-        .red
-        .team_keys
+    let red_team = alliance_teams(&target_match.alliances.red, "red")?;
+    let blue_team = alliance_teams(&target_match.alliances.blue, "blue")?;
+
+    let db = DB.get().expect("Database not initialized");
+    let conn = db.lock().await;
+
+    let event_filter = (!all_events).then_some(event);
+
+    // The statement borrows the connection, which can't be held across an
+    // await
+    let mut entries = {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM {} WHERE deleted_at IS NULL AND (team_number = ?1 OR team_number = ?2 OR team_number = ?3 OR team_number = ?4 OR team_number = ?5 OR team_number = ?6) AND (?7 IS NULL OR event_key = ?7)",
+            entry_columns(),
+            entries_table()
+        ))?;
+        stmt.query_map(
+            duckdb::params![
+                red_team[0],
+                red_team[1],
+                red_team[2],
+                blue_team[0],
+                blue_team[1],
+                blue_team[2],
+                event_filter,
+            ],
+            ScoutEntry::map_entry,
+        )?
+        .collect::<Result<Vec<ScoutEntry>, _>>()?
+    };
+    drop(conn);
+
+    // The charts of the teams show the first series, against the average of
+    // the event
+    let series = trend_series().into_iter().next();
+    let event_entries = if series.is_some() {
+        get_data(event_filter)
+            .await
+            .map_err(BlueScoutError::database_error)?
+    } else {
+        Vec::new()
+    };
+    ScoutEntry::sort_by_match(&mut entries);
+
+    let mut team_data: HashMap<TeamNumber, Vec<ScoutEntry>> = red_team
         .iter()
-        .map(|x| {
-            x.trim_start_matches("frc")
-                .parse::<usize>()
-                .expect("Team number should be a number")
-        })
+        .chain(&blue_team)
+        .map(|&team_number| (team_number, Vec::new()))
         .collect();
 
-    if red_team.len() != 3 {
-        return Err(BlueScoutError::api_error(format!(
-            "Invalid red team data. Expected 3 teams but found {}",
-            red_team.len()
-        )));
+    for entry in entries {
+        team_data
+            .get_mut(&entry.data.team_number)
+            .expect("Team number should have been inserted earlier")
+            .push(entry);
     }
 
-    let blue_team: Vec<usize> = target_match
-        .alliances
-        .blue
-        .team_keys
-        .iter()
-        .map(|x| {
-            x.trim_start_matches("frc")
-                .parse::<usize>()
-                .expect("Team number should be a number")
-        })
-        .collect();
+    let mut match_info = MatchInfo::empty(match_key, red_team, blue_team);
+    match_info.label = match_key.label(is_double_elimination(&matches));
+    if match_key.level != MatchLevel::Qualification {
+        let alliances = get_alliances(event).await;
+        match_info.red_alliance =
+            alliance_number(&alliances, &target_match.alliances.red.team_keys);
+        match_info.blue_alliance =
+            alliance_number(&alliances, &target_match.alliances.blue.team_keys);
+    }
+
+    for (teams, alliance) in [
+        (&mut match_info.red, &target_match.alliances.red),
+        (&mut match_info.blue, &target_match.alliances.blue),
+    ] {
+        for team in teams {
+            let team_key = team.team_number.key();
+            team.team_name = team_name(team.team_number);
+            team.surrogate = alliance.surrogate_team_keys.contains(&team_key);
+            team.disqualified = alliance.dq_team_keys.contains(&team_key);
 
-    if blue_team.len() != 3 {
-        return Err(BlueScoutError::api_error(format!(
-            "Invalid blue team data. Expected 3 teams but found {}",
-            blue_team.len()
-        )));
+            let entries = &team_data[&team.team_number];
+            if entries.is_empty() {
+                continue;
+            }
+            team.metrics = Some(team_metrics(entries));
+            team.estimated_points = average_points(entries);
+            team.trend = series
+                .as_ref()
+                .map(|series| compute_trend(series, entries, &event_entries));
+        }
     }
 
+    match_info.predicted_time = target_match.predicted_time.unwrap_or(0);
+    match_info.schedule_age = Some(schedule_age);
+
+    Ok(match_info)
+}
+
+/// Fetches the scouting data of a single team and its standing at an event.
+///
+/// # Arguments
+///
+/// * `team_number` - The team number to fetch the data of.
+/// * `event` - The event to fetch the standing of the team at, if any.
+/// * `all_events` - Whether to include scouting data recorded at other events
+///   instead of only the data from `event`.
+///
+/// # Returns
+///
+/// A `Result` containing `TeamDetails` on success or `BlueScoutError` on
+/// failure.
+///
+/// # Errors
+///
+/// This function returns an error if there is an issue with the database
+/// connection. The standing of the team is left out if TBA can't be reached.
+///
+/// # Panics
+///
+/// Panics if the database connection is not initialized.
+pub async fn get_team_details(
+    team_number: TeamNumber,
+    event: Option<&str>,
+    all_events: bool,
+) -> Result<TeamDetails, BlueScoutError> {
     let db = DB.get().expect("Database not initialized");
     let conn = db.lock().await;
 
-    let mut stmt = conn
-        .prepare("SELECT * FROM scout_entries WHERE team_number = ?1 OR team_number = ?2 OR team_number = ?3 OR team_number = ?4 OR team_number = ?5 OR team_number = ?6")?;
-    let entry_iter = stmt.query_map(
-        [
-            &red_team[0],
-            &red_team[1],
-            &red_team[2],
-            &blue_team[0],
-            &blue_team[1],
-            &blue_team[2],
-        ],
-        DataPoint::map_datapoint,
-    )?;
-
-    let data_points = entry_iter.collect::<Result<Vec<DataPoint>, _>>()?;
+    let event_filter = event.filter(|_| !all_events);
 
+    let entries = {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM {} WHERE deleted_at IS NULL AND team_number = ?1 AND (?2 IS NULL OR event_key = ?2) ORDER BY event_key, match_number, id",
+            entry_columns(),
+            entries_table()
+        ))?;
+        stmt.query_map(duckdb::params![team_number, event_filter], ScoutEntry::map_entry)?
+            .collect::<Result<Vec<ScoutEntry>, _>>()?
+    };
     drop(conn);
 
-    let err_map = |_| anyhow::anyhow!("Team number shouldn't be larger than 32 bits");
+    let event_record = match event {
+        Some(event) => get_team_event_record(team_number, event).await,
+        None => None,
+    };
 
-    let mut team_data: HashMap<u32, Vec<DataPoint>> = [
-        (u32::try_from(red_team[0]).map_err(err_map)?, Vec::new()),
-        (u32::try_from(red_team[1]).map_err(err_map)?, Vec::new()),
-        (u32::try_from(red_team[2]).map_err(err_map)?, Vec::new()),
-        (u32::try_from(blue_team[0]).map_err(err_map)?, Vec::new()),
-        (u32::try_from(blue_team[1]).map_err(err_map)?, Vec::new()),
-        (u32::try_from(blue_team[2]).map_err(err_map)?, Vec::new()),
-    ]
-    .into();
+    Ok(TeamDetails {
+        team_number,
+        team_name: team_name(team_number),
+        metrics: (!entries.is_empty()).then(|| team_metrics(&entries)),
+        entries,
+        event_record,
+    })
+}
 
-    for data in data_points {
-        team_data
-            .get_mut(&data.team_number)
-            .expect("Team number should have been inserted earlier")
-            .push(data);
-    }
+/// Compares the scouting data of teams side by side.
+///
+/// # Arguments
+///
+/// * `team_numbers` - The teams to compare, in display order.
+/// * `event` - The event to compare the teams at.
+/// * `all_events` - Whether to include scouting data recorded at other events
+///   instead of only the data from `event`.
+///
+/// # Returns
+///
+/// A `Result` containing `TeamComparison` on success or `BlueScoutError` on
+/// failure.
+///
+/// # Errors
+///
+/// This function returns an error if there is an issue with the database
+/// connection.
+pub async fn get_team_comparison(
+    team_numbers: &[TeamNumber],
+    event: Option<&str>,
+    all_events: bool,
+) -> Result<TeamComparison, BlueScoutError> {
+    let entries = get_data(event.filter(|_| !all_events))
+        .await
+        .map_err(BlueScoutError::database_error)?;
+
+    let team_entries = team_numbers
+        .iter()
+        .map(|&team_number| {
+            let mut team_entries = entries
+                .iter()
+                .filter(|entry| entry.data.team_number == team_number)
+                .cloned()
+                .collect::<Vec<_>>();
+            ScoutEntry::sort_by_match(&mut team_entries);
+            team_entries
+        })
+        .collect::<Vec<_>>();
 
-    let mut match_info = MatchInfo::empty();
+    let metrics = team_entries
+        .iter()
+        .map(|entries| (!entries.is_empty()).then(|| team_metrics(entries)))
+        .collect::<Vec<_>>();
 
-    for (team_number, data) in team_data {
-        let is_blue_team = blue_team.contains(&(team_number as usize));
-        let team_index = if is_blue_team {
-            blue_team
+    // Every scouted team has the same metrics, the ones of the first are the rows
+    let rows = metrics
+        .iter()
+        .flatten()
+        .next()
+        .map(|first| {
+            first
                 .iter()
-                .position(|&x| x == team_number as usize)
-                .expect("team number should have been inserted earlier")
-        } else {
-            red_team
+                .map(|metric| {
+                    let values = metrics
+                        .iter()
+                        .map(|team_metrics| {
+                            team_metrics
+                                .iter()
+                                .flatten()
+                                .find(|other| other.name == metric.name)
+                                .map_or(DataType::Null, |other| other.value.clone())
+                        })
+                        .collect::<Vec<_>>();
+                    ComparisonRow {
+                        name: metric.name.clone(),
+                        pretty_name: metric.pretty_name.clone(),
+                        best: best_values(&values, game().lower_is_better(&metric.name)),
+                        values,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let teams = team_numbers
+        .iter()
+        .zip(&team_entries)
+        .map(|(&team_number, entries)| ComparedTeam {
+            team_number,
+            team_name: team_name(team_number),
+            matches: entries.len(),
+            breakdowns: game().team_breakdowns(entries),
+            notes: entries
+                .iter()
+                .filter(|entry| !entry.data.notes.trim().is_empty())
+                .map(|entry| (entry.data.match_number, entry.data.notes.clone()))
+                .collect(),
+        })
+        .collect();
+
+    Ok(TeamComparison { teams, rows })
+}
+
+/// Finds the best numeric values of a row of a comparison.
+///
+/// # Returns
+///
+/// Whether every value is the best one. Nothing is the best when all the
+/// numeric values are the same.
+fn best_values(values: &[DataType], lower_is_better: bool) -> Vec<bool> {
+    let numbers = values.iter().map(DataType::as_f64).collect::<Vec<_>>();
+    let Some(best) = numbers
+        .iter()
+        .flatten()
+        .copied()
+        .reduce(|a, b| if lower_is_better { a.min(b) } else { a.max(b) })
+    else {
+        return vec![false; values.len()];
+    };
+
+    if numbers.iter().flatten().all(|number| number.total_cmp(&best).is_eq()) {
+        return vec![false; values.len()];
+    }
+    numbers
+        .iter()
+        .map(|number| number.is_some_and(|number| number.total_cmp(&best).is_eq()))
+        .collect()
+}
+
+/// Fetches the pick list of an event, with a summary of the scouting data of
+/// every team at the event.
+///
+/// # Arguments
+///
+/// * `event` - The event key.
+///
+/// # Returns
+///
+/// A `Result` containing the rows of the pick list, grouped by tier, on
+/// success or `BlueScoutError` on failure.
+///
+/// # Errors
+///
+/// This function returns an error if there is an issue with the database
+/// connection.
+pub async fn get_pick_list_rows(event: &str) -> Result<Vec<PickListRow>, BlueScoutError> {
+    let pick_list = get_pick_list(event).await?;
+    let (marks, _) = get_selection_state(event).await?;
+    let entries = get_data(Some(event))
+        .await
+        .map_err(BlueScoutError::database_error)?;
+
+    Ok(pick_list
+        .into_iter()
+        .map(|entry| {
+            let team_entries = entries
                 .iter()
-                .position(|&x| x == team_number as usize)
-                .expect("team number should have been inserted earlier")
-        };
-        let team_name = TEAM_NAMES.get(&team_number).copied();
-        if data.is_empty() {
-            if is_blue_team {
-                match_info.blue[team_index] = TeamInfo {
-                    team_number,
-                    team_name: team_name.map(ToOwned::to_owned),
-                    team_data: None,
-                };
-            } else {
-                match_info.red[team_index] = TeamInfo {
-                    team_number,
-                    team_name: team_name.map(ToOwned::to_owned),
-                    team_data: None,
-                };
+                .filter(|other| other.data.team_number == entry.team_number)
+                .cloned()
+                .collect::<Vec<_>>();
+            PickListRow {
+                team_name: team_name(entry.team_number),
+                matches: team_entries.len(),
+                estimated_points: average_points(&team_entries),
+                metrics: (!team_entries.is_empty()).then(|| team_metrics(&team_entries)),
+                unavailable: is_unavailable(&marks, entry.team_number),
+                entry,
             }
-            continue;
-        }
+        })
+        .collect())
+}
 
-        if is_blue_team {
-            match_info.blue[team_index] = TeamInfo {
-                team_number,
-                team_name: team_name.map(ToOwned::to_owned),
-                team_data: Some(data),
-            };
-        } else {
-            match_info.red[team_index] = TeamInfo {
-                team_number,
-                team_name: team_name.map(ToOwned::to_owned),
-                team_data: Some(data),
-            };
-        }
+/// Fetches the playoff alliances of an event published by TBA.
+///
+/// # Returns
+///
+/// The alliances, by number, or none if they have not been published or TBA
+/// can't be reached.
+async fn get_alliances(event: &str) -> Vec<EliminationAlliance> {
+    // TBA has no alliances before the selection
+    cached_get::<Option<Vec<EliminationAlliance>>>(&format!(
+        "/event/{}/alliances",
+        urlencode(event)
+    ))
+    .await
+    .inspect_err(|err| tracing::warn!("Failed to fetch the alliances of {event}: {err}"))
+    .ok()
+    .and_then(|(alliances, _)| alliances)
+    .unwrap_or_default()
+}
+
+/// Fetches the picks and declines of the alliance selection of an event: the
+/// final alliances published by TBA, or the marks of the scouts until then.
+///
+/// # Returns
+///
+/// The marks, in the order the teams were picked, and the number of alliances
+/// published by TBA, or `None` if they haven't been published.
+///
+/// # Errors
+///
+/// This function returns an error if there is an issue with the database
+/// connection.
+async fn get_selection_state(
+    event: &str,
+) -> Result<(Vec<SelectionMark>, Option<u8>), BlueScoutError> {
+    let alliances = get_alliances(event).await;
+
+    if alliances.is_empty() {
+        return Ok((get_selection_marks(event).await?, None));
     }
 
-    match_info.predicted_time = target_match.predicted_time.unwrap_or(0);
+    let marks = published_marks(&alliances);
+    let alliance_count = u8::try_from(alliances.len()).unwrap_or(u8::MAX);
+    Ok((marks, Some(alliance_count)))
+}
 
-    Ok(match_info)
+/// Fetches the ranked teams of an event and the state of its alliance
+/// selection.
+///
+/// # Arguments
+///
+/// * `event` - The event key.
+///
+/// # Returns
+///
+/// A `Result` containing `AllianceSelection` on success or `BlueScoutError` on
+/// failure.
+///
+/// # Errors
+///
+/// This function returns an error if the rankings can't be fetched from TBA
+/// or if there is an issue with the database connection.
+pub async fn get_alliance_selection(event: &str) -> Result<AllianceSelection, BlueScoutError> {
+    // TBA has no rankings before the first qualification match
+    let (rankings, _) =
+        cached_get::<Option<EventRanking>>(&format!("/event/{}/rankings", urlencode(event)))
+            .await
+            .map_err(BlueScoutError::api_error)?;
+    let rankings = rankings.unwrap_or_default();
+    let (marks, alliance_count) = get_selection_state(event).await?;
+
+    let mut teams = rankings
+        .rankings
+        .iter()
+        .filter_map(|ranking| {
+            let team_number = TeamNumber::from_key(&ranking.team_key)?;
+            Some(SelectionTeam {
+                rank: ranking.rank,
+                team_number,
+                team_name: team_name(team_number),
+                status: team_status(&marks, team_number),
+            })
+        })
+        .collect::<Vec<_>>();
+    teams.sort_by_key(|team| team.rank);
+
+    Ok(AllianceSelection {
+        teams,
+        alliances: group_alliances(&marks, alliance_count),
+        published: alliance_count.is_some(),
+    })
+}
+
+/// Fetches the standing of a team at an event from TBA.
+///
+/// # Returns
+///
+/// The standing, or `None` if the team is not at the event or TBA can't be
+/// reached.
+async fn get_team_event_record(team_number: TeamNumber, event: &str) -> Option<TeamEventRecord> {
+    // TBA has no status for a team that is not at the event
+    let (status, _) = cached_get::<Option<TeamEventStatus>>(&format!(
+        "/team/frc{team_number}/event/{}/status",
+        urlencode(event)
+    ))
+    .await
+    .inspect_err(|err| {
+        tracing::warn!("Failed to fetch the status of team {team_number} at {event}: {err}");
+    })
+    .ok()?;
+    let status = status?;
+
+    let ranking = status
+        .qual
+        .flatten()
+        .map(|qual| (qual.num_teams, qual.ranking));
+    let (num_teams, ranking) = ranking.unwrap_or_default();
+    let ranking = ranking.map(|ranking| *ranking);
+    Some(TeamEventRecord {
+        event_key: event.to_owned(),
+        rank: ranking.as_ref().and_then(|ranking| ranking.rank),
+        num_teams,
+        record: ranking
+            .and_then(|ranking| ranking.record.flatten())
+            .map(|record| (record.wins, record.losses, record.ties)),
+        status: status.overall_status_str.map(|status| strip_html(&status)),
+    })
+}
+
+/// Removes the HTML tags of the status strings of TBA, keeping their text.
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
 }
 
 /// Fetches the list of FRC events for the current year.
 ///
 /// # Returns
 ///
-/// A `Result` containing a vector of `Event` on success or `anyhow::Error` on
-/// failure.
+/// A `Result` containing a vector of `Event` and how old it is on success or
+/// `anyhow::Error` on failure.
 ///
 /// # Errors
 ///
-/// This function returns an error if the API request fails.
-pub async fn get_frc_events() -> Result<Vec<Event>, anyhow::Error> {
-    Ok(get_events_by_year(api_config(), chrono::Utc::now().year()).await?)
+/// This function returns an error if the API request fails and the events
+/// have never been cached.
+pub async fn get_frc_events() -> Result<(Vec<Event>, CacheAge), anyhow::Error> {
+    cached_get(&format!("/events/{}", chrono::Utc::now().year())).await
 }
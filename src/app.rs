@@ -3,10 +3,13 @@ use leptos::prelude::*;
 use leptos_meta::{provide_meta_context, MetaTags, Stylesheet, Title};
 use leptos_router::{
     components::{Route, Router, Routes},
-    StaticSegment,
+    ParamSegment, StaticSegment,
 };
 
-use crate::components::{FallbackPage, HomePage, SettingsPage, ViewDataPage};
+use crate::components::{
+    AllianceSelectionPage, ComparePage, FallbackPage, HomePage, PickListPage, SettingsPage,
+    TeamPage, ViewDataPage,
+};
 
 #[must_use]
 pub fn shell(options: LeptosOptions) -> impl IntoView {
@@ -48,6 +51,10 @@ pub fn App() -> impl IntoView {
                     <Route path=StaticSegment("/") view=HomePage />
                     <Route path=StaticSegment("/view-data") view=ViewDataPage />
                     <Route path=StaticSegment("/settings") view=SettingsPage />
+                    <Route path=(StaticSegment("/team"), ParamSegment("number")) view=TeamPage />
+                    <Route path=StaticSegment("/compare") view=ComparePage />
+                    <Route path=StaticSegment("/pick-list") view=PickListPage />
+                    <Route path=StaticSegment("/alliance-selection") view=AllianceSelectionPage />
                 </Routes>
             </main>
         </Router>
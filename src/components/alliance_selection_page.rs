@@ -0,0 +1,386 @@
+//! Provide the `AllianceSelectionPage` component to track the alliance
+//! selection of an event as it happens.
+
+#![allow(clippy::needless_return, clippy::missing_docs_in_private_items)]
+
+#[cfg(feature = "ssr")]
+#[allow(unused_imports)]
+use crate::api_config;
+
+use core::time::Duration;
+
+use leptos::{prelude::*, server_fn::codec::Json, task::spawn_local};
+use web_sys::window;
+
+use crate::{
+    alliance_selection::{AllianceSelection, SelectionStatus, SelectionTeam, MAX_ALLIANCES},
+    components::{pick_list_page::fetch_pick_list, PageWrapper},
+    team::TeamNumber,
+    BlueScoutError,
+};
+
+/// How often the selection is fetched again, to show the marks of the other
+/// scouts.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Fetches the ranked teams of an event and the state of its alliance
+/// selection.
+///
+/// # Arguments
+///
+/// * `event` - The event to fetch the alliance selection of.
+///
+/// # Returns
+///
+/// A `Result` containing `AllianceSelection` or a `BlueScoutError`.
+#[server(endpoint = "fetch_alliance_selection")]
+pub async fn fetch_alliance_selection(event: String) -> Result<AllianceSelection, BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::api::get_alliance_selection;
+        get_alliance_selection(&event).await
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Marks what happened to a team during the alliance selection of an event.
+///
+/// # Arguments
+///
+/// * `event` - The event of the alliance selection.
+/// * `team_number` - The team to mark.
+/// * `status` - What happened to the team, or `None` to clear its mark.
+///
+/// # Returns
+///
+/// A `Result` indicating success or a `BlueScoutError`.
+// The status is an enum with data, which the default URL encoding can't carry
+#[server(endpoint = "mark_selection_team", input = Json)]
+pub async fn mark_selection_team(
+    event: String,
+    team_number: TeamNumber,
+    status: Option<SelectionStatus>,
+) -> Result<(), BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        if event.is_empty() {
+            return Err(BlueScoutError::validation(
+                "event",
+                "The event needs to be set in settings",
+            ));
+        }
+        crate::db::mark_selection(&event, team_number, status)
+            .await
+            .map_err(BlueScoutError::from)
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Describes what happened to a team during alliance selection.
+fn status_text(status: Option<SelectionStatus>) -> String {
+    match status {
+        Some(SelectionStatus::Picked(alliance)) => format!("Alliance {alliance}"),
+        Some(SelectionStatus::Declined) => "Declined".to_owned(),
+        None => "Available".to_owned(),
+    }
+}
+
+/// Renders the teams of every alliance, captain first.
+fn alliances_view(selection: &AllianceSelection) -> impl IntoView {
+    selection
+        .alliances
+        .iter()
+        .enumerate()
+        .map(|(index, teams)| {
+            let teams = if teams.is_empty() {
+                "-".to_owned()
+            } else {
+                teams
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            view! {
+                <p>
+                    <span class="font-bold">{format!("Alliance {}: ", index + 1)}</span>
+                    {teams}
+                </p>
+            }
+        })
+        .collect_view()
+}
+
+/// Component to track the alliance selection of the event selected in the
+/// settings: the ranked teams, the picks and declines marked by the scouts and
+/// our pick list without the teams that can't be picked anymore.
+///
+/// The selection is fetched again every few seconds, and replaced by the final
+/// alliances once TBA publishes them.
+#[component]
+pub fn AllianceSelectionPage() -> impl IntoView {
+    let (current_event, set_current_event) = signal(None::<String>);
+
+    // Initialize the event from localStorage on component mount
+    Effect::new(move |_| {
+        if let Some(window) = window()
+            && let Ok(Some(storage)) = window.local_storage()
+        {
+            set_current_event(Some(
+                storage.get_item("currentEvent").ok().flatten().unwrap_or_default(),
+            ));
+        }
+    });
+
+    let selection = Resource::new(
+        move || current_event.get(),
+        |event| async move {
+            match event {
+                Some(event) if !event.is_empty() => fetch_alliance_selection(event).await.map(Some),
+                _ => Ok(None),
+            }
+        },
+    );
+    let pick_list = Resource::new(
+        move || current_event.get(),
+        |event| async move {
+            match event {
+                Some(event) if !event.is_empty() => fetch_pick_list(event).await,
+                _ => Ok(Vec::new()),
+            }
+        },
+    );
+
+    // Show the marks of the other scouts
+    Effect::new(move |_| {
+        if let Ok(handle) = set_interval_with_handle(
+            move || {
+                selection.refetch();
+                pick_list.refetch();
+            },
+            REFRESH_INTERVAL,
+        ) {
+            on_cleanup(move || handle.clear());
+        }
+    });
+
+    let (error, set_error) = signal(None::<String>);
+
+    let mark = move |team_number: TeamNumber, status: Option<SelectionStatus>| {
+        let event = current_event.get_untracked().unwrap_or_default();
+        spawn_local(async move {
+            match mark_selection_team(event, team_number, status).await {
+                Ok(()) => set_error(None),
+                Err(err) => set_error(Some(err.to_string())),
+            }
+            selection.refetch();
+            pick_list.refetch();
+        });
+    };
+
+    // Picks can be marked for the alliances formed so far and the next one
+    let team_row = move |team: SelectionTeam, published: bool, alliance_count: u8| {
+        let team_number = team.team_number;
+        let label = team.team_name.as_ref().map_or_else(
+            || team_number.to_string(),
+            |team_name| format!("{team_number} \"{team_name}\""),
+        );
+        let picked_by = match team.status {
+            Some(SelectionStatus::Picked(alliance)) => Some(alliance),
+            _ => None,
+        };
+        let controls = (!published).then(|| {
+            view! {
+                <td class="whitespace-nowrap">
+                    <select
+                        class="select select-bordered select-sm"
+                        on:change=move |ev| {
+                            let status = event_target_value(&ev)
+                                .parse()
+                                .ok()
+                                .map(SelectionStatus::Picked);
+                            mark(team_number, status);
+                        }
+                    >
+                        <option value="" selected=picked_by.is_none()>
+                            Alliance
+                        </option>
+                        {(1..=alliance_count)
+                            .map(|alliance| {
+                                view! {
+                                    <option value=alliance selected=picked_by == Some(alliance)>
+                                        {alliance}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                    <button
+                        class="btn btn-sm btn-ghost"
+                        on:click=move |_| mark(team_number, Some(SelectionStatus::Declined))
+                    >
+                        Declined
+                    </button>
+                    <button class="btn btn-sm btn-ghost" on:click=move |_| mark(team_number, None)>
+                        Clear
+                    </button>
+                </td>
+            }
+        });
+        view! {
+            <tr class="hover:bg-base-300" class:opacity-50=team.status.is_some()>
+                <td>{team.rank}</td>
+                <td>
+                    <a class="link" href=format!("/team/{team_number}")>
+                        {label}
+                    </a>
+                </td>
+                <td>{status_text(team.status)}</td>
+                {controls}
+            </tr>
+        }
+    };
+
+    let pick_list_view = move || match pick_list.get() {
+        Some(Ok(rows)) if rows.is_empty() => view! { <p>No teams in the pick list</p> }.into_any(),
+        Some(Ok(rows)) => {
+            view! {
+                <ol class="list-decimal list-inside">
+                    {rows
+                        .into_iter()
+                        .map(|row| {
+                            let unavailable = row.unavailable;
+                            view! {
+                                <li
+                                    class:opacity-50=unavailable
+                                    class:line-through=unavailable
+                                    class:font-bold=!unavailable
+                                >
+                                    {format!("{} ({})", row.entry.team_number, row.entry.tier)}
+                                </li>
+                            }
+                        })
+                        .collect_view()}
+                </ol>
+            }
+                .into_any()
+        }
+        Some(Err(_)) => view! { <p class="text-error">Error loading the pick list...</p> }.into_any(),
+        None => view! { <p>Loading...</p> }.into_any(),
+    };
+
+    let selection_view = move |selection: AllianceSelection| {
+        let published = selection.published;
+        let alliance_count = u8::try_from(selection.alliances.len())
+            .unwrap_or(MAX_ALLIANCES)
+            .saturating_add(1)
+            .min(MAX_ALLIANCES);
+        let notice = published.then(|| {
+            view! {
+                <div role="alert" class="alert alert-info mb-8">
+                    <span>The final alliances have been published by TBA</span>
+                </div>
+            }
+        });
+        view! {
+            {notice}
+            <div class="grid grid-cols-1 lg:grid-cols-3 gap-8">
+                <div class="card bg-base-200 shadow-xl lg:col-span-2">
+                    <div class="card-body p-8">
+                        <h2 class="text-xl font-bold mb-2">Rankings</h2>
+                        <div class="overflow-x-auto">
+                            <table class="table">
+                                <thead>
+                                    <tr>
+                                        <th>Rank</th>
+                                        <th>Team</th>
+                                        <th>Status</th>
+                                        {(!published).then(|| view! { <th></th> })}
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {selection
+                                        .teams
+                                        .iter()
+                                        .cloned()
+                                        .map(|team| team_row(team, published, alliance_count))
+                                        .collect_view()}
+                                </tbody>
+                            </table>
+                        </div>
+                    </div>
+                </div>
+                <div>
+                    <div class="card bg-base-200 shadow-xl mb-8">
+                        <div class="card-body p-8">
+                            <h2 class="text-xl font-bold mb-2">Alliances</h2>
+                            {alliances_view(&selection)}
+                        </div>
+                    </div>
+                    <div class="card bg-base-200 shadow-xl">
+                        <div class="card-body p-8">
+                            <h2 class="text-xl font-bold mb-2">Our Pick List</h2>
+                            <Suspense fallback=move || view! { <p>Loading...</p> }>
+                                {pick_list_view}
+                            </Suspense>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    };
+
+    view! {
+        <PageWrapper>
+            <div class="container mx-auto">
+                <h1 class="text-3xl font-bold text-center mb-8">Alliance Selection</h1>
+                {move || {
+                    error
+                        .get()
+                        .map(|error| {
+                            view! {
+                                <div role="alert" class="alert alert-error mb-8">
+                                    <span>{error}</span>
+                                </div>
+                            }
+                        })
+                }}
+                <Suspense fallback=move || {
+                    view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }
+                }>
+                    {move || match (current_event.get(), selection.get()) {
+                        (Some(event), _) if event.is_empty() => {
+                            view! {
+                                <h2 class="text-2xl text-center mb-16 text-error">
+                                    Event Name needs to be set in settings for this feature to work!
+                                </h2>
+                            }
+                                .into_any()
+                        }
+                        (_, Some(Ok(Some(selection)))) => selection_view(selection).into_any(),
+                        (_, Some(Err(_))) => {
+                            view! {
+                                <h2 class="text-2xl text-center mb-16 text-error">
+                                    Error loading the rankings...
+                                </h2>
+                            }
+                                .into_any()
+                        }
+                        _ => {
+                            view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }
+                                .into_any()
+                        }
+                    }}
+                </Suspense>
+            </div>
+        </PageWrapper>
+    }
+}
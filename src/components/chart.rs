@@ -0,0 +1,266 @@
+//! SVG charts of the trends of a team, rendered without a charting library.
+
+use leptos::prelude::*;
+
+use crate::trend::Trend;
+
+/// The space left of the plot, for the labels of the vertical axis.
+const PADDING_LEFT: f32 = 36.0;
+/// The space right of the plot.
+const PADDING_RIGHT: f32 = 8.0;
+/// The space above the plot.
+const PADDING_TOP: f32 = 8.0;
+/// The space below the plot, for the match numbers.
+const PADDING_BOTTOM: f32 = 24.0;
+
+/// The most match numbers labelled on the horizontal axis.
+const MAX_X_LABELS: usize = 12;
+
+/// Maps the matches and values of a trend to the coordinates of a chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scale {
+    /// The value at the top of the vertical axis.
+    max: f32,
+    /// The height of the plot, without the padding.
+    plot_height: f32,
+    /// The width of the slot of every match.
+    slot: f32,
+}
+
+impl Scale {
+    /// Fits a trend with at least one point in a chart of the given size.
+    fn new(trend: &Trend, width: f32, height: f32) -> Self {
+        // The vertical axis starts at zero and fits the values and the average
+        let max = trend
+            .points
+            .iter()
+            .map(|point| point.value)
+            .chain(trend.average)
+            .fold(0.0_f32, f32::max);
+
+        Self {
+            max: if max > 0.0 { max } else { 1.0 },
+            plot_height: height - PADDING_TOP - PADDING_BOTTOM,
+            slot: (width - PADDING_LEFT - PADDING_RIGHT) / trend.points.len() as f32,
+        }
+    }
+
+    /// Returns the horizontal position of the match at an index, in the
+    /// middle of its slot.
+    fn x(self, index: usize) -> f32 {
+        (index as f32).mul_add(self.slot, PADDING_LEFT + self.slot / 2.0)
+    }
+
+    /// Returns the vertical position of a value.
+    fn y(self, value: f32) -> f32 {
+        PADDING_TOP + self.plot_height * (1.0 - value / self.max)
+    }
+}
+
+/// Plots a trend against the match number, as a line or as bars, with a dashed
+/// line at the event average.
+///
+/// # Props
+///
+/// * `trend`: The trend to plot.
+/// * `bar`: Whether to draw bars instead of a line.
+/// * `width`: The width of the chart, in pixels.
+/// * `height`: The height of the chart, in pixels.
+#[component]
+pub fn TrendChart(
+    trend: Trend,
+    #[prop(optional)] bar: bool,
+    #[prop(default = 480)] width: u32,
+    #[prop(default = 200)] height: u32,
+) -> impl IntoView {
+    if trend.points.is_empty() {
+        return view! { <p class="text-sm opacity-75">{format!("No {} recorded", trend.pretty_name)}</p> }
+            .into_any();
+    }
+
+    let (width, height) = (width as f32, height as f32);
+    let scale = Scale::new(&trend, width, height);
+    let (max, slot) = (scale.max, scale.slot);
+    let x = move |index: usize| scale.x(index);
+    let y = move |value: f32| scale.y(value);
+
+    let values = if bar {
+        trend
+            .points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let bar_width = slot * 0.7;
+                view! {
+                    <rect
+                        class="fill-primary"
+                        x=x(index) - bar_width / 2.0
+                        y=y(point.value)
+                        width=bar_width
+                        height=y(0.0) - y(point.value)
+                    >
+                        <title>{format!("Match {}: {}", point.match_number, point.value)}</title>
+                    </rect>
+                }
+            })
+            .collect_view()
+            .into_any()
+    } else {
+        let line = trend
+            .points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| format!("{:.1},{:.1}", x(index), y(point.value)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let dots = trend
+            .points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                view! {
+                    <circle class="fill-primary" cx=x(index) cy=y(point.value) r="3">
+                        <title>{format!("Match {}: {}", point.match_number, point.value)}</title>
+                    </circle>
+                }
+            })
+            .collect_view();
+        view! {
+            <polyline class="stroke-primary" fill="none" stroke-width="2" points=line />
+            {dots}
+        }
+        .into_any()
+    };
+
+    let average = trend.average.map(|average| {
+        view! {
+            <line
+                class="stroke-secondary"
+                x1=PADDING_LEFT
+                x2=width - PADDING_RIGHT
+                y1=y(average)
+                y2=y(average)
+                stroke-width="1.5"
+                stroke-dasharray="4 4"
+            >
+                <title>{format!("Event average: {average:.1}")}</title>
+            </line>
+        }
+    });
+
+    let label_step = trend.points.len().div_ceil(MAX_X_LABELS);
+    let x_labels = trend
+        .points
+        .iter()
+        .enumerate()
+        .step_by(label_step)
+        .map(|(index, point)| {
+            view! {
+                <text x=x(index) y=height - 6.0 text-anchor="middle" font-size="10" fill="currentColor">
+                    {point.match_number}
+                </text>
+            }
+        })
+        .collect_view();
+    let y_labels = [0.0, max / 2.0, max]
+        .into_iter()
+        .map(|value| {
+            view! {
+                <text
+                    x=PADDING_LEFT - 4.0
+                    y=y(value) + 3.0
+                    text-anchor="end"
+                    font-size="10"
+                    fill="currentColor"
+                >
+                    {if max < 10.0 { format!("{value:.1}") } else { format!("{value:.0}") }}
+                </text>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <svg
+            xmlns="http://www.w3.org/2000/svg"
+            width=width
+            height=height
+            viewBox=format!("0 0 {width} {height}")
+            role="img"
+        >
+            <title>{format!("{} by match", trend.pretty_name)}</title>
+            <line
+                x1=PADDING_LEFT
+                x2=PADDING_LEFT
+                y1=PADDING_TOP
+                y2=y(0.0)
+                stroke="currentColor"
+                stroke-opacity="0.5"
+            />
+            <line
+                x1=PADDING_LEFT
+                x2=width - PADDING_RIGHT
+                y1=y(0.0)
+                y2=y(0.0)
+                stroke="currentColor"
+                stroke-opacity="0.5"
+            />
+            {y_labels}
+            {x_labels}
+            {values}
+            {average}
+        </svg>
+    }
+    .into_any()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scale, PADDING_BOTTOM, PADDING_LEFT, PADDING_RIGHT, PADDING_TOP};
+    use crate::trend::{Trend, TrendPoint};
+
+    /// The size of the charts of the tests.
+    const SIZE: (f32, f32) = (480.0, 200.0);
+
+    /// Returns a trend of the values of consecutive matches.
+    fn trend(values: &[f32], average: Option<f32>) -> Trend {
+        Trend {
+            pretty_name: "Points".to_owned(),
+            points: values
+                .iter()
+                .zip(1..)
+                .map(|(&value, match_number)| TrendPoint {
+                    match_number,
+                    value,
+                })
+                .collect(),
+            average,
+        }
+    }
+
+    #[test]
+    fn centers_a_single_point() {
+        let scale = Scale::new(&trend(&[5.0], None), SIZE.0, SIZE.1);
+
+        let plot_width = SIZE.0 - PADDING_LEFT - PADDING_RIGHT;
+        assert!((scale.x(0) - (PADDING_LEFT + plot_width / 2.0)).abs() < 1e-4);
+        assert!((scale.y(5.0) - PADDING_TOP).abs() < 1e-4);
+        assert!((scale.y(0.0) - (SIZE.1 - PADDING_BOTTOM)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn draws_zeros_at_the_bottom() {
+        let scale = Scale::new(&trend(&[0.0, 0.0, 0.0], Some(0.0)), SIZE.0, SIZE.1);
+
+        assert!(scale.max > 0.0);
+        assert!((scale.y(0.0) - (SIZE.1 - PADDING_BOTTOM)).abs() < 1e-4);
+        assert!(scale.x(2) < SIZE.0 - PADDING_RIGHT);
+    }
+
+    #[test]
+    fn fits_the_average_line() {
+        let scale = Scale::new(&trend(&[1.0, 2.0], Some(8.0)), SIZE.0, SIZE.1);
+
+        assert!((scale.y(8.0) - PADDING_TOP).abs() < 1e-4);
+        assert!(scale.y(2.0) > scale.y(8.0));
+    }
+}
@@ -0,0 +1,351 @@
+//! Provide the `ComparePage` component to compare the scouting data of teams
+//! side by side.
+
+#![allow(clippy::needless_return, clippy::missing_docs_in_private_items)]
+
+#[cfg(feature = "ssr")]
+#[allow(unused_imports)]
+use crate::api_config;
+
+use leptos::prelude::*;
+use leptos_router::{
+    hooks::{use_navigate, use_query_map},
+    NavigateOptions,
+};
+use web_sys::window;
+
+use crate::{components::PageWrapper, team::TeamNumber, BlueScoutError, TeamComparison};
+
+/// The fewest teams that can be compared.
+const MIN_COMPARED_TEAMS: usize = 2;
+
+/// The most teams that can be compared at once: the six robots of a match.
+const MAX_COMPARED_TEAMS: usize = 6;
+
+/// Fetches the scouting data of teams to compare them side by side.
+///
+/// # Arguments
+///
+/// * `team_numbers` - The teams to compare, in display order.
+/// * `event` - The event to compare the teams at.
+/// * `all_events` - Whether to include the scouting data of every event
+///   instead of only `event`.
+///
+/// # Returns
+///
+/// A `Result` containing `TeamComparison` or a `BlueScoutError`.
+#[server(endpoint = "fetch_team_comparison")]
+pub async fn fetch_team_comparison(
+    team_numbers: Vec<TeamNumber>,
+    event: Option<String>,
+    all_events: bool,
+) -> Result<TeamComparison, BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::api::get_team_comparison;
+
+        if !(MIN_COMPARED_TEAMS..=MAX_COMPARED_TEAMS).contains(&team_numbers.len()) {
+            return Err(BlueScoutError::validation(
+                "team_numbers",
+                format!("Compare between {MIN_COMPARED_TEAMS} and {MAX_COMPARED_TEAMS} teams"),
+            ));
+        }
+        get_team_comparison(&team_numbers, event.as_deref(), all_events).await
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Parses the team numbers separated by commas or spaces, skipping repeated
+/// ones.
+///
+/// # Returns
+///
+/// The team numbers, or `None` if one of them is not a team, like `254` or
+/// `254B`.
+fn parse_team_numbers(text: &str) -> Option<Vec<TeamNumber>> {
+    let mut team_numbers = Vec::new();
+    for team_number in text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|team_number| !team_number.is_empty())
+    {
+        let team_number = team_number.parse::<TeamNumber>().ok()?;
+        if !team_numbers.contains(&team_number) {
+            team_numbers.push(team_number);
+        }
+    }
+    Some(team_numbers)
+}
+
+/// Renders the teams side by side: a column per team and a row per metric,
+/// with the best values highlighted.
+fn comparison_view(comparison: &TeamComparison) -> impl IntoView {
+    let headers = comparison
+        .teams
+        .iter()
+        .map(|team| {
+            let name = team
+                .team_name
+                .clone()
+                .map(|team_name| view! { <div class="font-normal opacity-75">{team_name}</div> });
+            view! {
+                <th>
+                    <a class="link" href=format!("/team/{}", team.team_number)>
+                        {team.team_number.to_string()}
+                    </a>
+                    {name}
+                </th>
+            }
+        })
+        .collect_view();
+
+    let matches = comparison
+        .teams
+        .iter()
+        .map(|team| view! { <td>{team.matches}</td> })
+        .collect_view();
+
+    let metrics = comparison
+        .rows
+        .iter()
+        .map(|row| {
+            view! {
+                <tr class="hover:bg-base-300">
+                    <th>{row.pretty_name.clone()}</th>
+                    {row
+                        .values
+                        .iter()
+                        .zip(&row.best)
+                        .map(|(value, &best)| {
+                            view! {
+                                <td class:font-bold=best class:text-success=best>
+                                    {value.format_metric()}
+                                </td>
+                            }
+                        })
+                        .collect_view()}
+                </tr>
+            }
+        })
+        .collect_view();
+
+    // Every team has the same breakdowns, the ones of the first are the rows
+    let breakdowns = comparison
+        .teams
+        .first()
+        .map(|first| {
+            first
+                .breakdowns
+                .iter()
+                .enumerate()
+                .map(|(index, breakdown)| {
+                    view! {
+                        <tr class="hover:bg-base-300">
+                            <th>{breakdown.pretty_name.clone()}</th>
+                            {comparison
+                                .teams
+                                .iter()
+                                .map(|team| {
+                                    let shares = team
+                                        .breakdowns
+                                        .get(index)
+                                        .map(|breakdown| breakdown.shares.clone())
+                                        .unwrap_or_default();
+                                    if shares.is_empty() {
+                                        return view! { <td>N/A</td> }.into_any();
+                                    }
+                                    view! {
+                                        <td>
+                                            {shares
+                                                .into_iter()
+                                                .map(|(outcome, share)| {
+                                                    view! {
+                                                        <div>{format!("{outcome}: {:.0}%", share * 100.0)}</div>
+                                                    }
+                                                })
+                                                .collect_view()}
+                                        </td>
+                                    }
+                                        .into_any()
+                                })
+                                .collect_view()}
+                        </tr>
+                    }
+                })
+                .collect_view()
+        });
+
+    let notes = comparison
+        .teams
+        .iter()
+        .map(|team| {
+            if team.notes.is_empty() {
+                return view! { <td>No notes</td> }.into_any();
+            }
+            view! {
+                <td class="align-top">
+                    <ul>
+                        {team
+                            .notes
+                            .iter()
+                            .map(|(match_number, notes)| {
+                                view! {
+                                    <li class="mb-2">
+                                        <span class="font-bold">
+                                            {format!("Match {match_number}: ")}
+                                        </span>
+                                        {notes.clone()}
+                                    </li>
+                                }
+                            })
+                            .collect_view()}
+                    </ul>
+                </td>
+            }
+                .into_any()
+        })
+        .collect_view();
+
+    view! {
+        <div class="card bg-base-200 shadow-xl">
+            <div class="card-body p-8">
+                <div class="overflow-x-auto">
+                    <table class="table">
+                        <thead>
+                            <tr>
+                                <th>Team</th>
+                                {headers}
+                            </tr>
+                        </thead>
+                        <tbody>
+                            <tr class="hover:bg-base-300">
+                                <th>Matches</th>
+                                {matches}
+                            </tr>
+                            {metrics}
+                            {breakdowns}
+                            <tr>
+                                <th class="align-top">Notes</th>
+                                {notes}
+                            </tr>
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Component to compare the teams in the `teams` query parameter, separated
+/// by commas.
+#[component]
+pub fn ComparePage() -> impl IntoView {
+    let query = use_query_map();
+    let teams_text = move || query.read().get("teams").unwrap_or_default();
+    let team_numbers = move || parse_team_numbers(&teams_text());
+
+    let (current_event, set_current_event) = signal(None::<String>);
+
+    let (all_events, set_all_events) = signal(false);
+
+    // Initialize the event from localStorage on component mount
+    Effect::new(move |_| {
+        if let Some(window) = window()
+            && let Ok(Some(storage)) = window.local_storage()
+            && let Ok(saved_event) = storage.get_item("currentEvent")
+        {
+            set_current_event(saved_event.filter(|event| !event.is_empty()));
+        }
+    });
+
+    let comparison = Resource::new(
+        move || (team_numbers(), current_event.get(), all_events.get()),
+        |(team_numbers, event, all_events)| async move {
+            match team_numbers {
+                Some(team_numbers)
+                    if (MIN_COMPARED_TEAMS..=MAX_COMPARED_TEAMS)
+                        .contains(&team_numbers.len()) =>
+                {
+                    fetch_team_comparison(team_numbers, event, all_events)
+                        .await
+                        .map(Some)
+                }
+                _ => Ok(None),
+            }
+        },
+    );
+
+    // The teams are kept in the URL, so a comparison can be shared
+    let navigate = use_navigate();
+    let set_teams = move |ev: leptos::ev::Event| {
+        let teams = event_target_value(&ev);
+        navigate(
+            &format!("/compare?teams={}", teams.trim()),
+            NavigateOptions::default(),
+        );
+    };
+
+    view! {
+        <PageWrapper>
+            <div class="container mx-auto">
+                <h1 class="text-3xl font-bold text-center mb-8">Compare Teams</h1>
+                <div class="flex flex-wrap items-center gap-4 mb-8">
+                    <input
+                        class="input input-bordered w-full max-w-md"
+                        type="text"
+                        id="teamsInput"
+                        name="teamsInput"
+                        placeholder="Team numbers, separated by commas"
+                        prop:value=teams_text
+                        on:change=set_teams
+                    />
+                    <label class="label cursor-pointer gap-2" for="allEventsCheckbox">
+                        <span class="label-text text-lg font-medium">Show All Events</span>
+                        <input
+                            class="checkbox checkbox-primary"
+                            type="checkbox"
+                            id="allEventsCheckbox"
+                            name="allEventsCheckbox"
+                            on:input=move |ev| {
+                                set_all_events(event_target_checked(&ev));
+                            }
+                        />
+                    </label>
+                </div>
+                <Suspense fallback=move || {
+                    view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }
+                }>
+                    {move || match comparison.get() {
+                        Some(Ok(Some(comparison))) => comparison_view(&comparison).into_any(),
+                        Some(Ok(None)) => {
+                            let message = if team_numbers().is_some() {
+                                format!(
+                                    "Enter {MIN_COMPARED_TEAMS} to {MAX_COMPARED_TEAMS} team numbers"
+                                )
+                            } else {
+                                "Invalid team number".to_owned()
+                            };
+                            view! { <h2 class="text-2xl text-center mb-16">{message}</h2> }
+                                .into_any()
+                        }
+                        Some(Err(_)) => {
+                            view! {
+                                <h2 class="text-2xl text-center mb-16 text-error">
+                                    Error loading team data...
+                                </h2>
+                            }
+                                .into_any()
+                        }
+                        None => {
+                            view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }
+                                .into_any()
+                        }
+                    }}
+                </Suspense>
+            </div>
+        </PageWrapper>
+    }
+}
@@ -0,0 +1,281 @@
+//! Renders the inputs of the scouting form from the season schema, so new
+//! fields show up without extra UI code.
+
+use leptos::{ev, prelude::*};
+
+use crate::{
+    data::{DataTypeName, Widget},
+    schema::{SchemaField, SeasonSchema},
+};
+
+/// Whether the field has to be filled in before submitting the form.
+fn is_required(field: &SchemaField) -> bool {
+    field.required || (!field.nullable && field.data_type != DataTypeName::String)
+}
+
+/// The values and labels of the options of an enum or `bool` field.
+fn field_options(field: &SchemaField) -> Vec<(String, String)> {
+    if field.data_type == DataTypeName::Bool {
+        // The values understood by `InsertDataArgs` for checkboxes
+        vec![("on".to_owned(), "Yes".to_owned()), ("off".to_owned(), "No".to_owned())]
+    } else {
+        field
+            .options
+            .iter()
+            .map(|option| (option.clone(), option.clone()))
+            .collect()
+    }
+}
+
+/// Prevents typing characters a number field can't hold.
+fn prevent_invalid_input(field: &SchemaField) -> impl Fn(ev::KeyboardEvent) + 'static {
+    let allow_negative = field.min.as_ref().is_some_and(|min| min.starts_with('-'));
+    let allow_decimal = field.data_type == DataTypeName::Float;
+    move |ev: ev::KeyboardEvent| {
+        if (ev.key() == "-" && !allow_negative) || (ev.key() == "." && !allow_decimal) {
+            ev.prevent_default();
+        }
+    }
+}
+
+/// Renders the inputs of every field of the season schema, grouped in their
+/// sections.
+///
+/// The inputs are named after the arguments of `insert_data`, so the component
+/// is meant to be placed in the `ActionForm` of `InsertData`. Fields with a
+/// `Widget::Hidden` widget are left to the page. Re-render the component to
+/// reset the counters.
+#[component]
+pub fn DataForm(schema: SeasonSchema) -> impl IntoView {
+    let fields = schema
+        .fields
+        .into_iter()
+        .filter(|field| field.widget != Widget::Hidden)
+        .collect::<Vec<_>>();
+
+    fields
+        .chunk_by(|a, b| a.section == b.section)
+        .map(|section| {
+            let heading = section.first().and_then(|field| field.section.clone()).map(|heading| {
+                view! { <h2 class="text-2xl font-bold mt-4 mb-6">{heading}</h2> }
+            });
+            let groups = section
+                .chunk_by(|a, b| a.group.is_some() && a.group == b.group)
+                .map(|group| match group.first().and_then(|field| field.group.clone()) {
+                    Some(label) => view! { <FieldGroup label=label fields=group.to_vec() /> }
+                        .into_any(),
+                    None => group
+                        .iter()
+                        .map(|field| view! { <FieldInput field=field.clone() /> })
+                        .collect_view()
+                        .into_any(),
+                })
+                .collect_view();
+            view! {
+                {heading}
+                {groups}
+            }
+        })
+        .collect_view()
+}
+
+/// A row of fields shown under a shared label.
+#[component]
+fn FieldGroup(label: String, fields: Vec<SchemaField>) -> impl IntoView {
+    let columns = match fields.len() {
+        1 => "grid grid-cols-1 gap-4 ml-4 mt-3",
+        2 => "grid grid-cols-2 gap-4 ml-4 mt-3",
+        3 => "grid grid-cols-3 gap-4 ml-4 mt-3",
+        4 => "grid grid-cols-4 gap-4 ml-4 mt-3",
+        _ => "grid grid-cols-5 gap-4 ml-4 mt-3",
+    };
+    view! {
+        <div class="form-control w-full mb-8">
+            <label class="label pb-2">
+                <span class="label-text text-lg font-medium">{label}</span>
+            </label>
+            <div class=columns>
+                {fields
+                    .into_iter()
+                    .map(|field| {
+                        if field.widget == Widget::Counter {
+                            view! { <CounterInput field=field /> }.into_any()
+                        } else {
+                            view! { <FieldInput field=field /> }.into_any()
+                        }
+                    })
+                    .collect_view()}
+            </div>
+        </div>
+    }
+}
+
+/// The input of a single field, with its label.
+#[component]
+fn FieldInput(field: SchemaField) -> impl IntoView {
+    let name = field.input_name();
+    let required = is_required(&field);
+    let placeholder = field.placeholder.clone();
+    let pretty_name = field.pretty_name.clone();
+
+    match field.widget {
+        Widget::Checkbox => view! {
+            <div class="form-control w-full mb-8">
+                <label class="label cursor-pointer py-2">
+                    <span class="label-text text-lg font-medium">{pretty_name}</span>
+                    <input type="checkbox" class="checkbox checkbox-primary" name=name />
+                </label>
+            </div>
+        }
+        .into_any(),
+        Widget::Counter => view! {
+            <div class="form-control w-full mb-8">
+                <div class="flex ml-4 mt-3">
+                    <CounterInput field=field />
+                </div>
+            </div>
+        }
+        .into_any(),
+        Widget::Radio => view! {
+            <div class="form-control w-full mb-8">
+                <label class="label pb-2">
+                    <span class="label-text text-lg font-medium">{pretty_name}</span>
+                </label>
+                <div class="ml-4 space-y-3 mt-3">
+                    {field_options(&field)
+                        .into_iter()
+                        .map(|(value, label)| {
+                            view! {
+                                <label class="label cursor-pointer justify-start gap-3 py-2 mr-5">
+                                    <input
+                                        type="radio"
+                                        name=name.clone()
+                                        value=value
+                                        class="radio radio-primary"
+                                        required=required
+                                    />
+                                    <span class="label-text">{label}</span>
+                                </label>
+                            }
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+        }
+        .into_any(),
+        Widget::Select => view! {
+            <div class="form-control w-full mb-8">
+                <label class="label pb-2">
+                    <span class="label-text text-lg font-medium">{pretty_name}</span>
+                </label>
+                <select class="select select-bordered w-full" name=name required=required>
+                    <option value="" selected>
+                        {placeholder.unwrap_or_default()}
+                    </option>
+                    {field_options(&field)
+                        .into_iter()
+                        .map(|(value, label)| view! { <option value=value>{label}</option> })
+                        .collect_view()}
+                </select>
+            </div>
+        }
+        .into_any(),
+        Widget::TextArea => view! {
+            <div class="form-control w-full mb-8">
+                <label class="label pb-2">
+                    <span class="label-text text-lg font-medium">{pretty_name}</span>
+                </label>
+                <textarea
+                    class="textarea textarea-bordered w-full h-32"
+                    placeholder=placeholder
+                    name=name
+                    maxlength=field.max
+                    required=required
+                ></textarea>
+            </div>
+        }
+        .into_any(),
+        Widget::Text => view! {
+            <div class="form-control w-full mb-8">
+                <label class="label pb-2">
+                    <span class="label-text text-lg font-medium">{pretty_name}</span>
+                </label>
+                <input
+                    class="input input-bordered w-full"
+                    type="text"
+                    placeholder=placeholder
+                    name=name
+                    maxlength=field.max
+                    required=required
+                />
+            </div>
+        }
+        .into_any(),
+        Widget::Number => view! {
+            <div class="form-control w-full mb-8">
+                <label class="label pb-2">
+                    <span class="label-text text-lg font-medium">{pretty_name}</span>
+                </label>
+                <input
+                    class="input input-bordered w-full"
+                    type="number"
+                    placeholder=placeholder
+                    name=name
+                    min=field.min.clone().unwrap_or_else(|| "0".to_owned())
+                    max=field.max.clone()
+                    required=required
+                    on:keydown=prevent_invalid_input(&field)
+                />
+            </div>
+        }
+        .into_any(),
+        // Set by the page
+        Widget::Hidden => ().into_any(),
+    }
+}
+
+/// A number with buttons to increment and decrement it, kept within the
+/// constraints of the field.
+#[component]
+fn CounterInput(field: SchemaField) -> impl IntoView {
+    let min = field
+        .min
+        .as_ref()
+        .and_then(|min| min.parse::<i64>().ok())
+        .unwrap_or(0);
+    let max = field.max.as_ref().and_then(|max| max.parse::<i64>().ok());
+    let clamp = move |value: i64| max.map_or(value, |max| value.min(max)).max(min);
+    let count = RwSignal::new(min);
+
+    view! {
+        <div class="flex flex-col items-center">
+            <span class="label-text mb-1 text-2xl">{field.pretty_name.clone()}</span>
+            <button
+                type="button"
+                class="btn btn-sm btn-soft mb-1 outline-none"
+                on:click=move |_| count.update(|x| *x = clamp(*x + 1))
+            >
+                +
+            </button>
+            <input
+                class="input input-bordered w-16 text-center py-1 text-xl transparent-num"
+                type="number"
+                min=field.min.clone().unwrap_or_else(|| "0".to_owned())
+                max=field.max.clone()
+                prop:value=move || count.get().to_string()
+                on:change=move |ev| {
+                    count.set(clamp(event_target_value(&ev).trim().parse().unwrap_or(min)));
+                }
+                on:keydown=prevent_invalid_input(&field)
+            />
+            <button
+                type="button"
+                class="btn btn-sm btn-soft mt-1 outline-none"
+                on:click=move |_| count.update(|x| *x = clamp(*x - 1))
+            >
+                -
+            </button>
+            <input type="hidden" name=field.input_name() prop:value=move || count.get().to_string() />
+        </div>
+    }
+}
@@ -1,24 +1,42 @@
 #![allow(unused_variables, clippy::missing_docs_in_private_items)]
+use std::collections::HashMap;
+
 use leptos::{ev, html, prelude::*};
 
-use crate::{components::PageWrapper, data::InsertDataArgs, BlueScoutError};
+use crate::{
+    components::{DataForm, PageWrapper},
+    data::InsertDataArgs,
+    schema::fetch_season_schema,
+    BlueScoutError,
+};
 
 /// Asynchronous server function to insert data.
 ///
+/// The data is checked against the constraints of the season schema before it
+/// is inserted.
+///
 /// # Arguments
 ///
 /// * `args` - The data to be inserted.
+/// * `extra` - The inputs of the fields added by the season schema.
 ///
 /// # Returns
 ///
 /// * `Result<(), BlueScoutError>` - Returns `Ok(())` if successful, otherwise
 ///   returns a `BlueScoutError`.
 #[server]
-pub async fn insert_data(args: InsertDataArgs) -> Result<(), BlueScoutError> {
+pub async fn insert_data(
+    args: InsertDataArgs,
+    #[server(default)] extra: HashMap<String, String>,
+) -> Result<(), BlueScoutError> {
     #[cfg(feature = "ssr")]
     return {
-        use crate::db::insert_form_data;
-        insert_form_data(args.map_insert_data_args())
+        use crate::{db::insert_form_data, schema::season_schema};
+        let schema = season_schema();
+        let data_point = args.map_insert_data_args()?;
+        let extra = schema.parse_extra(&extra)?;
+        schema.validate(&data_point, &extra)?;
+        insert_form_data(data_point, &extra)
             .await
             .map_err(BlueScoutError::database_error)?;
 
@@ -36,51 +54,31 @@ pub async fn insert_data(args: InsertDataArgs) -> Result<(), BlueScoutError> {
 pub fn HomePage() -> impl IntoView {
     let (loading, set_loading) = signal(false);
     let (error_message, set_error_message) = signal(None::<String>);
+    let (event_key, set_event_key) = signal(None::<String>);
 
-    let auto_coral = RwSignal::new(0_usize);
-    let auto_algae = RwSignal::new(0_usize);
-
-    let l1_coral_count = RwSignal::new(0_usize);
-    let l2_coral_count = RwSignal::new(0_usize);
-    let l3_coral_count = RwSignal::new(0_usize);
-    let l4_coral_count = RwSignal::new(0_usize);
-    let dropped_coral_count = RwSignal::new(0_usize);
-    let barge_algae_count = RwSignal::new(0_usize);
-    let floor_hole_algae_count = RwSignal::new(0_usize);
-
-    let increment_coral_closure = |x: &mut usize| {
-        if *x < 12 {
-            *x += 1;
-        }
-    };
-    let increment_closure = |x: &mut usize| {
-        *x += 1;
-    };
-    let decrement_closure = |x: &mut usize| {
-        if *x > 0 {
-            *x -= 1;
-        }
-    };
-
-    fn create_handle_input_change(value: RwSignal<usize>) -> impl Fn(ev::Event) {
-        move |ev: ev::Event| {
-            let input_string = event_target_value(&ev);
-            let new_value = input_string.parse::<usize>().unwrap_or(0);
-            value.set(new_value.max(0));
+    // Scouting entries are tagged with the event selected in the settings
+    Effect::new(move |_| {
+        if let Some(window) = web_sys::window()
+            && let Ok(Some(storage)) = window.local_storage()
+        {
+            set_event_key(Some(
+                storage
+                    .get_item("currentEvent")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default(),
+            ));
         }
-    }
+    });
 
+    // Bumped to re-render the form inputs, resetting the counters
+    let (form_generation, set_form_generation) = signal(0_u32);
     let reset_counters = move |_: ev::MouseEvent| {
-        l1_coral_count.set(0);
-        l2_coral_count.set(0);
-        l3_coral_count.set(0);
-        l4_coral_count.set(0);
-        dropped_coral_count.set(0);
-        floor_hole_algae_count.set(0);
-        barge_algae_count.set(0);
+        set_form_generation.update(|generation| *generation += 1);
     };
 
     let form_ref: NodeRef<html::Form> = NodeRef::new();
+    let schema = Resource::new(|| (), |()| fetch_season_schema());
 
     let on_submit = move |ev: ev::SubmitEvent| {
         #[cfg(feature = "hydrate")]
@@ -207,12 +205,6 @@ pub fn HomePage() -> impl IntoView {
         }
     };
 
-    let prevent_invalid_input = |ev: ev::KeyboardEvent| {
-        if ev.key() == "-" || ev.key() == "." {
-            ev.prevent_default();
-        }
-    };
-
     let insert_data = ServerAction::<InsertData>::new();
     view! {
         <PageWrapper>
@@ -222,444 +214,33 @@ pub fn HomePage() -> impl IntoView {
                 <div class="card bg-base-200 shadow-xl">
                     <div class="card-body p-8">
                         <ActionForm action=insert_data node_ref=form_ref on:submit=on_submit>
-                            <div class="form-control w-full mb-8">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">Name</span>
-                                </label>
-                                <input
-                                    class="input input-bordered w-full"
-                                    type="text"
-                                    placeholder="Enter your name"
-                                    name="args[name]"
-                                    required
-                                />
-                            </div>
-                            <div class="form-control w-full mb-8">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">Match Number</span>
-                                </label>
-                                <input
-                                    class="input input-bordered w-full"
-                                    type="number"
-                                    placeholder="Enter match number"
-                                    name="args[match_number]"
-                                    required
-                                    on:keydown=prevent_invalid_input
-                                />
-                            </div>
-
-                            <div class="form-control w-full mb-8">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">Team Number</span>
-                                </label>
-                                <input
-                                    class="input input-bordered w-full"
-                                    type="number"
-                                    placeholder="Enter team number"
-                                    name="args[team_number]"
-                                    required
-                                    on:keydown=prevent_invalid_input
-                                />
-                            </div>
-
-                            <div class="form-control w-full mb-8">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">
-                                        Auto Coral Number
-                                    </span>
-                                </label>
-                                <input
-                                    id="autoCoral"
-                                    class="input input-bordered w-full"
-                                    type="number"
-                                    placeholder="Enter auto coral number"
-                                    name="args[auto_coral]"
-                                    prop:value=move || auto_coral.get().to_string()
-                                    on:change=create_handle_input_change(auto_coral)
-                                    on:keydown=prevent_invalid_input
-                                />
-                            </div>
-
-                            <div class="form-control w-full mb-8">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">
-                                        Auto Algae Number
-                                    </span>
-                                </label>
-                                <input
-                                    id="autoAlgae"
-                                    class="input input-bordered w-full"
-                                    type="number"
-                                    placeholder="Enter auto algae number"
-                                    name="args[auto_algae]"
-                                    prop:value=move || auto_algae.get().to_string()
-                                    on:change=create_handle_input_change(auto_algae)
-                                    on:keydown=prevent_invalid_input
-                                />
-                            </div>
-
-                            <div class="form-control w-full mb-8">
-                                <label class="label cursor-pointer py-2">
-                                    <span class="label-text text-lg font-medium">Auto Leave</span>
-                                    <input
-                                        type="checkbox"
-                                        class="checkbox checkbox-primary"
-                                        name="args[auto_leave]"
-                                    />
-                                </label>
-                            </div>
-
-                            <div class="form-control w-full mb-8">
-                                <label class="label cursor-pointer py-2">
-                                    <span class="label-text text-lg font-medium">Algae Clear</span>
-                                    <input
-                                        type="checkbox"
-                                        class="checkbox checkbox-primary"
-                                        name="args[algae_clear]"
-                                    />
-                                </label>
-                            </div>
-
-                            <div class="form-control w-full mb-8">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">Teleop Coral</span>
-                                </label>
-                                <div class="grid grid-cols-5 gap-4 ml-4 mt-3">
-                                    <div class="flex flex-col items-center">
-                                        <span class="label-text mb-1 text-2xl">L1</span>
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mb-1 outline-none"
-                                            on:click=move |_| {
-                                                l1_coral_count.update(increment_closure);
-                                            }
-                                        >
-                                            +
-                                        </button>
-                                        <input
-                                            id="coralL1Count"
-                                            class="input input-bordered w-16 text-center py-1 text-xl transparent-num"
-                                            type="number"
-                                            min="0"
-                                            prop:value=move || l1_coral_count.get().to_string()
-                                            on:change=create_handle_input_change(l1_coral_count)
-                                            on:keydown=prevent_invalid_input
-                                        />
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mt-1 outline-none"
-                                            on:click=move |_| {
-                                                l1_coral_count.update(decrement_closure);
-                                            }
-                                        >
-                                            -
-                                        </button>
-                                        <input
-                                            type="hidden"
-                                            name="args[l1_coral]"
-                                            id="coralL1Input"
-                                            prop:value=move || l1_coral_count.get().to_string()
-                                        />
-                                    </div>
-                                    <div class="flex flex-col items-center">
-                                        <span class="label-text mb-1 text-2xl">L2</span>
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mb-1 outline-none"
-                                            on:click=move |_| {
-                                                l2_coral_count.update(increment_coral_closure);
-                                            }
-                                        >
-                                            +
-                                        </button>
-                                        <input
-                                            id="coralL2Count"
-                                            class="input input-bordered w-16 text-center py-1 text-xl transparent-num"
-                                            type="number"
-                                            prop:value=move || l2_coral_count.get().to_string()
-                                            min="0"
-                                            max="12"
-                                            on:change=create_handle_input_change(l2_coral_count)
-                                            on:keydown=prevent_invalid_input
-                                        />
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mt-1 outline-none"
-                                            on:click=move |_| {
-                                                l2_coral_count.update(decrement_closure);
-                                            }
-                                        >
-                                            -
-                                        </button>
-                                        <input
-                                            type="hidden"
-                                            name="args[l2_coral]"
-                                            id="coralL2Input"
-                                            prop:value=move || l2_coral_count.get().to_string()
-                                        />
-                                    </div>
-                                    <div class="flex flex-col items-center">
-                                        <span class="label-text mb-1 text-2xl">L3</span>
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mb-1 outline-none"
-                                            on:click=move |_| {
-                                                l3_coral_count.update(increment_coral_closure);
-                                            }
-                                        >
-                                            +
-                                        </button>
-                                        <input
-                                            id="coralL3Count"
-                                            class="input input-bordered w-16 text-center py-1 text-xl transparent-num"
-                                            type="number"
-                                            prop:value=move || l3_coral_count.get().to_string()
-                                            min="0"
-                                            max="12"
-                                            on:change=create_handle_input_change(l3_coral_count)
-                                            on:keydown=prevent_invalid_input
-                                        />
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mt-1 outline-none"
-                                            on:click=move |_| {
-                                                l3_coral_count.update(decrement_closure);
-                                            }
-                                        >
-                                            -
-                                        </button>
-                                        <input
-                                            type="hidden"
-                                            name="args[l3_coral]"
-                                            id="coralL3Input"
-                                            prop:value=move || l3_coral_count.get().to_string()
-                                        />
-                                    </div>
-                                    <div class="flex flex-col items-center">
-                                        <span class="label-text mb-1 text-2xl">L4</span>
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mb-1 outline-none"
-                                            on:click=move |_| {
-                                                l4_coral_count.update(increment_coral_closure);
-                                            }
-                                        >
-                                            +
-                                        </button>
-                                        <input
-                                            id="coralL4Count"
-                                            class="input input-bordered w-16 text-center py-1 text-xl transparent-num"
-                                            type="number"
-                                            prop:value=move || l4_coral_count.get().to_string()
-                                            min="0"
-                                            max="12"
-                                            on:change=create_handle_input_change(l4_coral_count)
-                                            on:keydown=prevent_invalid_input
-                                        />
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mt-1 outline-none"
-                                            on:click=move |_| {
-                                                l4_coral_count.update(decrement_closure);
-                                            }
-                                        >
-                                            -
-                                        </button>
-                                        <input
-                                            type="hidden"
-                                            name="args[l4_coral]"
-                                            id="coralL4Input"
-                                            prop:value=move || l4_coral_count.get().to_string()
-                                        />
-                                    </div>
-                                    <div class="flex flex-col items-center">
-                                        <span class="label-text mb-1 text-2xl">Dropped</span>
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mb-1 outline-none"
-                                            on:click=move |_| {
-                                                dropped_coral_count.update(increment_closure);
-                                            }
-                                        >
-                                            +
-                                        </button>
-                                        <input
-                                            id="coralDroppedCount"
-                                            class="input input-bordered w-16 text-center py-1 text-xl transparent-num"
-                                            type="number"
-                                            prop:value=move || dropped_coral_count.get().to_string()
-                                            min="0"
-                                            on:change=create_handle_input_change(dropped_coral_count)
-                                            on:keydown=prevent_invalid_input
-                                        />
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mt-1 outline-none"
-                                            on:click=move |_| {
-                                                dropped_coral_count.update(decrement_closure);
-                                            }
-                                        >
-                                            -
-                                        </button>
-                                        <input
-                                            type="hidden"
-                                            name="args[dropped_coral]"
-                                            id="coralDroppedInput"
-                                            prop:value=move || dropped_coral_count.get().to_string()
-                                        />
-                                    </div>
-                                </div>
-                            </div>
-
-                            <div class="form-control w-full mb-8">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">Teleop Algae</span>
-                                </label>
-                                <div class="grid grid-cols-2 gap-4 ml-4 mt-3">
-                                    <div class="flex flex-col items-center">
-                                        <span class="label-text mb-1 text-2xl">Floor Hole</span>
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mb-1 outline-none"
-                                            on:click=move |_| {
-                                                floor_hole_algae_count.update(increment_closure);
-                                            }
-                                        >
-                                            +
-                                        </button>
-                                        <input
-                                            id="algaeFloorHoleCount"
-                                            class="input input-bordered w-16 text-center py-1 text-xl transparent-num"
-                                            type="number"
-                                            min="0"
-                                            prop:value=move || floor_hole_algae_count.get().to_string()
-                                            on:change=create_handle_input_change(floor_hole_algae_count)
-                                            on:keydown=prevent_invalid_input
-                                        />
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mt-1 outline-none"
-                                            on:click=move |_| {
-                                                floor_hole_algae_count.update(decrement_closure);
-                                            }
-                                        >
-                                            -
-                                        </button>
-                                        <input
-                                            type="hidden"
-                                            name="args[algae_floor_hole]"
-                                            id="algaeFloorHoleInput"
-                                            prop:value=move || floor_hole_algae_count.get().to_string()
-                                        />
-                                    </div>
-                                    <div class="flex flex-col items-center">
-                                        <span class="label-text mb-1 text-2xl">Barge</span>
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mb-1 outline-none"
-                                            on:click=move |_| {
-                                                barge_algae_count.update(increment_closure);
-                                            }
-                                        >
-                                            +
-                                        </button>
-                                        <input
-                                            id="algaeBargeCount"
-                                            class="input input-bordered w-16 text-center py-1 text-xl transparent-num"
-                                            type="number"
-                                            prop:value=move || barge_algae_count.get().to_string()
-                                            min="0"
-                                            on:change=create_handle_input_change(barge_algae_count)
-                                            on:keydown=prevent_invalid_input
-                                        />
-                                        <button
-                                            type="button"
-                                            class="btn btn-sm btn-soft mt-1 outline-none"
-                                            on:click=move |_| {
-                                                barge_algae_count.update(decrement_closure);
-                                            }
-                                        >
-                                            -
-                                        </button>
-                                        <input
-                                            type="hidden"
-                                            name="args[algae_barge]"
-                                            id="algaeBargeInput"
-                                            prop:value=move || barge_algae_count.get().to_string()
-                                        />
-                                    </div>
-                                </div>
-                            </div>
-
-                            <div class="form-control w-full">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">Climb?</span>
-                                </label>
-                                <div class="ml-4 space-y-3 mt-3">
-                                    <label class="label cursor-pointer justify-start gap-3 py-2 mr-5">
-                                        <input
-                                            type="radio"
-                                            name="args[climb]"
-                                            value="Shallow"
-                                            class="radio radio-primary"
-                                            required
-                                        />
-                                        <span class="label-text">Shallow</span>
-                                    </label>
-                                    <label class="label cursor-pointer justify-start gap-3 py-2 mr-5">
-                                        <input
-                                            type="radio"
-                                            name="args[climb]"
-                                            value="Deep"
-                                            class="radio radio-primary"
-                                            required
-                                        />
-                                        <span class="label-text">Deep</span>
-                                    </label>
-                                    <label class="label cursor-pointer justify-start gap-3 py-2 mr-5">
-                                        <input
-                                            type="radio"
-                                            name="args[climb]"
-                                            value="Park"
-                                            class="radio radio-primary"
-                                            required
-                                        />
-                                        <span class="label-text">Park</span>
-                                    </label>
-                                    <label class="label cursor-pointer justify-start gap-3 py-2 mr-5">
-                                        <input
-                                            type="radio"
-                                            name="args[climb]"
-                                            value="Not Attempted"
-                                            class="radio radio-primary"
-                                            required
-                                        />
-                                        <span class="label-text">Not Attempted</span>
-                                    </label>
-                                </div>
-                            </div>
-
-                            <div class="form-control w-full mb-4">
-                                <label class="label cursor-pointer py-2">
-                                    <span class="label-text text-lg font-medium">Defense Bot</span>
-                                    <input
-                                        type="checkbox"
-                                        class="checkbox checkbox-primary"
-                                        name="args[defense_bot]"
-                                    />
-                                </label>
-                            </div>
-
-                            <div class="form-control w-full mb-8">
-                                <label class="label pb-2">
-                                    <span class="label-text text-lg font-medium">Notes</span>
-                                </label>
-                                <textarea
-                                    class="textarea textarea-bordered w-full h-32"
-                                    placeholder="Additional notes"
-                                    name="args[notes]"
-                                ></textarea>
-                            </div>
+                            <Show when=move || event_key.get().is_some_and(|x| x.is_empty())>
+                                <p class="text-error mb-8">
+                                    Event Name needs to be set in settings to tag this entry with an event!
+                                </p>
+                            </Show>
+                            <input
+                                type="hidden"
+                                name="args[event_key]"
+                                prop:value=move || event_key.get().unwrap_or_default()
+                            />
+                            <Suspense fallback=move || {
+                                view! { <span class="loading loading-spinner"></span> }
+                            }>
+                                {move || {
+                                    form_generation.track();
+                                    match schema.get() {
+                                        Some(Ok(schema)) => view! { <DataForm schema=schema /> }.into_any(),
+                                        Some(Err(err)) => view! {
+                                            <p class="text-error mb-8">
+                                                {format!("Failed to load the form: {err}")}
+                                            </p>
+                                        }
+                                        .into_any(),
+                                        None => ().into_any(),
+                                    }
+                                }}
+                            </Suspense>
 
                             // Display error messages if any
                             <Show when=move || error_message.get().is_some()>
@@ -1,17 +1,29 @@
 //! Defines the main components of the application.
 
 #![allow(clippy::must_use_candidate, clippy::exhaustive_structs)]
+mod alliance_selection_page;
+mod chart;
+mod compare_page;
+mod data_form;
 mod dock;
 mod fallback_page;
 mod home_page;
+mod pick_list_page;
 mod settings_page;
+mod team_page;
 mod viewdata_page;
+pub use alliance_selection_page::AllianceSelectionPage;
+pub use chart::TrendChart;
+pub use compare_page::ComparePage;
+pub use data_form::DataForm;
 pub use dock::Dock;
 pub use fallback_page::FallbackPage;
 pub use home_page::HomePage;
 use leptos::prelude::*;
 use leptos_meta::Script;
+pub use pick_list_page::PickListPage;
 pub use settings_page::SettingsPage;
+pub use team_page::TeamPage;
 pub use viewdata_page::ViewDataPage;
 
 /// Provides a consistent page layout with a navigation dock.
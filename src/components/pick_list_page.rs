@@ -0,0 +1,359 @@
+//! Provide the `PickListPage` component to build the pick list of an event.
+
+#![allow(clippy::needless_return, clippy::missing_docs_in_private_items)]
+
+#[cfg(feature = "ssr")]
+#[allow(unused_imports)]
+use crate::api_config;
+
+use leptos::{prelude::*, server_fn::codec::Json, task::spawn_local};
+use web_sys::window;
+
+use crate::{
+    components::PageWrapper,
+    pick_list::{PickListChange, PickTier, MAX_COMMENT_LENGTH},
+    team::TeamNumber,
+    BlueScoutError, PickListRow,
+};
+
+/// Fetches the pick list of an event, with a summary of the scouting data of
+/// its teams.
+///
+/// # Arguments
+///
+/// * `event` - The event to fetch the pick list of.
+///
+/// # Returns
+///
+/// A `Result` containing the `PickListRow`s, grouped by tier, or a
+/// `BlueScoutError`.
+#[server(endpoint = "fetch_pick_list")]
+pub async fn fetch_pick_list(event: String) -> Result<Vec<PickListRow>, BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::api::get_pick_list_rows;
+        get_pick_list_rows(&event).await
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Applies a change to the pick list of an event.
+///
+/// # Arguments
+///
+/// * `event` - The event of the pick list.
+/// * `change` - The change to apply.
+///
+/// # Returns
+///
+/// A `Result` indicating success or a `BlueScoutError`.
+// The change is an enum with data, which the default URL encoding can't carry
+#[server(endpoint = "change_pick_list", input = Json)]
+pub async fn change_pick_list(event: String, change: PickListChange) -> Result<(), BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        if event.is_empty() {
+            return Err(BlueScoutError::validation(
+                "event",
+                "The event needs to be set in settings",
+            ));
+        }
+        crate::db::change_pick_list(&event, change)
+            .await
+            .map_err(BlueScoutError::from)
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Renders the summary of the scouting data of a team in the pick list.
+fn summary_view(row: &PickListRow) -> impl IntoView {
+    let points = row
+        .estimated_points
+        .map_or_else(|| "N/A".to_owned(), |points| format!("{points:.1}"));
+    let metrics = row.metrics.as_ref().map(|metrics| {
+        view! {
+            <details>
+                <summary class="cursor-pointer">Stats</summary>
+                {metrics
+                    .iter()
+                    .map(|metric| {
+                        view! {
+                            <p>{format!("{}: {}", metric.pretty_name, metric.value.format_metric())}</p>
+                        }
+                    })
+                    .collect_view()}
+            </details>
+        }
+    });
+
+    view! {
+        <p>{format!("Matches: {} | Avg Points: {points}", row.matches)}</p>
+        {metrics}
+    }
+}
+
+/// Component to build the pick list of the event selected in the settings.
+///
+/// Every change is saved to the server as soon as it is made, and the list is
+/// fetched again with the latest scouting data of its teams.
+#[component]
+pub fn PickListPage() -> impl IntoView {
+    let (current_event, set_current_event) = signal(None::<String>);
+
+    // Initialize the event from localStorage on component mount
+    Effect::new(move |_| {
+        if let Some(window) = window()
+            && let Ok(Some(storage)) = window.local_storage()
+        {
+            set_current_event(Some(
+                storage.get_item("currentEvent").ok().flatten().unwrap_or_default(),
+            ));
+        }
+    });
+
+    let pick_list = Resource::new(
+        move || current_event.get(),
+        |event| async move {
+            match event {
+                Some(event) if !event.is_empty() => fetch_pick_list(event).await,
+                _ => Ok(Vec::new()),
+            }
+        },
+    );
+
+    let (error, set_error) = signal(None::<String>);
+    let (new_team, set_new_team) = signal(String::new());
+    let (new_tier, set_new_tier) = signal(PickTier::FirstPick);
+
+    let apply_change = move |change: PickListChange| {
+        let event = current_event.get_untracked().unwrap_or_default();
+        spawn_local(async move {
+            match change_pick_list(event, change).await {
+                Ok(()) => set_error(None),
+                Err(err) => set_error(Some(err.to_string())),
+            }
+            pick_list.refetch();
+        });
+    };
+
+    let add_team = move |_| match new_team.get_untracked().parse::<TeamNumber>() {
+        Ok(team_number) => {
+            set_new_team(String::new());
+            apply_change(PickListChange::Add(team_number, new_tier.get_untracked()));
+        }
+        Err(_) => set_error(Some("Enter a valid team number".to_owned())),
+    };
+
+    let tier_options = move |selected: PickTier| {
+        PickTier::ALL
+            .into_iter()
+            .map(|tier| {
+                view! {
+                    <option value=tier.name() selected=tier == selected>
+                        {tier.to_string()}
+                    </option>
+                }
+            })
+            .collect_view()
+    };
+
+    let row_view = move |(rank, row): (usize, PickListRow)| {
+        let team_number = row.entry.team_number;
+        let team = row.team_name.as_ref().map_or_else(
+            || team_number.to_string(),
+            |team_name| format!("{team_number} \"{team_name}\""),
+        );
+        view! {
+            <tr class="hover:bg-base-300" class:opacity-50=row.unavailable>
+                <td>{rank + 1}</td>
+                <td>
+                    <a class="link font-bold" href=format!("/team/{team_number}")>
+                        {team}
+                    </a>
+                    {row.unavailable.then(|| view! { <span class="badge badge-ghost ml-2">Unavailable</span> })}
+                    {summary_view(&row)}
+                </td>
+                <td>
+                    <textarea
+                        class="textarea textarea-bordered w-full"
+                        maxlength=MAX_COMMENT_LENGTH
+                        placeholder="Comment"
+                        prop:value=row.entry.comment.clone()
+                        on:change=move |ev| {
+                            apply_change(PickListChange::Comment(team_number, event_target_value(&ev)));
+                        }
+                    ></textarea>
+                </td>
+                <td>
+                    <select
+                        class="select select-bordered"
+                        on:change=move |ev| {
+                            if let Some(tier) = PickTier::from_name(&event_target_value(&ev)) {
+                                apply_change(PickListChange::SetTier(team_number, tier));
+                            }
+                        }
+                    >
+                        {tier_options(row.entry.tier)}
+                    </select>
+                </td>
+                <td class="whitespace-nowrap">
+                    <button
+                        class="btn btn-sm btn-ghost"
+                        title="Move up"
+                        on:click=move |_| apply_change(PickListChange::MoveUp(team_number))
+                    >
+                        "↑"
+                    </button>
+                    <button
+                        class="btn btn-sm btn-ghost"
+                        title="Move down"
+                        on:click=move |_| apply_change(PickListChange::MoveDown(team_number))
+                    >
+                        "↓"
+                    </button>
+                    <button
+                        class="btn btn-sm btn-ghost text-error"
+                        title="Remove"
+                        on:click=move |_| apply_change(PickListChange::Remove(team_number))
+                    >
+                        "✕"
+                    </button>
+                </td>
+            </tr>
+        }
+    };
+
+    let tiers_view = move |rows: Vec<PickListRow>| {
+        PickTier::ALL
+            .into_iter()
+            .map(|tier| {
+                let tier_rows = rows
+                    .iter()
+                    .filter(|row| row.entry.tier == tier)
+                    .cloned()
+                    .enumerate()
+                    .collect::<Vec<_>>();
+                let content = if tier_rows.is_empty() {
+                    view! { <p>No teams</p> }.into_any()
+                } else {
+                    view! {
+                        <div class="overflow-x-auto">
+                            <table class="table">
+                                <thead>
+                                    <tr>
+                                        <th>"#"</th>
+                                        <th>Team</th>
+                                        <th>Comment</th>
+                                        <th>Tier</th>
+                                        <th></th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {tier_rows.into_iter().map(row_view).collect_view()}
+                                </tbody>
+                            </table>
+                        </div>
+                    }
+                        .into_any()
+                };
+                view! {
+                    <div class="card bg-base-200 shadow-xl mb-8">
+                        <div class="card-body p-8">
+                            <h2 class="text-xl font-bold mb-2">{tier.to_string()}</h2>
+                            {content}
+                        </div>
+                    </div>
+                }
+            })
+            .collect_view()
+    };
+
+    view! {
+        <PageWrapper>
+            <div class="container mx-auto">
+                <h1 class="text-3xl font-bold text-center mb-8">Pick List</h1>
+                {move || {
+                    error
+                        .get()
+                        .map(|error| {
+                            view! {
+                                <div role="alert" class="alert alert-error mb-8">
+                                    <span>{error}</span>
+                                </div>
+                            }
+                        })
+                }}
+                {move || match current_event.get() {
+                    None => view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }.into_any(),
+                    Some(event) if event.is_empty() => {
+                        view! {
+                            <h2 class="text-2xl text-center mb-16 text-error">
+                                Event Name needs to be set in settings for this feature to work!
+                            </h2>
+                        }
+                            .into_any()
+                    }
+                    Some(_) => {
+                        view! {
+                            <div class="flex flex-wrap items-center gap-4 mb-8">
+                                <input
+                                    class="input input-bordered"
+                                    type="text"
+                                    id="newTeamInput"
+                                    name="newTeamInput"
+                                    placeholder="Team number, like 254 or 254B"
+                                    prop:value=new_team
+                                    on:input=move |ev| set_new_team(event_target_value(&ev))
+                                />
+                                <select
+                                    class="select select-bordered"
+                                    on:change=move |ev| {
+                                        if let Some(tier) = PickTier::from_name(&event_target_value(&ev)) {
+                                            set_new_tier(tier);
+                                        }
+                                    }
+                                >
+                                    {tier_options(new_tier.get_untracked())}
+                                </select>
+                                <button class="btn btn-primary" on:click=add_team>
+                                    Add Team
+                                </button>
+                                <button class="btn btn-outline" on:click=move |_| pick_list.refetch()>
+                                    Refresh
+                                </button>
+                            </div>
+                            <Suspense fallback=move || {
+                                view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }
+                            }>
+                                {move || match pick_list.get() {
+                                    Some(Ok(rows)) => tiers_view(rows).into_any(),
+                                    Some(Err(_)) => {
+                                        view! {
+                                            <h2 class="text-2xl text-center mb-16 text-error">
+                                                Error loading the pick list...
+                                            </h2>
+                                        }
+                                            .into_any()
+                                    }
+                                    None => {
+                                        view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }
+                                            .into_any()
+                                    }
+                                }}
+                            </Suspense>
+                        }
+                            .into_any()
+                    }
+                }}
+            </div>
+        </PageWrapper>
+    }
+}
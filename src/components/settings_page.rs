@@ -5,7 +5,7 @@
 use crate::api_config;
 
 use crate::components::PageWrapper;
-use crate::BlueScoutError;
+use crate::{BlueScoutError, CacheAge};
 use leptos::{ev, logging, prelude::*, task::spawn_local};
 use tbaapi::models::Event as TBAEvent;
 use web_sys::{window, Event};
@@ -14,10 +14,10 @@ use web_sys::{window, Event};
 ///
 /// # Returns
 ///
-/// A `Result` containing a vector of `TBAEvent` on success, or a
-/// `BlueScoutError` on failure.
+/// A `Result` containing a vector of `TBAEvent` and how old the list is on
+/// success, or a `BlueScoutError` on failure.
 #[server]
-pub async fn get_frc_events() -> Result<Vec<TBAEvent>, BlueScoutError> {
+pub async fn get_frc_events() -> Result<(Vec<TBAEvent>, CacheAge), BlueScoutError> {
     #[cfg(feature = "ssr")]
     return crate::api::get_frc_events()
         .await
@@ -35,14 +35,19 @@ pub fn SettingsPage() -> impl IntoView {
     // Create signals for theme and team number
     let (theme, set_theme) = signal("dark".to_owned());
     let (team_number, set_team_number) = signal(String::new());
+    let (scout_name, set_scout_name) = signal(String::new());
     let (event_name, set_event_name) = signal(String::new());
     let (events_list, set_events_list) = signal(Vec::<TBAEvent>::new());
+    let (events_age, set_events_age) = signal(None::<CacheAge>);
 
     Effect::new(move |_| {
         spawn_local(async move {
             let events = get_frc_events().await;
             match events {
-                Ok(events) => set_events_list(events),
+                Ok((events, age)) => {
+                    set_events_list(events);
+                    set_events_age(Some(age));
+                }
                 Err(err) => logging::error!("Failed to load events list: {}", err),
             }
         });
@@ -114,6 +119,11 @@ pub fn SettingsPage() -> impl IntoView {
                     set_team_number(saved_team_number);
                 }
 
+                // Get saved scout name
+                if let Ok(Some(saved_scout_name)) = storage.get_item("scoutName") {
+                    set_scout_name(saved_scout_name);
+                }
+
                 // Get saved event
                 if let Ok(Some(saved_event)) = storage.get_item("currentEventName") {
                     set_event_name(saved_event);
@@ -155,6 +165,19 @@ pub fn SettingsPage() -> impl IntoView {
         }
     };
 
+    // Handle scout name change
+    let on_scout_name_change = move |ev: Event| {
+        let new_value = event_target_value(&ev);
+        set_scout_name(new_value.clone());
+
+        // Save to localStorage
+        if let Some(window) = window() {
+            if let Ok(Some(storage)) = window.local_storage() {
+                let _ = storage.set_item("scoutName", new_value.trim());
+            }
+        }
+    };
+
     let on_event_change = move |ev: Event| {
         let new_value = event_target_value(&ev);
         set_event_name(new_value.clone());
@@ -204,15 +227,30 @@ pub fn SettingsPage() -> impl IntoView {
                                     <span class="label-text text-lg">Enter Team Number:</span>
                                 </label>
                                 <input
-                                    type="number"
+                                    type="text"
                                     class="input input-primary"
                                     id="teamNumberInput"
+                                    placeholder="Like 254 or 254B"
                                     prop:value=team_number
                                     on:change=on_team_number_change
                                     on:keydown=prevent_invalid_input
                                 />
                             </div>
                         </div>
+                        <div class="form-control">
+                            <div class="w-96 relative">
+                                <label class="label cursor-pointer">
+                                    <span class="label-text text-lg">Enter Your Name:</span>
+                                </label>
+                                <input
+                                    type="text"
+                                    class="input input-primary"
+                                    id="scoutNameInput"
+                                    prop:value=scout_name
+                                    on:change=on_scout_name_change
+                                />
+                            </div>
+                        </div>
                         <div class="form-control">
                             <div class="w-96 relative">
                                 <label class="label cursor-pointer">
@@ -275,6 +313,9 @@ pub fn SettingsPage() -> impl IntoView {
                                         }}
                                     </Suspense>
                                 </ul>
+                                <p class="text-sm opacity-70 mt-2">
+                                    {move || events_age.get().map(|age| age.describe())}
+                                </p>
                             </div>
                         </div>
                         <div class="form-control mt-6">
@@ -0,0 +1,381 @@
+//! Provide the `TeamPage` component to view the scouting data of a single
+//! team.
+
+#![allow(clippy::needless_return, clippy::missing_docs_in_private_items)]
+
+#[cfg(feature = "ssr")]
+#[allow(unused_imports)]
+use crate::api_config;
+
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+use web_sys::window;
+
+use crate::{
+    components::{PageWrapper, TrendChart},
+    data::DataPoint,
+    team::TeamNumber,
+    trend::{fetch_team_trend, fetch_trend_series},
+    BlueScoutError, TeamDetails, TeamEventRecord,
+};
+
+/// Fetches the scouting data of a team and its standing at an event.
+///
+/// # Arguments
+///
+/// * `team_number` - The team number to fetch the data of.
+/// * `event` - The event to fetch the standing of the team at, if any.
+/// * `all_events` - Whether to include the scouting data of every event
+///   instead of only `event`.
+///
+/// # Returns
+///
+/// A `Result` containing `TeamDetails` or a `BlueScoutError`.
+#[server(endpoint = "fetch_team_data")]
+pub async fn fetch_team_data(
+    team_number: TeamNumber,
+    event: Option<String>,
+    all_events: bool,
+) -> Result<TeamDetails, BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::api::get_team_details;
+        get_team_details(team_number, event.as_deref(), all_events).await
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Renders the standing of a team at an event.
+fn event_record_view(record: &TeamEventRecord) -> impl IntoView {
+    let rank = record.rank.map(|rank| {
+        let num_teams = record
+            .num_teams
+            .map_or_else(String::new, |num_teams| format!(" of {num_teams}"));
+        view! { <p>{format!("Rank: {rank}{num_teams}")}</p> }
+    });
+    let wins_losses_ties = record.record.map(|(wins, losses, ties)| {
+        view! { <p>{format!("Record: {wins}-{losses}-{ties}")}</p> }
+    });
+    let status = record.status.clone().map(|status| view! { <p>{status}</p> });
+
+    view! {
+        <h2 class="text-xl font-bold mb-2">{format!("At {}", record.event_key)}</h2>
+        {rank}
+        {wins_losses_ties}
+        {status}
+    }
+}
+
+/// Renders the scouting data of a team: its standing, metrics, matches and
+/// notes.
+fn team_details_view(details: &TeamDetails) -> impl IntoView {
+    let title = details.team_name.as_ref().map_or_else(
+        || format!("Team {}", details.team_number),
+        |team_name| format!("Team {} \"{team_name}\"", details.team_number),
+    );
+
+    let event_record = details.event_record.as_ref().map(|record| {
+        view! {
+            <div class="card bg-base-200 shadow-xl mb-8">
+                <div class="card-body p-8">{event_record_view(record)}</div>
+            </div>
+        }
+    });
+
+    let metrics = details.metrics.as_ref().map_or_else(
+        || view! { <p>No stats available</p> }.into_any(),
+        |metrics| {
+            metrics
+                .iter()
+                .map(|metric| {
+                    view! {
+                        <p>{format!("{}: {}", metric.pretty_name, metric.value.format_metric())}</p>
+                    }
+                })
+                .collect_view()
+                .into_any()
+        },
+    );
+
+    let matches = details
+        .entries
+        .iter()
+        .map(|entry| {
+            view! {
+                <tr class="hover:bg-base-300">
+                    <td>{entry.data.event_key.clone()}</td>
+                    {entry
+                        .data
+                        .get_reduced_columns()
+                        .into_iter()
+                        .map(|(_, value)| view! { <td>{value}</td> })
+                        .collect_view()}
+                </tr>
+            }
+        })
+        .collect_view();
+
+    let notes = details
+        .entries
+        .iter()
+        .filter(|entry| !entry.data.notes.trim().is_empty())
+        .map(|entry| {
+            view! {
+                <li class="mb-2">
+                    <span class="font-bold">
+                        {format!("Match {} ({}): ", entry.data.match_number, entry.data.name)}
+                    </span>
+                    {entry.data.notes.clone()}
+                </li>
+            }
+        })
+        .collect::<Vec<_>>();
+    let notes = if notes.is_empty() {
+        view! { <p>No notes</p> }.into_any()
+    } else {
+        view! { <ul>{notes}</ul> }.into_any()
+    };
+
+    view! {
+        <h1 class="text-3xl font-bold text-center mb-8">{title}</h1>
+        {event_record}
+        <div class="card bg-base-200 shadow-xl mb-8">
+            <div class="card-body p-8">
+                <h2 class="text-xl font-bold mb-2">Stats</h2>
+                <div class="grid grid-cols-1 sm:grid-cols-3 gap-x-8">{metrics}</div>
+            </div>
+        </div>
+        <div class="card bg-base-200 shadow-xl mb-8">
+            <div class="card-body p-8">
+                <h2 class="text-xl font-bold mb-2">Matches</h2>
+                <div class="overflow-x-auto">
+                    <table class="table">
+                        <thead>
+                            <tr>
+                                <th>Event</th>
+                                {DataPoint::reduced_column_names()
+                                    .iter()
+                                    .map(|&name| view! { <th>{name.to_owned()}</th> })
+                                    .collect_view()}
+                            </tr>
+                        </thead>
+                        <tbody>{matches}</tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+        <div class="card bg-base-200 shadow-xl mb-8">
+            <div class="card-body p-8">
+                <h2 class="text-xl font-bold mb-2">Notes</h2>
+                {notes}
+            </div>
+        </div>
+    }
+}
+
+/// Component to plot a series of a team against the match number, with a
+/// select for the series and a toggle between a line and bars.
+///
+/// # Props
+///
+/// * `team_number`: The team to plot, or `None` if the route is invalid.
+/// * `current_event`: The event to plot the matches of.
+/// * `all_events`: Whether to plot the matches of every event.
+#[component]
+fn TeamTrends(
+    team_number: Signal<Option<TeamNumber>>,
+    current_event: ReadSignal<Option<String>>,
+    all_events: ReadSignal<bool>,
+) -> impl IntoView {
+    let series = Resource::new(|| (), |()| fetch_trend_series());
+
+    let (selected_series, set_selected_series) = signal(None::<String>);
+    let (bar, set_bar) = signal(false);
+
+    // Default to the first series once they are loaded
+    Effect::new(move |_| {
+        if selected_series.read().is_none()
+            && let Some(Ok(series)) = series.get()
+            && let Some(first) = series.first()
+        {
+            set_selected_series(Some(first.name.clone()));
+        }
+    });
+
+    let trend = Resource::new(
+        move || {
+            (
+                team_number.get(),
+                current_event.get(),
+                all_events.get(),
+                selected_series.get(),
+            )
+        },
+        |(team_number, event, all_events, series)| async move {
+            match (team_number, series) {
+                (Some(team_number), Some(series)) => {
+                    fetch_team_trend(team_number, event, all_events, series)
+                        .await
+                        .map(Some)
+                }
+                _ => Ok(None),
+            }
+        },
+    );
+
+    view! {
+        <div class="card bg-base-200 shadow-xl mb-8">
+            <div class="card-body p-8">
+                <h2 class="text-xl font-bold mb-2">Trends</h2>
+                <div class="flex flex-wrap items-center gap-4 mb-4">
+                    <Suspense>
+                        <select
+                            class="select select-bordered"
+                            on:change=move |ev| set_selected_series(Some(event_target_value(&ev)))
+                        >
+                            {move || {
+                                series
+                                    .get()
+                                    .and_then(Result::ok)
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|series| {
+                                        let selected = selected_series.read().as_ref()
+                                            == Some(&series.name);
+                                        view! {
+                                            <option value=series.name selected=selected>
+                                                {series.pretty_name}
+                                            </option>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
+                        </select>
+                    </Suspense>
+                    <label class="label cursor-pointer gap-2" for="barChartCheckbox">
+                        <span class="label-text">Bars</span>
+                        <input
+                            class="checkbox checkbox-primary"
+                            type="checkbox"
+                            id="barChartCheckbox"
+                            name="barChartCheckbox"
+                            on:input=move |ev| set_bar(event_target_checked(&ev))
+                        />
+                    </label>
+                </div>
+                <Suspense fallback=move || view! { <p>Loading...</p> }>
+                    {move || match trend.get() {
+                        Some(Ok(Some(trend))) => {
+                            view! { <TrendChart trend=trend bar=bar.get() /> }.into_any()
+                        }
+                        Some(Ok(None)) | None => view! { <p>Loading...</p> }.into_any(),
+                        Some(Err(_)) => {
+                            view! { <p class="text-error">Error loading trend...</p> }.into_any()
+                        }
+                    }}
+                </Suspense>
+            </div>
+        </div>
+    }
+}
+
+/// Component to display the scouting data of the team in the `number` route
+/// parameter.
+#[component]
+pub fn TeamPage() -> impl IntoView {
+    let params = use_params_map();
+    let team_number = Signal::derive(move || {
+        params
+            .read()
+            .get("number")
+            .and_then(|number| number.parse::<TeamNumber>().ok())
+    });
+
+    let (current_event, set_current_event) = signal(None::<String>);
+
+    let (all_events, set_all_events) = signal(false);
+
+    // Initialize the event from localStorage on component mount
+    Effect::new(move |_| {
+        if let Some(window) = window()
+            && let Ok(Some(storage)) = window.local_storage()
+            && let Ok(saved_event) = storage.get_item("currentEvent")
+        {
+            set_current_event(saved_event.filter(|event| !event.is_empty()));
+        }
+    });
+
+    let details = Resource::new(
+        move || (team_number.get(), current_event.get(), all_events.get()),
+        |(team_number, event, all_events)| async move {
+            match team_number {
+                Some(team_number) => fetch_team_data(team_number, event, all_events)
+                    .await
+                    .map(Some),
+                None => Ok(None),
+            }
+        },
+    );
+
+    view! {
+        <PageWrapper>
+            <div class="container mx-auto">
+                <label class="label-text text-lg font-medium" for="allEventsCheckbox">
+                    Show All Events
+                </label>
+                <input
+                    class="checkbox checkbox-primary"
+                    type="checkbox"
+                    id="allEventsCheckbox"
+                    name="allEventsCheckbox"
+                    on:input=move |ev| {
+                        set_all_events(event_target_checked(&ev));
+                    }
+                />
+                <br />
+                <br />
+                <Suspense fallback=move || {
+                    view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }
+                }>
+                    {move || match details.get() {
+                        Some(Ok(Some(details))) => {
+                            view! {
+                                {team_details_view(&details)}
+                                <TeamTrends
+                                    team_number=team_number
+                                    current_event=current_event
+                                    all_events=all_events
+                                />
+                            }
+                                .into_any()
+                        }
+                        Some(Ok(None)) => {
+                            view! {
+                                <h2 class="text-2xl text-center mb-16 text-error">
+                                    Invalid team number
+                                </h2>
+                            }
+                                .into_any()
+                        }
+                        Some(Err(_)) => {
+                            view! {
+                                <h2 class="text-2xl text-center mb-16 text-error">
+                                    Error loading team data...
+                                </h2>
+                            }
+                                .into_any()
+                        }
+                        None => {
+                            view! { <h2 class="text-2xl text-center mb-16">Loading...</h2> }
+                                .into_any()
+                        }
+                    }}
+                </Suspense>
+            </div>
+        </PageWrapper>
+    }
+}
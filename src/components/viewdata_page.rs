@@ -8,13 +8,20 @@
 use crate::api_config;
 
 use chrono::{DateTime, Local};
-use leptos::{ev, prelude::*, task::spawn_local};
+use leptos::{ev, prelude::*, server_fn::codec::Json, task::spawn_local};
 use web_sys::{window, Event, HtmlInputElement};
 
-use crate::{components::PageWrapper, data::DataPoint, BlueScoutError, MatchInfo};
+use crate::{
+    components::{PageWrapper, TrendChart},
+    data::{AuditRecord, DataPoint, DataType, DataTypeName, ScoutEntry},
+    match_key::{MatchKey, MatchLevel},
+    schema::{fetch_season_schema, SeasonSchema},
+    team::TeamNumber,
+    BlueScoutError, MatchInfo,
+};
 
-/// Default match number to display when the page loads.
-const DEFAULT_MATCH: u32 = 1;
+/// Default match to display when the page loads.
+const DEFAULT_MATCH: MatchKey = MatchKey::qualification(1);
 
 /// Enables debug mode for the next match feature.
 const DEBUG_NEXT_MATCH: bool = true;
@@ -68,7 +75,8 @@ fn show_error(title: &str, text: &str) -> js_sys::Promise {
     unreachable!("This should be called on the client");
 }
 
-/// Fetches the next match number for a given team at a specific event.
+/// Fetches the next match of a given team at a specific event, qualification
+/// or playoff.
 ///
 /// # Arguments
 ///
@@ -77,27 +85,17 @@ fn show_error(title: &str, text: &str) -> js_sys::Promise {
 ///
 /// # Returns
 ///
-/// A `Result` containing an `Option` with the next match number or a
+/// A `Result` containing an `Option` with the key of the next match or a
 /// `BlueScoutError`.
 #[server]
 pub async fn next_team_match(
-    team_number: u32,
+    team_number: TeamNumber,
     event: String,
-) -> Result<Option<u32>, BlueScoutError> {
+) -> Result<Option<MatchKey>, BlueScoutError> {
     #[cfg(feature = "ssr")]
     return {
-        use tbaapi::apis::team_api::get_team_event_matches;
-        Ok(
-            get_team_event_matches(api_config(), &format!("frc{team_number}"), &event)
-                .await
-                .map_err(BlueScoutError::api_error)?
-                .iter()
-                .filter(|&x| x.actual_time.is_none() || DEBUG_NEXT_MATCH)
-                .map(|x| {
-                    u32::try_from(x.match_number).expect("Match number should not be negative")
-                })
-                .min(),
-        )
+        use crate::api::get_next_team_match;
+        get_next_team_match(team_number, &event, DEBUG_NEXT_MATCH).await
     };
     #[cfg(not(feature = "ssr"))]
     {
@@ -106,25 +104,29 @@ pub async fn next_team_match(
     }
 }
 
-/// Fetches match data for a given match number and event.
+/// Fetches match data for a given match and event.
 ///
 /// # Arguments
 ///
-/// * `match_number` - The match number to fetch data for.
+/// * `match_key` - The match to fetch data for, like `qm12`, `sf5m1` or a
+///   full TBA match key.
 /// * `event` - The event to fetch match data for.
+/// * `all_events` - Whether the team stats should include data from every
+///   event instead of only `event`.
 ///
 /// # Returns
 ///
 /// A `Result` containing `MatchInfo` or a `BlueScoutError`.
 #[server(endpoint = "fetch_match_data")]
 pub async fn fetch_match_data(
-    match_number: i32,
+    match_key: String,
     event: String,
+    all_events: bool,
 ) -> Result<MatchInfo, BlueScoutError> {
     #[cfg(feature = "ssr")]
     return {
         use crate::api::get_match_info;
-        get_match_info(match_number, &event).await
+        get_match_info(match_key.parse()?, &event, all_events).await
     };
     #[cfg(not(feature = "ssr"))]
     {
@@ -135,15 +137,24 @@ pub async fn fetch_match_data(
 
 /// Fetches scouting data from the database.
 ///
+/// # Arguments
+///
+/// * `event_key` - The event to fetch scouting data for, or `None` to fetch
+///   the scouting data of all events.
+///
 /// # Returns
 ///
-/// A `Result` containing a `Vec` of `DataPoint` or a `BlueScoutError`.
+/// A `Result` containing a `Vec` of `ScoutEntry` or a `BlueScoutError`.
 #[server(endpoint = "fetch_scouting_data")]
-pub async fn fetch_scouting_data() -> Result<Vec<DataPoint>, BlueScoutError> {
+pub async fn fetch_scouting_data(
+    event_key: Option<String>,
+) -> Result<Vec<ScoutEntry>, BlueScoutError> {
     #[cfg(feature = "ssr")]
     {
         use crate::db::get_data as get_db_data;
-        return get_db_data().await.map_err(BlueScoutError::database_error);
+        return get_db_data(event_key.as_deref())
+            .await
+            .map_err(BlueScoutError::database_error);
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -152,21 +163,130 @@ pub async fn fetch_scouting_data() -> Result<Vec<DataPoint>, BlueScoutError> {
     }
 }
 
-/// Displays a boolean value as a string.
+/// Replaces the data of a scouting entry in the database, including the
+/// values of the fields added by the season schema.
+///
+/// The new data is checked against the constraints of the season schema
+/// before it is written.
 ///
 /// # Arguments
 ///
-/// * `b` - The boolean value to display.
+/// * `entry` - The entry to update, with its new data.
+/// * `actor` - The name of the scout making the change.
 ///
 /// # Returns
 ///
-/// A `String` representing the boolean value.
-#[inline]
-fn display_bool(b: bool) -> String {
-    if b {
-        "Yes".to_owned()
-    } else {
-        "No".to_owned()
+/// A `Result` indicating success or a `BlueScoutError`.
+#[server(endpoint = "update_scouting_entry", input = Json)]
+pub async fn update_scouting_entry(
+    entry: ScoutEntry,
+    actor: String,
+) -> Result<(), BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::{db::update_entry, schema::season_schema};
+        let id = entry.id;
+        season_schema().validate(&entry.data, &entry.extra)?;
+        if update_entry(id, entry.into(), &actor)
+            .await
+            .map_err(BlueScoutError::database_error)?
+        {
+            Ok(())
+        } else {
+            Err(BlueScoutError::custom(format!("Entry {id} does not exist")))
+        }
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Deletes a scouting entry from the database. The entry can be restored from
+/// the audit log.
+///
+/// # Arguments
+///
+/// * `id` - The id of the entry to delete.
+/// * `actor` - The name of the scout making the change.
+///
+/// # Returns
+///
+/// A `Result` indicating success or a `BlueScoutError`.
+#[server(endpoint = "delete_scouting_entry")]
+pub async fn delete_scouting_entry(id: i32, actor: String) -> Result<(), BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::db::delete_entry;
+        if delete_entry(id, &actor)
+            .await
+            .map_err(BlueScoutError::database_error)?
+        {
+            Ok(())
+        } else {
+            Err(BlueScoutError::custom(format!("Entry {id} does not exist")))
+        }
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Fetches the changes made to scouting entries, most recent first.
+///
+/// # Arguments
+///
+/// * `event_key` - The event to fetch the changes of, or `None` to fetch the
+///   changes of all events.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `AuditRecord` or a `BlueScoutError`.
+#[server(endpoint = "fetch_audit_log")]
+pub async fn fetch_audit_log(
+    event_key: Option<String>,
+) -> Result<Vec<AuditRecord>, BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::db::get_audit_log;
+        get_audit_log(event_key.as_deref())
+            .await
+            .map_err(BlueScoutError::database_error)
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Undoes a change recorded in the audit log, restoring the entry as it was
+/// before the change.
+///
+/// # Arguments
+///
+/// * `audit_id` - The id of the change to undo.
+/// * `actor` - The name of the scout making the change.
+///
+/// # Returns
+///
+/// A `Result` indicating success or a `BlueScoutError`.
+#[server(endpoint = "restore_scouting_entry")]
+pub async fn restore_scouting_entry(audit_id: i32, actor: String) -> Result<(), BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::db::restore_entry;
+        restore_entry(audit_id, &actor)
+            .await
+            .map_err(BlueScoutError::custom)
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
     }
 }
 
@@ -182,9 +302,26 @@ macro_rules! team_data_view {
         move || match $current_match.get() {
             Some(Some(match_data)) => {
                 let team_data = &match_data.$team[$index];
-                team_data.team_data.as_ref().map_or_else(
+                team_data.metrics.as_ref().map_or_else(
                     || view! { <span class = "team-number"> No stats available </span> }.into_any(),
-                    |data| DataPoint::view_team_data(data),
+                    |metrics| {
+                        let trend = team_data
+                            .trend
+                            .clone()
+                            .map(|trend| view! { <TrendChart trend=trend width=240 height=100 /> });
+                        view! {
+                            {metrics
+                                .iter()
+                                .map(|metric| {
+                                    view! {
+                                        <p>{format!("{}: {}", metric.pretty_name, metric.value.format_metric())}</p>
+                                    }
+                                })
+                                .collect_view()}
+                            {trend}
+                        }
+                        .into_any()
+                    },
                 )
             }
             Some(None) => view! {
@@ -199,7 +336,35 @@ macro_rules! team_data_view {
     };
 }
 
-/// Macro to generate a view for team numbers.
+/// Macro to generate a view for the points an alliance is expected to score:
+/// the sum of the average points of its scouted teams.
+///
+/// # Arguments
+///
+/// * `$current_match` - The current match data.
+/// * `$team` - The team (red or blue).
+macro_rules! alliance_points_view {
+    ($current_match:expr, $team:ident) => {
+        move || match $current_match.get() {
+            Some(Some(match_data)) => {
+                let points = match_data
+                    .$team
+                    .iter()
+                    .filter_map(|team| team.estimated_points)
+                    .reduce(|a, b| a + b);
+                format!(
+                    "Expected Points: {}",
+                    points.map_or_else(|| "N/A".to_owned(), |points| format!("{points:.1}"))
+                )
+            }
+            Some(None) => "Error loading stats...".to_owned(),
+            None => "Loading stats...".to_owned(),
+        }
+    };
+}
+
+/// Macro to generate a view for team numbers, with badges marking surrogate
+/// and disqualified teams.
 ///
 /// # Arguments
 ///
@@ -208,34 +373,66 @@ macro_rules! team_data_view {
 /// * `$index` - The index of the team data.
 macro_rules! team_number_view {
     ($current_match:expr, $team:ident, $index:expr) => {
-        move || {
-            let content = match $current_match.get() {
-                Some(Some(match_data)) => {
-                    let team_name = match_data.$team[$index]
-                        .team_name
-                        .as_ref()
-                        .map(String::as_str);
-                    format!(
-                        "{}{}",
-                        match_data.$team[$index].team_number,
-                        if let Some(team_name) = team_name {
-                            format!("  \"{}\"", team_name)
-                        } else {
-                            String::new()
-                        }
-                    )
+        move || match $current_match.get() {
+            Some(Some(match_data)) => {
+                let team = &match_data.$team[$index];
+                let content = team.team_name.as_ref().map_or_else(
+                    || team.team_number.to_string(),
+                    |team_name| format!("{}  \"{team_name}\"", team.team_number),
+                );
+                view! {
+                    <a class="team-number link" href=team_link(team.team_number)>
+                        {content}
+                    </a>
+                    {team.surrogate.then(|| view! { <span class="badge badge-warning ml-2">Surrogate</span> })}
+                    {team.disqualified.then(|| view! { <span class="badge badge-error ml-2">DQ</span> })}
                 }
-                Some(None) => "Error loading data...".to_owned(),
-                None => "Loading data...".to_owned(),
-            };
-            view! {
-                <span class="team-number">{content}</span>
+                .into_any()
             }
-            .into_any()
+            Some(None) => view! { <span class="team-number">Error loading data...</span> }.into_any(),
+            None => view! { <span class="team-number">Loading data...</span> }.into_any(),
         }
     };
 }
 
+/// Returns the URL of the page comparing the teams of a match, or of the
+/// empty comparison page if the match is not loaded.
+fn compare_link(match_info: Option<MatchInfo>) -> String {
+    let teams = match_info.map_or_else(String::new, |match_info| {
+        match_info
+            .red
+            .iter()
+            .chain(&match_info.blue)
+            .map(|team| team.team_number.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    format!("/compare?teams={teams}")
+}
+
+/// Returns the URL of the page of a team.
+fn team_link(team_number: impl core::fmt::Display) -> String {
+    format!("/team/{team_number}")
+}
+
+/// Renders a cell of the data table, linking it to the page of the team if it
+/// holds a team number.
+fn team_cell(is_team_number: bool, value: String) -> AnyView {
+    if is_team_number {
+        let href = team_link(&value);
+        view! {
+            <td>
+                <a class="link" href=href>
+                    {value}
+                </a>
+            </td>
+        }
+        .into_any()
+    } else {
+        view! { <td>{value}</td> }.into_any()
+    }
+}
+
 /// Formats a timestamp into a human-readable string.
 ///
 /// # Arguments
@@ -261,7 +458,20 @@ pub fn ViewDataPage() -> impl IntoView {
 
     let (team_number, set_team_number) = signal(None::<String>);
 
-    let (current_match_num, set_current_match_num) = signal(DEFAULT_MATCH);
+    let (current_match_key, set_current_match_key) = signal(DEFAULT_MATCH);
+
+    let (all_events, set_all_events) = signal(false);
+
+    let (scout_name, set_scout_name) = signal(String::new());
+
+    // The event to filter the scouting data by, `None` when showing every event
+    let event_filter = move || {
+        if all_events.get() {
+            None
+        } else {
+            current_event.get().filter(|x| !x.is_empty())
+        }
+    };
 
     // Initialize values from localStorage on component mount
     Effect::new(move |_| {
@@ -278,32 +488,59 @@ pub fn ViewDataPage() -> impl IntoView {
                 if let Ok(saved_event) = storage.get_item("currentEvent") {
                     set_current_event(Some(saved_event.unwrap_or_default()));
                 }
+
+                // Get saved scout name, recorded as the author of changes
+                if let Ok(Some(saved_scout_name)) = storage.get_item("scoutName") {
+                    set_scout_name(saved_scout_name);
+                }
             }
         }
     });
 
+    // The fields shown with full names, described by the season schema
+    let schema = Resource::new(|| (), |()| fetch_season_schema());
+    let season = move || {
+        schema
+            .get()
+            .and_then(Result::ok)
+            .unwrap_or_else(SeasonSchema::compiled)
+    };
+
     let column_names = move || {
-        if use_full_names() {
-            DataPoint::field_pretty_names()
-                .iter()
-                .map(|&(_name, pretty_name)| view! { <th>{pretty_name.to_owned()}</th> })
+        let columns = if use_full_names() {
+            season()
+                .fields
+                .into_iter()
+                .map(|field| view! { <th>{field.pretty_name}</th> })
                 .collect_view()
         } else {
             DataPoint::reduced_column_names()
                 .iter()
                 .map(|&name| view! { <th>{name.to_owned()}</th> })
                 .collect_view()
+        };
+        view! {
+            {columns}
+            <th>Actions</th>
         }
     };
 
-    let data = Resource::new(move || use_full_names.get(), move |_| fetch_scouting_data());
+    // The entry being edited in the edit dialog
+    let editing = RwSignal::new(None::<ScoutEntry>);
+    let editing_id = Memo::new(move |_| editing.with(|entry| entry.as_ref().map(|entry| entry.id)));
+
+    let data = Resource::new(
+        move || (use_full_names.get(), event_filter()),
+        move |(_, event_key)| fetch_scouting_data(event_key),
+    );
+    let audit_log = Resource::new(event_filter, fetch_audit_log);
     let current_match = Resource::new(
-        move || (current_event.get(), current_match_num.get()),
-        move |(current_event, current_match_num)| async move {
+        move || (current_event.get(), current_match_key.get(), all_events.get()),
+        move |(current_event, current_match_key, all_events)| async move {
             fetch_match_data(
-                i32::try_from(current_match_num)
-                    .expect("Current match number should fit into a 32 bit signed integer"),
+                current_match_key.to_string(),
                 current_event.unwrap_or_default(),
+                all_events,
             )
             .await
             .ok()
@@ -359,13 +596,26 @@ pub fn ViewDataPage() -> impl IntoView {
             ],
         });
 
-        for (i, &(_, filter_type)) in (if use_full_names.get_untracked() {
-            DataPoint::field_filter_types()
+        let filter_types = if use_full_names.get_untracked() {
+            schema
+                .get_untracked()
+                .and_then(Result::ok)
+                .unwrap_or_else(SeasonSchema::compiled)
+                .fields
+                .iter()
+                .map(|field| field.filter_type)
+                .collect::<Vec<_>>()
         } else {
             DataPoint::field_filter_types_reduced()
-        })
-        .iter()
-        .enumerate()
+                .iter()
+                .map(|&(_, filter_type)| filter_type)
+                .collect()
+        };
+        for (i, filter_type) in filter_types
+            .into_iter()
+            // The actions column can't be filtered
+            .chain([FilterType::None])
+            .enumerate()
         {
             if filter_type != FilterType::Normal {
                 Reflect::set(
@@ -397,42 +647,45 @@ pub fn ViewDataPage() -> impl IntoView {
         true,
     );
 
-    let update_match_number = move |ev: Event| {
-        let value = event_target_value(&ev);
-        let el: HtmlInputElement = event_target(&ev);
-        if !value.is_empty() {
-            // Parse the current value as a number
-            if let Ok(num_val) = value.parse::<usize>() {
-                // Get min and max attributes if they exist
-                let min = el.min().parse::<usize>().ok();
-                let max = el.max().parse::<usize>().ok();
-
-                // Enforce minimum value if present
-                if let Some(min_val) = min {
-                    if num_val < min_val {
-                        el.set_value(&min_val.to_string());
-                        return;
-                    }
-                }
-
-                // Enforce maximum value if present
-                if let Some(max_val) = max {
-                    if num_val > max_val {
-                        el.set_value(&max_val.to_string());
-                        return;
-                    }
-                }
+    // Changes one part of the current match, keeping the others
+    let set_match_part =
+        move |level: Option<MatchLevel>, set_number: Option<u32>, match_number: Option<u32>| {
+            let current = current_match_key.get_untracked();
+            let level = level.unwrap_or(current.level);
+            let set_number = if level == MatchLevel::Qualification {
+                1
             } else {
-                // If the value isn't a valid number, reset to empty or min
-                if let Ok(min_val) = el.min().parse::<usize>() {
-                    el.set_value(&min_val.to_string());
-                } else {
-                    el.set_value("");
-                }
+                set_number.unwrap_or(current.set_number)
+            };
+            set_current_match_key(MatchKey::new(
+                level,
+                set_number,
+                match_number.unwrap_or(current.match_number),
+            ));
+        };
+
+    // Parses a positive number from a number input, restoring `current` when
+    // it is not one
+    let parse_part = move |ev: &Event, current: u32| {
+        let el: HtmlInputElement = event_target(ev);
+        match el.value().parse::<u32>() {
+            Ok(value) if value > 0 => Some(value),
+            _ => {
+                el.set_value(&current.to_string());
+                None
+            }
+        }
+    };
+
+    let update_match_key = move |ev: Event| {
+        let el: HtmlInputElement = event_target(&ev);
+        match el.value().parse::<MatchKey>() {
+            Ok(match_key) => set_current_match_key(match_key),
+            Err(err) => {
+                let _ = show_error("Invalid Match", &err.to_string());
             }
         }
-        set_current_match_num.set(el.value().parse().unwrap_or(1));
-        println!("{}", current_match_num.get());
+        el.set_value("");
     };
 
     let prevent_invalid_input = move |ev: ev::KeyboardEvent| {
@@ -444,14 +697,14 @@ pub fn ViewDataPage() -> impl IntoView {
     let set_next_team_match = move |_: ev::MouseEvent| {
         spawn_local(async move {
             if let Ok(team_number) = team_number.get_untracked().unwrap_or_default().parse() {
-                if let Ok(match_num) = next_team_match(
+                if let Ok(match_key) = next_team_match(
                     team_number,
                     current_event.get_untracked().unwrap_or_default(),
                 )
                 .await
-                    && let Some(match_num) = match_num
+                    && let Some(match_key) = match_key
                 {
-                    set_current_match_num(match_num);
+                    set_current_match_key(match_key);
                 }
             } else {
                 let _ = show_error(
@@ -462,6 +715,213 @@ pub fn ViewDataPage() -> impl IntoView {
         });
     };
 
+    // The name of the scout making changes, required to record them in the
+    // audit log
+    let actor = move || {
+        let name = scout_name.get_untracked();
+        if name.trim().is_empty() {
+            let _ = show_error(
+                "Error",
+                "Your name needs to be set in settings to change scouting data!",
+            );
+            None
+        } else {
+            Some(name)
+        }
+    };
+
+    let delete_entry = move |id: i32| {
+        let Some(actor) = actor() else {
+            return;
+        };
+        let confirmed = window()
+            .and_then(|window| {
+                window
+                    .confirm_with_message(&format!(
+                        "Delete entry {id}? It can be restored from the change log."
+                    ))
+                    .ok()
+            })
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+        spawn_local(async move {
+            match delete_scouting_entry(id, actor).await {
+                Ok(()) => {
+                    data.refetch();
+                    audit_log.refetch();
+                }
+                Err(err) => {
+                    let _ = show_error("Failed to delete entry", &err.to_string());
+                }
+            }
+        });
+    };
+
+    let save_entry = move |_: ev::MouseEvent| {
+        let Some(entry) = editing.get_untracked() else {
+            return;
+        };
+        let Some(actor) = actor() else {
+            return;
+        };
+        spawn_local(async move {
+            match update_scouting_entry(entry, actor).await {
+                Ok(()) => {
+                    editing.set(None);
+                    data.refetch();
+                    audit_log.refetch();
+                }
+                Err(err) => {
+                    let _ = show_error("Failed to save entry", &err.to_string());
+                }
+            }
+        });
+    };
+
+    let restore_entry = move |audit_id: i32| {
+        let Some(actor) = actor() else {
+            return;
+        };
+        spawn_local(async move {
+            match restore_scouting_entry(audit_id, actor).await {
+                Ok(()) => {
+                    data.refetch();
+                    audit_log.refetch();
+                }
+                Err(err) => {
+                    let _ = show_error("Failed to restore entry", &err.to_string());
+                }
+            }
+        });
+    };
+
+    // Inputs for every field of the season schema for the entry being edited.
+    // Only re-rendered when another entry is opened, the values are read when
+    // the dialog opens.
+    let edit_fields = move || {
+        editing_id.get()?;
+        let entry = editing.get_untracked()?;
+        Some(
+            season()
+                .fields
+                .into_iter()
+                .map(|field| {
+                    let data_type = field.data_type;
+                    let nullable = field.nullable;
+                    let is_extra = field.extra;
+                    let name = field.name;
+                    let pretty_name = field.pretty_name;
+                    let min = field.min;
+                    let max = field.max;
+                    let value = entry.get_field(&name).unwrap_or(DataType::Null);
+                    let on_change = move |ev: Event| {
+                        let new_value = if data_type == DataTypeName::Bool && !nullable {
+                            Some(DataType::Bool(event_target_checked(&ev)))
+                        } else {
+                            let text = event_target_value(&ev);
+                            // Blank inputs of nullable fields are missing values
+                            if nullable && text.trim().is_empty() {
+                                Some(DataType::Null)
+                            } else {
+                                data_type.parse_value(&text)
+                            }
+                        };
+                        if let Some(new_value) = new_value {
+                            editing.update(|entry| {
+                                if let Some(entry) = entry.as_mut() {
+                                    if is_extra {
+                                        entry.extra.insert(name.clone(), new_value);
+                                    } else {
+                                        entry.data.set_field(&name, new_value);
+                                    }
+                                }
+                            });
+                        }
+                    };
+                    let input = match data_type {
+                        DataTypeName::Bool if nullable => view! {
+                            <select class="select select-bordered w-full" on:change=on_change>
+                                <option value="" selected={value == DataType::Null}></option>
+                                <option value="true" selected={value == DataType::Bool(true)}>
+                                    Yes
+                                </option>
+                                <option value="false" selected={value == DataType::Bool(false)}>
+                                    No
+                                </option>
+                            </select>
+                        }
+                        .into_any(),
+                        DataTypeName::Enum => {
+                            let current = value.to_string();
+                            let options = field
+                                .options
+                                .into_iter()
+                                .map(|option| {
+                                    let selected = option == current;
+                                    let label = option.clone();
+                                    view! {
+                                        <option value=option selected=selected>
+                                            {label}
+                                        </option>
+                                    }
+                                })
+                                .collect_view();
+                            view! {
+                                <select class="select select-bordered w-full" on:change=on_change>
+                                    <Show when=move || nullable>
+                                        <option value=""></option>
+                                    </Show>
+                                    {options}
+                                </select>
+                            }
+                            .into_any()
+                        }
+                        DataTypeName::Bool => view! {
+                            <input
+                                type="checkbox"
+                                class="checkbox checkbox-primary"
+                                prop:checked={value == DataType::Bool(true)}
+                                on:change=on_change
+                            />
+                        }
+                        .into_any(),
+                        DataTypeName::String => view! {
+                            <input
+                                type="text"
+                                class="input input-bordered w-full"
+                                maxlength=max
+                                prop:value=value.to_string()
+                                on:change=on_change
+                            />
+                        }
+                        .into_any(),
+                        _ => view! {
+                            <input
+                                type="number"
+                                class="input input-bordered w-full"
+                                min=min
+                                max=max
+                                prop:value=value.to_string()
+                                on:change=on_change
+                            />
+                        }
+                        .into_any(),
+                    };
+                    view! {
+                        <div class="form-control w-full mb-4">
+                            <label class="label pb-2">
+                                <span class="label-text font-medium">{pretty_name}</span>
+                            </label>
+                            {input}
+                        </div>
+                    }
+                })
+                .collect_view(),
+        )
+    };
+
     view! {
         <Suspense>
             <script src="/tablefilter/tablefilter.js"></script>
@@ -486,6 +946,19 @@ pub fn ViewDataPage() -> impl IntoView {
                                 }
                             />
                             <br />
+                            <label class="label-text text-lg font-medium" for="allEventsCheckbox">
+                                Show All Events
+                            </label>
+                            <input
+                                class="checkbox checkbox-primary"
+                                type="checkbox"
+                                id="allEventsCheckbox"
+                                name="allEventsCheckbox"
+                                on:input=move |ev| {
+                                    set_all_events(event_target_checked(&ev));
+                                }
+                            />
+                            <br />
                             <br />
                             <table class="table" id="scouting_data_table">
                                 <thead>
@@ -500,41 +973,46 @@ pub fn ViewDataPage() -> impl IntoView {
                                                 items
                                                     .iter()
                                                     .map(|item| {
+                                                        let id = item.id;
+                                                        let entry = item.clone();
                                                         view! {
                                                             <tr class="hover:bg-base-300">
                                                                 {if use_full_names.get() {
-                                                                    DataPoint::field_names()
+                                                                    season()
+                                                                        .fields
                                                                         .iter()
-                                                                        .map(|name| {
-                                                                            use crate::data::DataType;
+                                                                        .map(|field| {
                                                                             let value = item
-                                                                                .get_field(name)
-                                                                                .expect("Field should exist");
-                                                                            let string_value = match value {
-                                                                                DataType::U16(val) => val.to_string(),
-                                                                                DataType::U32(val) => val.to_string(),
-                                                                                DataType::U64(val) => val.to_string(),
-                                                                                DataType::I16(val) => val.to_string(),
-                                                                                DataType::I32(val) => val.to_string(),
-                                                                                DataType::I64(val) => val.to_string(),
-                                                                                DataType::String(val) => val,
-                                                                                DataType::Bool(val) => display_bool(val),
-                                                                                DataType::Float(val) => format!("{val:.2}"),
-                                                                            };
-                                                                            view! { <td>{string_value}</td> }
+                                                                                .get_field(&field.name)
+                                                                                .unwrap_or(DataType::Null)
+                                                                                .to_string();
+                                                                            team_cell(field.name == "team_number", value)
                                                                         })
                                                                         .collect_view()
                                                                         .into_any()
                                                                 } else {
-                                                                    item.get_reduced_columns()
+                                                                    item.data
+                                                                        .get_reduced_columns()
                                                                         .iter()
                                                                         .cloned()
-                                                                        .map(|(_, value)| {
-                                                                            view! { <td>{value}</td> }
-                                                                        })
+                                                                        .map(|(name, value)| team_cell(name == "Team", value))
                                                                         .collect_view()
                                                                         .into_any()
                                                                 }}
+                                                                <td class="whitespace-nowrap">
+                                                                    <button
+                                                                        class="btn btn-xs btn-outline mr-1"
+                                                                        on:click=move |_| editing.set(Some(entry.clone()))
+                                                                    >
+                                                                        Edit
+                                                                    </button>
+                                                                    <button
+                                                                        class="btn btn-xs btn-outline btn-error"
+                                                                        on:click=move |_| delete_entry(id)
+                                                                    >
+                                                                        Delete
+                                                                    </button>
+                                                                </td>
                                                             </tr>
                                                         }
                                                     })
@@ -564,7 +1042,13 @@ pub fn ViewDataPage() -> impl IntoView {
                             <br />
                             <div class="flex justify-center">
                                 <a
-                                    href="/download-xlsx"
+                                    href=move || {
+                                        event_filter()
+                                            .map_or_else(
+                                                || "/download-xlsx".to_owned(),
+                                                |event_key| format!("/download-xlsx?event={event_key}"),
+                                            )
+                                    }
                                     class="btn btn-primary"
                                     download="data.xlsx"
                                 >
@@ -576,6 +1060,105 @@ pub fn ViewDataPage() -> impl IntoView {
                 </div>
             </div>
 
+            <div class="container mx-auto mt-8">
+                <h1 class="text-3xl font-bold text-center mb-8">Change Log</h1>
+                <div class="card bg-base-200 shadow-xl">
+                    <div class="card-body p-8">
+                        <div class="overflow-x-auto max-h-[32rem]">
+                            <table class="table table-pin-rows">
+                                <thead>
+                                    <tr>
+                                        <th>Time</th>
+                                        <th>Scout</th>
+                                        <th>Action</th>
+                                        <th>Entry</th>
+                                        <th>Match</th>
+                                        <th>Team</th>
+                                        <th></th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    <Suspense fallback=move || {
+                                        view! { <span>Loading...</span> }
+                                    }>
+                                        {move || match *audit_log.read() {
+                                            Some(Ok(ref records)) => {
+                                                records
+                                                    .iter()
+                                                    .map(|record| {
+                                                        let audit_id = record.id;
+                                                        let entry = record.new.as_ref().or(record.old.as_ref());
+                                                        view! {
+                                                            <tr class="hover:bg-base-300">
+                                                                <td class="whitespace-nowrap">{record.changed_at.clone()}</td>
+                                                                <td>{record.actor.clone()}</td>
+                                                                <td class="capitalize">{record.action.to_string()}</td>
+                                                                <td>{record.entry_id}</td>
+                                                                <td>{entry.map(|entry| entry.data.match_number)}</td>
+                                                                <td>{entry.map(|entry| entry.data.team_number.to_string())}</td>
+                                                                <td>
+                                                                    <Show when={
+                                                                        let restorable = record.action.is_restorable();
+                                                                        move || restorable
+                                                                    }>
+                                                                        <button
+                                                                            class="btn btn-xs btn-outline"
+                                                                            on:click=move |_| restore_entry(audit_id)
+                                                                        >
+                                                                            Restore
+                                                                        </button>
+                                                                    </Show>
+                                                                </td>
+                                                            </tr>
+                                                        }
+                                                    })
+                                                    .collect_view()
+                                                    .into_any()
+                                            }
+                                            Some(Err(_)) => {
+                                                view! {
+                                                    <tr>
+                                                        <td>Error loading changes...</td>
+                                                    </tr>
+                                                }
+                                                    .into_any()
+                                            }
+                                            None => {
+                                                view! {
+                                                    <tr>
+                                                        <td>Loading...</td>
+                                                    </tr>
+                                                }
+                                                    .into_any()
+                                            }
+                                        }}
+                                    </Suspense>
+                                </tbody>
+                            </table>
+                        </div>
+                    </div>
+                </div>
+            </div>
+
+            <div class="modal" class:modal-open=move || editing_id.get().is_some()>
+                <div class="modal-box">
+                    <h3 class="text-lg font-bold mb-4">
+                        {move || {
+                            editing_id.get().map(|id| format!("Edit Entry {id}")).unwrap_or_default()
+                        }}
+                    </h3>
+                    {edit_fields}
+                    <div class="modal-action">
+                        <button class="btn" on:click=move |_| editing.set(None)>
+                            Cancel
+                        </button>
+                        <button class="btn btn-primary" on:click=save_entry>
+                            Save
+                        </button>
+                    </div>
+                </div>
+            </div>
+
             <div class="container mx-auto mt-[69px]">
                 <h1 class="text-3xl font-bold text-center mb-8">View Match</h1>
                 <Suspense>
@@ -599,7 +1182,19 @@ pub fn ViewDataPage() -> impl IntoView {
                                                 <div class="flex-1">
                                                     <h2 class="text-xl font-bold text-center text-error mb-4">
                                                         Red Alliance
+                                                        {move || {
+                                                            current_match
+                                                                .get()
+                                                                .flatten()
+                                                                .and_then(|match_data| match_data.red_alliance)
+                                                                .map(|alliance| format!(" - Alliance {alliance}"))
+                                                        }}
                                                     </h2>
+                                                    <p class="text-center font-medium">
+                                                        <Suspense fallback=move || {
+                                                            view! { Loading... }
+                                                        }>{alliance_points_view!(current_match, red)}</Suspense>
+                                                    </p>
                                                     <div class="rounded-lg p-4 space-y-2">
                                                         <div class="team-container" id="red1">
                                                             <span class="font-bold">{"Team 1: "}</span>
@@ -609,7 +1204,9 @@ pub fn ViewDataPage() -> impl IntoView {
                                                             <Suspense fallback=move || {
                                                                 view! { <p>"Loading stats..."</p> }
                                                             }>
-                                                                <div class="text-sm opacity-75 team-stats">{}</div>
+                                                                <div class="text-sm opacity-75 team-stats">
+                                                                    {team_data_view!(current_match, red, 0)}
+                                                                </div>
                                                             </Suspense>
                                                         </div>
                                                         <div class="team-container" id="red2">
@@ -650,19 +1247,83 @@ pub fn ViewDataPage() -> impl IntoView {
                                                             Next Team Match
                                                         </button>
                                                     </div>
-                                                    <div class="text-2xl font-bold mb-2" id="matchNumber">
-                                                        Match
+                                                    <div class="text-2xl font-bold mb-2" id="matchLabel">
+                                                        {move || {
+                                                            current_match
+                                                                .get()
+                                                                .flatten()
+                                                                .map_or_else(
+                                                                    || current_match_key.get().label(false),
+                                                                    |match_data| match_data.label,
+                                                                )
+                                                        }}
+                                                    </div>
+                                                    <div class="flex justify-center items-center gap-2 mb-2" id="matchNumber">
+                                                        <select
+                                                            class="select select-bordered select-sm"
+                                                            on:change=move |ev| {
+                                                                let level = MatchLevel::from_code(&event_target_value(&ev));
+                                                                set_match_part(level, None, None);
+                                                            }
+                                                        >
+                                                            {MatchLevel::ALL
+                                                                .into_iter()
+                                                                .map(|level| {
+                                                                    view! {
+                                                                        <option
+                                                                            value=level.code()
+                                                                            selected=move || current_match_key.get().level == level
+                                                                        >
+                                                                            {level.to_string()}
+                                                                        </option>
+                                                                    }
+                                                                })
+                                                                .collect_view()}
+                                                        </select>
+                                                        <input
+                                                            min="1"
+                                                            max="99"
+                                                            style="width: 60px !important;"
+                                                            class="input input-bordered input-sm"
+                                                            class:hidden=move || {
+                                                                current_match_key.get().level == MatchLevel::Qualification
+                                                            }
+                                                            type="number"
+                                                            title="Set"
+                                                            prop:value=move || current_match_key.get().set_number.to_string()
+                                                            on:keydown=prevent_invalid_input
+                                                            on:change=move |ev| {
+                                                                let set_number = parse_part(
+                                                                    &ev,
+                                                                    current_match_key.get_untracked().set_number,
+                                                                );
+                                                                set_match_part(None, set_number, None);
+                                                            }
+                                                        />
                                                         <input
                                                             min="1"
                                                             max="999"
-                                                            style="width: 60px !important; font-size: var(--text-xl) !important;"
-                                                            class=".transparent-num !input !input-primary"
+                                                            style="width: 60px !important;"
+                                                            class="input input-bordered input-sm"
                                                             type="number"
-                                                            prop:value=move || current_match_num.get().to_string()
+                                                            title="Match"
+                                                            prop:value=move || current_match_key.get().match_number.to_string()
                                                             on:keydown=prevent_invalid_input
-                                                            on:onchange=update_match_number
+                                                            on:change=move |ev| {
+                                                                let match_number = parse_part(
+                                                                    &ev,
+                                                                    current_match_key.get_untracked().match_number,
+                                                                );
+                                                                set_match_part(None, None, match_number);
+                                                            }
                                                         />
                                                     </div>
+                                                    <input
+                                                        class="input input-bordered input-sm w-40 mb-2"
+                                                        type="text"
+                                                        placeholder="Match key, like sf5m1"
+                                                        on:change=update_match_key
+                                                    />
                                                     <div class="badge badge-neutral" id="matchTime">
 
                                                         Time:
@@ -681,12 +1342,33 @@ pub fn ViewDataPage() -> impl IntoView {
                                                         </Suspense>
 
                                                     </div>
+                                                    <p class="text-xs opacity-70 mt-2" id="scheduleAge">
+                                                        {move || {
+                                                            current_match
+                                                                .get()
+                                                                .flatten()
+                                                                .and_then(|match_data| match_data.schedule_age)
+                                                                .map(|age| age.describe())
+                                                        }}
+                                                    </p>
                                                 </div>
 
                                                 <div class="flex-1">
                                                     <h2 class="text-xl font-bold text-center text-primary mb-4 text-blue-600">
                                                         Blue Alliance
+                                                        {move || {
+                                                            current_match
+                                                                .get()
+                                                                .flatten()
+                                                                .and_then(|match_data| match_data.blue_alliance)
+                                                                .map(|alliance| format!(" - Alliance {alliance}"))
+                                                        }}
                                                     </h2>
+                                                    <p class="text-center font-medium">
+                                                        <Suspense fallback=move || {
+                                                            view! { Loading... }
+                                                        }>{alliance_points_view!(current_match, blue)}</Suspense>
+                                                    </p>
                                                     <div class="rounded-lg p-4 space-y-2">
                                                         <div class="team-container" id="blue1">
                                                             <span class="font-bold">{"Team 1: "}</span>
@@ -730,7 +1412,14 @@ pub fn ViewDataPage() -> impl IntoView {
                                                     </div>
                                                 </div>
                                             </div>
-                                            <div class="flex justify-center mt-6">
+                                            <div class="flex justify-center gap-4 mt-6">
+                                                <a
+                                                    class="btn btn-outline"
+                                                    id="compareTeams"
+                                                    href=move || compare_link(current_match.get().flatten())
+                                                >
+                                                    Compare Teams
+                                                </a>
                                                 <button
                                                     class="btn btn-outline"
                                                     id="refreshNextMatch"
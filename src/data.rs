@@ -1,10 +1,13 @@
 use core::fmt::Display;
+use std::collections::BTreeMap;
 
-use blue_scout_macros::{define_reduced_columns, define_struct, define_team_data};
 #[cfg(feature = "ssr")]
 use duckdb::ToSql;
 
-#[derive(Debug, Clone, PartialEq)]
+// The scouting entry, declared by the first season, see `game`
+pub use crate::game::reefscape::{DataPoint, InsertDataArgs, TeamSummary};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[non_exhaustive]
 pub enum DataType {
     U16(u16),
@@ -16,12 +19,17 @@ pub enum DataType {
     String(String),
     Bool(bool),
     Float(f32),
+    /// The label of an enum value.
+    Enum(String),
+    /// The missing value of a nullable (`Option<T>`) field.
+    Null,
 }
 
 impl DataType {
+    /// Returns the type of the value, or `None` for a missing value.
     #[must_use]
-    pub const fn name(&self) -> DataTypeName {
-        match *self {
+    pub const fn name(&self) -> Option<DataTypeName> {
+        Some(match *self {
             Self::U16(_) => DataTypeName::U16,
             Self::U32(_) => DataTypeName::U32,
             Self::U64(_) => DataTypeName::U64,
@@ -31,6 +39,75 @@ impl DataType {
             Self::String(_) => DataTypeName::String,
             Self::Bool(_) => DataTypeName::Bool,
             Self::Float(_) => DataTypeName::Float,
+            Self::Enum(_) => DataTypeName::Enum,
+            Self::Null => return None,
+        })
+    }
+
+    /// Returns the value as a number, or `None` if it is not numeric.
+    #[must_use]
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::U16(val) => Some(f64::from(val)),
+            Self::U32(val) => Some(f64::from(val)),
+            Self::U64(val) => Some(val as f64),
+            Self::I16(val) => Some(f64::from(val)),
+            Self::I32(val) => Some(f64::from(val)),
+            Self::I64(val) => Some(val as f64),
+            Self::Float(val) => Some(f64::from(val)),
+            Self::String(_) | Self::Bool(_) | Self::Enum(_) | Self::Null => None,
+        }
+    }
+
+    /// Formats the value of a team metric for display.
+    ///
+    /// # Returns
+    ///
+    /// Decimals are rounded to one digit and missing values are shown as
+    /// `N/A`.
+    #[must_use]
+    pub fn format_metric(&self) -> String {
+        match *self {
+            Self::Float(value) => format!("{value:.1}"),
+            Self::Null => "N/A".to_owned(),
+            ref value => value.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ToSql for DataType {
+    fn to_sql(&self) -> duckdb::Result<duckdb::types::ToSqlOutput<'_>> {
+        use duckdb::types::{ToSqlOutput, Value};
+
+        Ok(match *self {
+            Self::U16(val) => ToSqlOutput::from(val),
+            Self::U32(val) => ToSqlOutput::from(val),
+            Self::U64(val) => ToSqlOutput::from(val),
+            Self::I16(val) => ToSqlOutput::from(val),
+            Self::I32(val) => ToSqlOutput::from(val),
+            Self::I64(val) => ToSqlOutput::from(val),
+            Self::String(ref val) | Self::Enum(ref val) => ToSqlOutput::from(val.as_str()),
+            Self::Bool(val) => ToSqlOutput::from(val),
+            Self::Float(val) => ToSqlOutput::from(val),
+            Self::Null => ToSqlOutput::Owned(Value::Null),
+        })
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::U16(val) => write!(f, "{val}"),
+            Self::U32(val) => write!(f, "{val}"),
+            Self::U64(val) => write!(f, "{val}"),
+            Self::I16(val) => write!(f, "{val}"),
+            Self::I32(val) => write!(f, "{val}"),
+            Self::I64(val) => write!(f, "{val}"),
+            Self::String(ref val) | Self::Enum(ref val) => write!(f, "{val}"),
+            Self::Bool(val) => write!(f, "{}", if val { "Yes" } else { "No" }),
+            Self::Float(val) => write!(f, "{val:.2}"),
+            Self::Null => Ok(()),
         }
     }
 }
@@ -71,7 +148,8 @@ impl From<f32> for DataType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 #[non_exhaustive]
 pub enum DataTypeName {
     U16,
@@ -83,9 +161,37 @@ pub enum DataTypeName {
     String,
     Bool,
     Float,
+    Enum,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl DataTypeName {
+    /// Parses a value of this type from its textual form, as entered in a form
+    /// input.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `DataType`, or `None` if the value is not valid for this
+    /// type.
+    #[must_use]
+    pub fn parse_value(self, value: &str) -> Option<DataType> {
+        let value = value.trim();
+        Some(match self {
+            Self::U16 => DataType::U16(value.parse().ok()?),
+            Self::U32 => DataType::U32(value.parse().ok()?),
+            Self::U64 => DataType::U64(value.parse().ok()?),
+            Self::I16 => DataType::I16(value.parse().ok()?),
+            Self::I32 => DataType::I32(value.parse().ok()?),
+            Self::I64 => DataType::I64(value.parse().ok()?),
+            Self::String => DataType::String(value.to_owned()),
+            Self::Bool => DataType::Bool(value == "on" || value.parse().ok()?),
+            Self::Float => DataType::Float(value.parse().ok()?),
+            Self::Enum => DataType::Enum(value.to_owned()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 #[non_exhaustive]
 pub enum FilterType {
     Normal,
@@ -105,91 +211,453 @@ impl Display for FilterType {
     }
 }
 
-// PLEASE NOTE:
-// Changing the display name will not affect the column name in the database
-// but if you change the field name, it will affect the column name in
-// the database. This means migrating the column name is not supported yet.
-define_struct!(
-    DataPoint,
-    name: String => "Name" @ Normal,
-    match_number: u16 => "Match" @ Normal,
-    team_number: u32 => "Team" @ Normal,
-    auto_coral: u16 => "Auto Coral" @ Normal,
-    auto_algae: u16 => "Auto Algae" @ Normal,
-    auto_leave: bool => "Auto Leave" @ Select,
-    algae_clear: bool => "Algae Clear" @ Select,
-    l1_coral: u16 => "L1" @ Normal,
-    l2_coral: u16 => "L2" @ Normal,
-    l3_coral: u16 => "L3" @ Normal,
-    l4_coral: u16 => "L4" @ Normal,
-    dropped_coral: u16 => "Dropped" @ Normal,
-    algae_barge: u16 => "Algae Barge" @ Normal,
-    algae_floor_hole: u16 => "Algae Floor Hole" @ Normal,
-    climb: String => "Climb" @ Checklist,
-    defense_bot: bool => "Defense" @ Select,
-    notes: String => "Notes" @ None,
-);
-
-define_reduced_columns!(
-    DataPoint,
-    "Match" @ Normal => |s: &Self| s.match_number.to_string(),
-    "Team" @ Normal => |s: &Self| s.team_number.to_string(),
-    "Auto Coral" @ Normal => |s: &Self| s.auto_coral.to_string(),
-    "Auto Leave" @ Select => |s: &Self| if s.auto_leave { "Yes".to_owned() } else { "No".to_owned() },
-    "Algae Clear" @ Select => |s: &Self| if s.algae_clear { "Yes".to_owned() } else { "No".to_owned() },
-    "Teleop Coral" @ Normal => |s: &Self| (s.l1_coral + s.l2_coral + s.l3_coral + s.l4_coral).to_string(),
-    "Teleop Algae" @ Normal => |s: &Self| (s.algae_barge + s.algae_floor_hole).to_string(),
-    "Climb" @ Checklist => |s: &Self| s.climb.clone(),
-    "Defense" @ Select => |s: &Self| if s.defense_bot { "Yes".to_owned() } else { "No".to_owned() },
-);
-
-define_team_data!(
-    DataPoint,
-    "Avg Coral" => |v: &[DataPoint]| {
-        format!("{:.1}", f64::from(v
-            .iter()
-            .map(|x| u32::from(x.l4_coral + x.l3_coral + x.l2_coral + x.l1_coral))
-            .sum::<u32>())
-        / v.len() as f64)
-    },
-    "Avg Auto Coral" => |v: &[DataPoint]|{
-        format!("{:.1}",
-        f64::from(v
-            .iter()
-            .map(|x| u32::from(x.auto_coral))
-            .sum::<u32>())
-        / v.len() as f64)
-    },
-    "Avg Barge Algae" => |v: &[DataPoint]|{
-        format!("{:.1}", f64::from(v
+/// The version of the database schema described by `DataPoint` and the
+/// bookkeeping tables of `db`.
+///
+/// Bump this whenever the fields of `DataPoint` or the tables in `db` change.
+/// The server refuses to start on a database migrated by a build with a newer
+/// schema version, so builds with different fields can't corrupt each other's
+/// data.
+pub const SCHEMA_VERSION: u32 = 10;
+
+/// A fieldless enum stored in a `DuckDB` `ENUM` column, defined with
+/// `define_enum!`.
+pub trait SqlEnum: Sized + Copy + 'static {
+    /// The name of the `DuckDB` type of the enum.
+    const SQL_TYPE: &'static str;
+    /// The labels of the variants, in declaration order. They are the values
+    /// stored in the database and shown to the user.
+    const LABELS: &'static [&'static str];
+    /// The variants, in declaration order.
+    const VARIANTS: &'static [Self];
+
+    /// Returns the label of the variant.
+    fn label(self) -> &'static str;
+
+    /// Returns the variant with the given label.
+    #[must_use]
+    fn from_label(label: &str) -> Option<Self> {
+        Self::LABELS
             .iter()
-            .map(|x| u32::from(x.algae_barge))
-            .sum::<u32>())
-            / v.len() as f64)
-    },
-    "Scoring Locations" => |v: &[DataPoint]|{
-        let (score_l1, score_l2, score_l3, score_l4) = (
-            u32::try_from(v.iter().filter(|x| x.l1_coral > 0).count()).expect("This should not be bigger than u32::MAX"),
-            u32::try_from(v.iter().filter(|x| x.l2_coral > 0).count()).expect("This should not be bigger than u32::MAX"),
-            u32::try_from(v.iter().filter(|x| x.l3_coral > 0).count()).expect("This should not be bigger than u32::MAX"),
-            u32::try_from(v.iter().filter(|x| x.l4_coral > 0).count()).expect("This should not be bigger than u32::MAX"),
-        );
-        let locations = [
-            ("L1", score_l1),
-            ("L2", score_l2),
-            ("L3", score_l3),
-            ("L4", score_l4),
-        ]
+            .zip(Self::VARIANTS)
+            .find(|&(&variant_label, _)| variant_label == label)
+            .map(|(_, &variant)| variant)
+    }
+
+    /// Returns the SQL statement creating the `DuckDB` type of the enum.
+    #[must_use]
+    fn create_type_sql() -> String {
+        let labels = Self::LABELS
             .iter()
-            .filter_map(|x| (x.1 > 0).then_some(x.0))
-            .collect::<Vec<&str>>()
+            .map(|label| format!("'{}'", label.replace('\'', "''")))
+            .collect::<Vec<_>>()
             .join(", ");
-        if locations.is_empty() { "None".to_owned() } else { locations }
-    },
-    "Sum of Deep Climbs" => |v: &[DataPoint]|{
-        u32::try_from(v.iter().filter(|x| x.climb == "Deep").count()).expect("This should not be bigger than u32::MAX")
-    },
-    "Sum of Not Attempted" => |v: &[DataPoint]|{
-        u32::try_from(v.iter().filter(|x| x.climb == "Not Attempted").count()).expect("This should not be bigger than u32::MAX")
-    }
-);
+        format!("CREATE TYPE {} AS ENUM ({labels});", Self::SQL_TYPE)
+    }
+}
+
+/// The constraints of a field, declared with `#[validate(...)]` in
+/// `define_struct!`.
+///
+/// Bounds are kept as text so they can be used directly as the `min` and `max`
+/// attributes of form inputs. For `String` fields they bound the length of the
+/// value (`maxlength` in forms).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct FieldConstraints {
+    /// The smallest allowed value (or length).
+    pub min: Option<&'static str>,
+    /// The largest allowed value (or length).
+    pub max: Option<&'static str>,
+    /// Whether the value must be present and not blank.
+    pub required: bool,
+    /// A regex the whole value must match.
+    pub regex: Option<&'static str>,
+    /// The allowed values, if restricted to a fixed list.
+    pub allowed: &'static [&'static str],
+}
+
+/// The input used for a field on the scouting form, declared with
+/// `#[form(widget = ...)]` in `define_struct!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Widget {
+    /// A single line text input.
+    Text,
+    /// A number input.
+    Number,
+    /// A number with buttons to increment and decrement it.
+    Counter,
+    /// A checkbox, for `bool` fields.
+    Checkbox,
+    /// A radio button for every option of the field.
+    Radio,
+    /// A dropdown of the options of the field.
+    Select,
+    /// A multi-line text input.
+    TextArea,
+    /// Not shown, the value is set by the page.
+    Hidden,
+}
+
+/// How a field is shown on the scouting form, generated by `define_struct!`
+/// from the `#[form(...)]` attribute of the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FormField {
+    /// The name of the field.
+    pub name: &'static str,
+    /// The display name of the field.
+    pub pretty_name: &'static str,
+    /// The input used for the field.
+    pub widget: Widget,
+    /// The heading of the section of the form the field is in.
+    pub section: Option<&'static str>,
+    /// The label of the row of inputs the field is shown in, shared with the
+    /// neighbouring fields of the same group.
+    pub group: Option<&'static str>,
+    /// The placeholder of the input.
+    pub placeholder: Option<&'static str>,
+}
+
+/// A scouting entry stored in the database, along with its id.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct ScoutEntry {
+    /// The id of the entry in the `scout_entries` table.
+    pub id: i32,
+    /// The scouted data.
+    pub data: DataPoint,
+    /// The values of the fields added by the season schema, by field name.
+    #[serde(default)]
+    pub extra: BTreeMap<String, DataType>,
+}
+
+impl ScoutEntry {
+    /// Returns the value of a `DataPoint` field or of a field added by the
+    /// season schema.
+    #[must_use]
+    pub fn get_field(&self, field_name: &str) -> Option<DataType> {
+        self.data
+            .get_field(field_name)
+            .or_else(|| self.extra.get(field_name).cloned())
+    }
+
+    /// Sorts entries by event, then by match number and in the order they were
+    /// recorded.
+    pub fn sort_by_match(entries: &mut [Self]) {
+        entries.sort_by(|a, b| {
+            (&a.data.event_key, a.data.match_number, a.id)
+                .cmp(&(&b.data.event_key, b.data.match_number, b.id))
+        });
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ScoutEntry {
+    /// Maps a database row to a new `ScoutEntry`.
+    ///
+    /// The first column (index 0) is expected to be the id, followed by the
+    /// `DataPoint` fields in order and the fields added by the season schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a column can't be converted to its field type.
+    pub fn map_entry(row: &duckdb::Row<'_>) -> duckdb::Result<Self> {
+        let first_extra = DataPoint::field_names().len() + 1;
+        let extra = crate::schema::season_schema()
+            .extra_fields()
+            .enumerate()
+            .map(|(i, field)| {
+                let index = first_extra + i;
+                let value = match field.data_type {
+                    DataTypeName::U16 => row.get::<_, Option<u16>>(index)?.map(DataType::U16),
+                    DataTypeName::U32 => row.get::<_, Option<u32>>(index)?.map(DataType::U32),
+                    DataTypeName::U64 => row.get::<_, Option<u64>>(index)?.map(DataType::U64),
+                    DataTypeName::I16 => row.get::<_, Option<i16>>(index)?.map(DataType::I16),
+                    DataTypeName::I32 => row.get::<_, Option<i32>>(index)?.map(DataType::I32),
+                    DataTypeName::I64 => row.get::<_, Option<i64>>(index)?.map(DataType::I64),
+                    DataTypeName::String => {
+                        row.get::<_, Option<String>>(index)?.map(DataType::String)
+                    }
+                    DataTypeName::Bool => row.get::<_, Option<bool>>(index)?.map(DataType::Bool),
+                    DataTypeName::Float => row.get::<_, Option<f32>>(index)?.map(DataType::Float),
+                    DataTypeName::Enum => row.get::<_, Option<String>>(index)?.map(DataType::Enum),
+                };
+                Ok((field.name.clone(), value.unwrap_or(DataType::Null)))
+            })
+            .collect::<duckdb::Result<_>>()?;
+
+        Ok(Self {
+            id: row.get(0)?,
+            data: DataPoint::map_datapoint(row)?,
+            extra,
+        })
+    }
+}
+
+/// A kind of change made to the `scout_entries` table.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AuditAction {
+    Insert,
+    Update,
+    Delete,
+    Restore,
+}
+
+impl AuditAction {
+    /// Parses an action from the name stored in the audit table.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "insert" => Some(Self::Insert),
+            "update" => Some(Self::Update),
+            "delete" => Some(Self::Delete),
+            "restore" => Some(Self::Restore),
+            _ => None,
+        }
+    }
+
+    /// Whether the change can be undone by restoring the previous row.
+    #[must_use]
+    pub const fn is_restorable(self) -> bool {
+        matches!(self, Self::Update | Self::Delete)
+    }
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Insert => write!(f, "insert"),
+            Self::Update => write!(f, "update"),
+            Self::Delete => write!(f, "delete"),
+            Self::Restore => write!(f, "restore"),
+        }
+    }
+}
+
+/// The data of an entry as recorded in the audit table: its `DataPoint` and
+/// the values of the fields added by the season schema.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct EntryData {
+    /// The scouted data.
+    #[serde(flatten)]
+    pub data: DataPoint,
+    /// The values of the fields added by the season schema, by field name.
+    /// Changes recorded before they were audited have none.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, DataType>,
+}
+
+impl From<ScoutEntry> for EntryData {
+    fn from(entry: ScoutEntry) -> Self {
+        Self {
+            data: entry.data,
+            extra: entry.extra,
+        }
+    }
+}
+
+/// A change made to a scouting entry, as recorded in the audit table.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct AuditRecord {
+    /// The id of the record in the audit table.
+    pub id: i32,
+    /// The id of the changed entry.
+    pub entry_id: i32,
+    /// The kind of change.
+    pub action: AuditAction,
+    /// The entry before the change, or `None` if it didn't exist or was
+    /// deleted.
+    pub old: Option<EntryData>,
+    /// The entry after the change, or `None` if it has been deleted.
+    pub new: Option<EntryData>,
+    /// The name of the scout who made the change.
+    pub actor: String,
+    /// When the change was made.
+    pub changed_at: String,
+}
+
+/// The metadata of a metric of `TeamSummary`, generated by
+/// `define_team_data!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MetricInfo {
+    /// The name of the field of the metric.
+    pub name: &'static str,
+    /// The display name of the metric.
+    pub pretty_name: &'static str,
+    /// The type of the values of the metric.
+    pub data_type: DataTypeName,
+}
+
+impl MetricInfo {
+    /// Formats a value of the metric for display.
+    ///
+    /// # Returns
+    ///
+    /// Decimals are rounded to one digit and missing values are shown as
+    /// `N/A`.
+    #[must_use]
+    pub fn format(&self, value: &DataType) -> String {
+        value.format_metric()
+    }
+}
+
+/// Computes the average of the recorded values, skipping the missing ones.
+///
+/// # Returns
+///
+/// The average, or `None` if no value has been recorded.
+#[must_use]
+pub fn average<T: Into<f32>>(values: impl IntoIterator<Item = Option<T>>) -> Option<f32> {
+    let (sum, count) = values
+        .into_iter()
+        .flatten()
+        .fold((0.0, 0_u32), |(sum, count), value| (sum + value.into(), count + 1));
+
+    (count > 0).then(|| sum / count as f32)
+}
+
+/// A statistic of the distribution of the values of a metric over the entries
+/// of a team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Statistic {
+    /// The average of the values.
+    Mean,
+    /// The middle value, or the average of the two middle values.
+    Median,
+    /// The smallest value.
+    Min,
+    /// The largest value.
+    Max,
+    /// The population standard deviation of the values.
+    StdDev,
+    /// The average of the given number of latest values.
+    Recent(usize),
+}
+
+/// The values of a metric over the entries of a team, in match order. Missing
+/// values are skipped.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[non_exhaustive]
+pub struct Distribution {
+    /// The recorded values.
+    values: Vec<f32>,
+}
+
+impl Distribution {
+    /// Creates a new `Distribution` from the values of the entries of a team.
+    #[must_use]
+    pub fn new(values: impl IntoIterator<Item = Option<f32>>) -> Self {
+        Self {
+            values: values.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Computes a statistic of the values.
+    ///
+    /// # Returns
+    ///
+    /// The statistic, or `None` if no value has been recorded.
+    #[must_use]
+    pub fn get(&self, statistic: Statistic) -> Option<f32> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        let mean = |values: &[f32]| values.iter().sum::<f32>() / values.len() as f32;
+        let value = match statistic {
+            Statistic::Mean => mean(&self.values),
+            Statistic::Median => {
+                let mut sorted = self.values.clone();
+                sorted.sort_by(f32::total_cmp);
+                let middle = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    f32::midpoint(sorted[middle - 1], sorted[middle])
+                } else {
+                    sorted[middle]
+                }
+            }
+            Statistic::Min => self.values.iter().copied().fold(f32::INFINITY, f32::min),
+            Statistic::Max => self.values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            Statistic::StdDev => {
+                let mean = mean(&self.values);
+                let variance = self
+                    .values
+                    .iter()
+                    .map(|value| (value - mean).powi(2))
+                    .sum::<f32>()
+                    / self.values.len() as f32;
+                variance.sqrt()
+            }
+            Statistic::Recent(count) => {
+                mean(&self.values[self.values.len().saturating_sub(count.max(1))..])
+            }
+        };
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Distribution, EntryData, Statistic};
+    use crate::team::TeamNumber;
+
+    #[test]
+    fn computes_statistics() {
+        let distribution = Distribution::new([Some(2.0), None, Some(4.0), Some(9.0), Some(5.0)]);
+
+        assert_eq!(distribution.get(Statistic::Mean), Some(5.0));
+        assert_eq!(distribution.get(Statistic::Median), Some(4.5));
+        assert_eq!(distribution.get(Statistic::Min), Some(2.0));
+        assert_eq!(distribution.get(Statistic::Max), Some(9.0));
+        let std_dev = distribution.get(Statistic::StdDev).unwrap_or_default();
+        assert!((std_dev - 6.5_f32.sqrt()).abs() < 1e-6);
+        assert_eq!(
+            Distribution::new([Some(3.0), Some(1.0), Some(2.0)]).get(Statistic::Median),
+            Some(2.0)
+        );
+    }
+
+    #[test]
+    fn averages_the_latest_values_in_match_order() {
+        let distribution = Distribution::new([Some(2.0), Some(4.0), Some(9.0), Some(5.0)]);
+
+        assert_eq!(distribution.get(Statistic::Recent(2)), Some(7.0));
+        assert_eq!(distribution.get(Statistic::Recent(0)), Some(5.0));
+        assert_eq!(distribution.get(Statistic::Recent(10)), Some(5.0));
+    }
+
+    #[test]
+    fn has_no_statistics_without_values() {
+        let distribution = Distribution::new([None, None]);
+
+        assert_eq!(distribution.get(Statistic::Mean), None);
+        assert_eq!(distribution.get(Statistic::Recent(3)), None);
+    }
+
+    #[test]
+    fn reads_legacy_audit_records() {
+        // Recorded before teams could have letters and before the auto levels
+        // were scouted
+        let json = r#"{
+            "name": "Scout", "match_number": 12, "team_number": 4682,
+            "auto_coral": 3, "auto_algae": 1, "auto_leave": true, "algae_clear": false,
+            "l1_coral": 1, "l2_coral": 2, "l3_coral": 3, "l4_coral": 4, "dropped_coral": 0,
+            "algae_barge": 2, "algae_floor_hole": 0, "climb": "Deep", "defense_bot": false,
+            "notes": "", "event_key": "2025casj"
+        }"#;
+
+        let entry: EntryData = serde_json::from_str(json).expect("Legacy records should be read");
+        assert_eq!(entry.data.team_number, TeamNumber::new(4682));
+        assert_eq!(entry.data.auto_l1_coral, None);
+        assert!(entry.extra.is_empty());
+    }
+}
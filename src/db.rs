@@ -1,13 +1,113 @@
 #![cfg(feature = "ssr")]
 
-use duckdb::Connection;
+use std::collections::BTreeMap;
+
+use duckdb::{Connection, OptionalExt as _};
 use once_cell::sync::OnceCell;
 use tokio::sync::{Mutex, MutexGuard};
 
-use crate::data::{DataPoint, DataTypeName};
+use crate::{
+    data::{
+        AuditAction, AuditRecord, DataPoint, DataType, DataTypeName, EntryData, ScoutEntry,
+        SCHEMA_VERSION,
+    },
+    game::{game, team_metrics},
+    alliance_selection::{mark_team, SelectionMark, SelectionStatus},
+    pick_list::{PickListChange, PickListEntry, PickTier},
+    schema::{season_schema, MetricValue},
+    tba_cache::CachedResponse,
+    team::TeamNumber,
+};
 
 pub static DB: OnceCell<Mutex<Connection>> = OnceCell::new();
 
+/// Returns the name of the table storing the entries of the season being
+/// scouted. Every season has its own table, see `Game::table`.
+#[must_use]
+pub fn entries_table() -> &'static str {
+    game().table()
+}
+
+/// Returns the SQL adding the column marking soft deleted entries to an
+/// entries table. Deleted entries are kept in the table so they can be
+/// restored.
+fn deleted_at_sql(table: &str) -> String {
+    format!("ALTER TABLE {table} ADD COLUMN deleted_at TIMESTAMP")
+}
+
+/// Returns the name of the table recording every change made to the entries
+/// of an entries table, named after it.
+fn audit_table_name(table: &str) -> String {
+    format!("{table}_audit")
+}
+
+/// Returns the SQL creating the sequence of the ids of an audit table.
+fn audit_sequence_sql(table: &str) -> String {
+    format!("CREATE SEQUENCE IF NOT EXISTS {table}_id_seq START 1;")
+}
+
+/// Returns the SQL creating an audit table, recording every change made to
+/// the entries. The rows before and after the change are stored as JSON.
+fn audit_table_sql(table: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {table} (
+    id INTEGER PRIMARY KEY DEFAULT nextval('{table}_id_seq'),
+    entry_id INTEGER NOT NULL,
+    action VARCHAR NOT NULL,
+    old_row VARCHAR,
+    new_row VARCHAR,
+    actor VARCHAR NOT NULL,
+    changed_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);"
+    )
+}
+
+/// The SQL creating the table recording the migrations applied to the
+/// database.
+const SCHEMA_MIGRATIONS_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS schema_migrations (
+    version UINTEGER PRIMARY KEY,
+    checksum VARCHAR NOT NULL,
+    description VARCHAR NOT NULL,
+    applied_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);";
+
+/// The SQL creating the table of the pick lists. The teams of an event are
+/// listed by `position`.
+const PICK_LIST_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS pick_list (
+    event_key VARCHAR NOT NULL,
+    team_number VARCHAR NOT NULL,
+    tier VARCHAR NOT NULL,
+    position INTEGER NOT NULL,
+    comment VARCHAR NOT NULL DEFAULT '',
+    PRIMARY KEY (event_key, team_number)
+);";
+
+/// The SQL creating the table caching the responses of TBA. The times are in
+/// seconds since the Unix epoch.
+const TBA_CACHE_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS tba_cache (
+    path VARCHAR PRIMARY KEY,
+    etag VARCHAR,
+    body VARCHAR NOT NULL,
+    fetched_at BIGINT NOT NULL,
+    max_age BIGINT
+);";
+
+/// The SQL creating the table of the team names fetched from TBA.
+const TEAM_NAMES_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS team_names (
+    team_number VARCHAR PRIMARY KEY,
+    nickname VARCHAR NOT NULL
+);";
+
+/// The SQL creating the table of the picks and declines marked during alliance
+/// selection. Declined teams have no `alliance`.
+const ALLIANCE_SELECTION_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS alliance_selection (
+    event_key VARCHAR NOT NULL,
+    team_number VARCHAR NOT NULL,
+    alliance UTINYINT,
+    marked_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (event_key, team_number)
+);";
+
 /// Retrieves a locked database connection.
 ///
 /// # Returns
@@ -23,64 +123,714 @@ pub async fn get_conn() -> MutexGuard<'static, Connection> {
     db.lock().await
 }
 
-/// Migrates the database schema to match the expected structure.
+/// A schema change that has not been applied to the database yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PendingMigration {
+    /// A human readable description of the change.
+    pub description: String,
+    /// The SQL statement applying the change.
+    pub sql: String,
+}
+
+impl PendingMigration {
+    /// Creates a new `PendingMigration`.
+    fn new(description: String, sql: String) -> Self {
+        Self { description, sql }
+    }
+}
+
+/// The latest migration recorded in the `schema_migrations` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AppliedMigration {
+    /// The schema version of the migration.
+    pub version: u32,
+    /// The checksum of the schema the migration resulted in.
+    pub checksum: String,
+}
+
+/// Hashes the given bytes with 64-bit FNV-1a. Unlike `DefaultHasher`, the
+/// result is stable across builds and Rust versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// Returns the checksum of the schema this binary expects.
+///
+/// Two builds with the same `SCHEMA_VERSION` but a different schema produce a
+/// different checksum. It is computed for the table declared with `DataPoint`,
+/// so it is the same whichever season is scouted.
+#[must_use]
+pub fn schema_checksum() -> String {
+    let table = DataPoint::table_name();
+    let mut schema = DataPoint::enum_types_sql()
+        .into_iter()
+        .map(|(_, sql)| sql)
+        .collect::<Vec<_>>();
+    schema.extend([
+        DataPoint::get_create_table_sql(table),
+        deleted_at_sql(table),
+        audit_table_sql(&audit_table_name(table)),
+    ]);
+    schema.extend(TABLES.iter().map(|&(_, sql)| sql.to_owned()));
+    let schema = schema.join("\n");
+    format!("{:016x}", fnv1a(schema.as_bytes()))
+}
+
+/// Retrieves the latest migration applied to the database, without creating
+/// the `schema_migrations` table.
 ///
 /// # Returns
 ///
-/// A `duckdb::Result<()>` indicating the success or failure of the operation.
+/// The latest `AppliedMigration`, or `None` if no migration has been recorded
+/// yet.
 ///
 /// # Errors
 ///
 /// Returns an error if there is an issue with the database operations.
-pub async fn migrate_db() -> duckdb::Result<()> {
-    let conn = get_conn().await;
+pub fn latest_migration(conn: &Connection) -> duckdb::Result<Option<AppliedMigration>> {
+    // Listing the migrations must not write to the database
+    let exists: bool = conn.query_row(
+        "SELECT count(*) > 0 FROM information_schema.tables WHERE table_name = 'schema_migrations'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Ok(None);
+    }
+
+    conn.query_row(
+        "SELECT version, checksum FROM schema_migrations ORDER BY version DESC LIMIT 1",
+        [],
+        |row| {
+            Ok(AppliedMigration {
+                version: row.get(0)?,
+                checksum: row.get(1)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Makes sure the database schema has not been created by a newer or
+/// different build of the application.
+///
+/// # Errors
+///
+/// Returns an error if the database schema version is newer than
+/// `SCHEMA_VERSION`, or if it is the same version but with a different
+/// schema.
+fn check_schema_version(latest: Option<&AppliedMigration>) -> anyhow::Result<()> {
+    let Some(latest) = latest else {
+        return Ok(());
+    };
+
+    if latest.version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "The database schema (version {}) is newer than the one of this build (version {SCHEMA_VERSION}). Update the application before using this database.",
+            latest.version
+        );
+    }
+
+    if latest.version == SCHEMA_VERSION && latest.checksum != schema_checksum() {
+        anyhow::bail!(
+            "The database schema (version {}) was created by a build with a different schema (checksum {} instead of {}). Bump SCHEMA_VERSION when changing the fields of DataPoint.",
+            latest.version,
+            latest.checksum,
+            schema_checksum()
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the value new rows get for a column of the given type when no value
+/// is available, as an SQL literal.
+const fn default_value(data_type: DataTypeName) -> &'static str {
+    match data_type {
+        DataTypeName::U16
+        | DataTypeName::U32
+        | DataTypeName::U64
+        | DataTypeName::I16
+        | DataTypeName::I32
+        | DataTypeName::I64 => "0",
+        DataTypeName::String => "''",
+        DataTypeName::Bool => "FALSE",
+        DataTypeName::Float => "0.0",
+        // Enum fields default to their first option, see `field_default_value`
+        DataTypeName::Enum => "NULL",
+    }
+}
+
+/// Returns the value new rows get for a field when no value is available, as
+/// an SQL literal.
+fn field_default_value(field_name: &str, data_type: DataTypeName) -> String {
+    DataPoint::field_options(field_name)
+        .and_then(|options| options.first())
+        .map_or_else(
+            || default_value(data_type).to_owned(),
+            |option| format!("'{}'", option.replace('\'', "''")),
+        )
+}
+
+/// Parses the labels of an enum column type reported by `DuckDB`, like
+/// `ENUM('Success', 'Failed')`.
+fn parse_enum_labels(sql_type: &str) -> Option<Vec<String>> {
+    let mut rest = sql_type.strip_prefix("ENUM(")?.strip_suffix(')')?.trim_start();
+    let mut labels = Vec::new();
+    while !rest.is_empty() {
+        rest = rest.strip_prefix('\'')?;
+        // Quotes in labels are escaped by doubling them
+        let mut label = String::new();
+        loop {
+            let (part, after) = rest.split_once('\'')?;
+            label.push_str(part);
+            if let Some(after) = after.strip_prefix('\'') {
+                label.push('\'');
+                rest = after;
+            } else {
+                rest = after;
+                break;
+            }
+        }
+        labels.push(label);
+        rest = rest.trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+    }
+    Some(labels)
+}
+
+/// Returns the labels the enum type named `type_name` is expected to have.
+fn expected_enum_labels(type_name: &str) -> Option<&'static [&'static str]> {
+    DataPoint::enum_types_labels()
+        .iter()
+        .find(|&&(name, _)| name == type_name)
+        .map(|&(_, labels)| labels)
+}
 
-    // Get current columns in the table
-    let mut stmt = conn
-        .prepare("SELECT * FROM information_schema.columns WHERE table_name = 'scout_entries'")?;
-    let existing_columns: Vec<String> = stmt
-        .query_map([], |row| row.get::<_, String>(3))?
+/// Checks if a column type reported by `DuckDB` is the expected SQL type.
+/// Columns of enum types are reported with their labels instead of the type
+/// name, so the labels are compared.
+fn is_same_sql_type(current_type: &str, sql_type: &str) -> bool {
+    current_type.eq_ignore_ascii_case(sql_type)
+        || parse_enum_labels(current_type)
+            .zip(expected_enum_labels(sql_type))
+            .is_some_and(|(labels, expected)| labels == expected)
+}
+
+/// Reads the labels of an existing enum type.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn enum_type_labels(conn: &Connection, type_name: &str) -> duckdb::Result<Vec<String>> {
+    conn.prepare(&format!(
+        "SELECT CAST(label AS VARCHAR) FROM (SELECT unnest(enum_range(NULL::{type_name})) AS label)"
+    ))?
+    .query_map([], |row| row.get(0))?
+    .collect()
+}
+
+/// Computes the migrations creating the enum types used by `DataPoint`, if they
+/// don't exist yet, and recreating the ones whose labels changed. They have to
+/// be applied before the columns using them are created.
+///
+/// A type can't be altered, so the columns using a changed type are converted
+/// to text while it is recreated, then back to the new type. Values whose label
+/// was removed are left missing for nullable fields, and replaced with the
+/// first label otherwise. The converted columns are updated in
+/// `existing_columns`.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn plan_enum_type_migrations(
+    conn: &Connection,
+    existing_columns: &mut [(String, String)],
+) -> duckdb::Result<Vec<PendingMigration>> {
+    let table = entries_table();
+    let mut migrations = Vec::new();
+    for (type_name, sql) in DataPoint::enum_types_sql() {
+        let exists: bool = conn.query_row(
+            "SELECT count(*) > 0 FROM duckdb_types() WHERE type_name = ?",
+            [type_name],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            migrations.push(PendingMigration::new(
+                format!("Create the {type_name} enum type"),
+                sql,
+            ));
+            continue;
+        }
+
+        let labels = enum_type_labels(conn, type_name)?;
+        let expected = expected_enum_labels(type_name).unwrap_or_default();
+        if labels == expected {
+            continue;
+        }
+
+        let columns = existing_columns
+            .iter_mut()
+            .filter(|column| parse_enum_labels(&column.1).as_ref() == Some(&labels))
+            .collect::<Vec<_>>();
+        for column in &columns {
+            let column_name = &column.0;
+            migrations.push(PendingMigration::new(
+                format!("Store column {column_name} as text"),
+                format!("ALTER TABLE {table} ALTER COLUMN {column_name} TYPE VARCHAR"),
+            ));
+        }
+        migrations.extend([
+            PendingMigration::new(
+                format!("Drop the {type_name} enum type"),
+                format!("DROP TYPE {type_name}"),
+            ),
+            PendingMigration::new(
+                format!(
+                    "Recreate the {type_name} enum type with the labels {}",
+                    expected.join(", ")
+                ),
+                sql,
+            ),
+        ]);
+        let first_label = expected.first().map_or_else(
+            || "NULL".to_owned(),
+            |label| format!("'{}'", label.replace('\'', "''")),
+        );
+        for column in columns {
+            let column_name = &column.0;
+            let converted = if DataPoint::is_field_nullable(column_name) {
+                format!("TRY_CAST({column_name} AS {type_name})")
+            } else {
+                format!("COALESCE(TRY_CAST({column_name} AS {type_name}), {first_label})")
+            };
+            migrations.push(PendingMigration::new(
+                format!("Store column {column_name} as {type_name}"),
+                format!(
+                    "ALTER TABLE {table} ALTER COLUMN {column_name} TYPE {type_name} USING {converted}"
+                ),
+            ));
+            type_name.clone_into(&mut column.1);
+        }
+    }
+
+    Ok(migrations)
+}
+
+/// Computes the migrations needed for the database schema to match the
+/// expected structure, without applying them.
+///
+/// Columns for new fields are added, columns of fields declared with
+/// `#[previously(old_name)]` in `define_struct!` are renamed, and columns of
+/// fields declared with `#[previously(name: OldType)]` are converted to the new
+/// type, keeping the existing data. Values that can't be converted are
+/// replaced with the default value of the new type, or left missing for
+/// nullable fields.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub fn plan_migrations(conn: &Connection) -> duckdb::Result<Vec<PendingMigration>> {
+    // Get current columns and their types in the table
+    let table = entries_table();
+    let mut existing_columns: Vec<(String, String)> = conn
+        .prepare(
+            "SELECT column_name, data_type FROM information_schema.columns WHERE table_name = ?",
+        )?
+        .query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))?
         .collect::<Result<Vec<_>, _>>()?;
 
-    // Get expected columns from DataPoint's metadata
+    let mut migrations = plan_enum_type_migrations(conn, &mut existing_columns)?;
 
+    if existing_columns.is_empty() {
+        migrations.extend([
+            PendingMigration::new(
+                format!("Create the {table} id sequence"),
+                DataPoint::get_create_sequence_sql(table),
+            ),
+            PendingMigration::new(
+                format!("Create the {table} table"),
+                DataPoint::get_create_table_sql(table),
+            ),
+            deleted_at_migration(),
+        ]);
+        migrations.extend(plan_season_migrations(&existing_columns));
+        migrations.extend(plan_audit_migrations(conn)?);
+        migrations.extend(plan_tables_migrations(conn)?);
+        return Ok(migrations);
+    }
+
+    let existing_type = |existing_columns: &[(String, String)], column_name: &str| {
+        existing_columns
+            .iter()
+            .find(|&(name, _)| name == column_name)
+            .map(|(_, sql_type)| sql_type.clone())
+    };
+
+    // Get expected columns from DataPoint's metadata
     for &(column_name, data_type) in DataPoint::field_metadata() {
-        if !existing_columns.contains(&(*column_name).to_owned()) {
-            let sql_type = match data_type {
-                DataTypeName::U16 => "USMALLINT",
-                DataTypeName::U32 => "UINTEGER",
-                DataTypeName::U64 => "UBIGINT",
-                DataTypeName::I16 => "SMALLINT",
-                DataTypeName::I32 => "INTEGER",
-                DataTypeName::I64 => "BIGINT",
-                DataTypeName::String => "VARCHAR",
-                DataTypeName::Bool => "BOOLEAN",
-                DataTypeName::Float => "REAL",
-            };
-            let default_val = match data_type {
-                DataTypeName::U16
-                | DataTypeName::U32
-                | DataTypeName::U64
-                | DataTypeName::I16
-                | DataTypeName::I32
-                | DataTypeName::I64 => "DEFAULT 0",
-                DataTypeName::String => "DEFAULT ''",
-                DataTypeName::Bool => "DEFAULT FALSE",
-                DataTypeName::Float => "DEFAULT 0.0",
+        let sql_type = DataPoint::get_field_sql_type(column_name)
+            .expect("Every field should have an SQL type");
+        let nullable = DataPoint::is_field_nullable(column_name);
+        let default_val = field_default_value(column_name, data_type);
+        let mut history = DataPoint::field_history()
+            .iter()
+            .filter(|&&(name, _, _)| name == column_name);
+
+        let current_type = if let Some(current_type) = existing_type(&existing_columns, column_name)
+        {
+            current_type
+        } else if let Some(&(_, previous_name, previous_type)) = history
+            .clone()
+            .rev()
+            .find(|&&(_, previous_name, _)| {
+                existing_type(&existing_columns, previous_name).is_some()
+            })
+        {
+            // The field has been renamed, carry the old column over
+            let current_type = existing_type(&existing_columns, previous_name)
+                .expect("Previous column should exist");
+            if let Some(previous_type) = previous_type
+                && previous_type != current_type
+            {
+                tracing::warn!(
+                    "Not renaming column {previous_name} to {column_name}: expected type {previous_type}, found {current_type}"
+                );
+                continue;
+            }
+
+            migrations.push(PendingMigration::new(
+                format!("Rename column {previous_name} to {column_name}"),
+                format!("ALTER TABLE {table} RENAME COLUMN {previous_name} TO {column_name}"),
+            ));
+            if let Some(column) = existing_columns
+                .iter_mut()
+                .find(|(name, _)| name == previous_name)
+            {
+                column.0 = column_name.to_owned();
+            }
+            current_type
+        } else {
+            // Existing rows of nullable fields are left missing
+            let default_clause = if nullable {
+                String::new()
+            } else {
+                format!(" DEFAULT {default_val}")
             };
+            migrations.push(PendingMigration::new(
+                format!("Add column {column_name}"),
+                format!("ALTER TABLE {table} ADD COLUMN {column_name} {sql_type}{default_clause}"),
+            ));
+            continue;
+        };
+
+        if is_same_sql_type(&current_type, sql_type) {
+            continue;
+        }
 
-            let alter_sql = format!(
-                "ALTER TABLE scout_entries ADD COLUMN {column_name} {sql_type} {default_val}",
+        // Only convert columns whose type change has been declared, to avoid
+        // silently losing data
+        let type_change_declared = history.any(|&(_, previous_name, previous_type)| {
+            previous_type == Some(current_type.as_str())
+                && (previous_name == column_name
+                    || existing_type(&existing_columns, previous_name).is_none())
+        });
+        if !type_change_declared {
+            tracing::warn!(
+                "Column {column_name} has type {current_type} but {sql_type} is expected. Declare the previous type with #[previously({column_name}: OldType)] to convert it"
             );
-            conn.execute(&alter_sql, [])?;
+            continue;
         }
+
+        let converted = if nullable {
+            format!("TRY_CAST({column_name} AS {sql_type})")
+        } else {
+            format!("COALESCE(TRY_CAST({column_name} AS {sql_type}), {default_val})")
+        };
+        migrations.push(PendingMigration::new(
+            format!("Change the type of column {column_name} from {current_type} to {sql_type}"),
+            format!(
+                "ALTER TABLE {table} ALTER COLUMN {column_name} TYPE {sql_type} USING {converted}",
+            ),
+        ));
+    }
+
+    if existing_type(&existing_columns, "deleted_at").is_none() {
+        migrations.push(deleted_at_migration());
+    }
+    migrations.extend(plan_season_migrations(&existing_columns));
+    migrations.extend(plan_audit_migrations(conn)?);
+    migrations.extend(plan_tables_migrations(conn)?);
+
+    Ok(migrations)
+}
+
+/// Computes the migrations adding the columns of the fields added by the season
+/// schema. They are nullable, so existing rows are left missing.
+///
+/// The columns are not part of the `schema_checksum`: they only depend on the
+/// schema file, which can change without bumping `SCHEMA_VERSION`. Columns of
+/// fields removed from the file are kept with their data.
+fn plan_season_migrations(existing_columns: &[(String, String)]) -> Vec<PendingMigration> {
+    let table = entries_table();
+    season_schema()
+        .extra_fields()
+        .filter_map(|field| {
+            let sql_type = field.sql_type();
+            match existing_columns.iter().find(|&(name, _)| *name == field.name) {
+                None => Some(PendingMigration::new(
+                    format!("Add column {} from the season schema", field.name),
+                    format!("ALTER TABLE {table} ADD COLUMN {} {sql_type}", field.name),
+                )),
+                Some((_, current_type)) if !current_type.eq_ignore_ascii_case(sql_type) => {
+                    tracing::warn!(
+                        "Column {} has type {current_type} but the season schema expects {sql_type}",
+                        field.name
+                    );
+                    None
+                }
+                Some(_) => None,
+            }
+        })
+        .collect()
+}
+
+/// Returns the migration adding the `deleted_at` column to the entries table.
+fn deleted_at_migration() -> PendingMigration {
+    PendingMigration::new(
+        "Add column deleted_at".to_owned(),
+        deleted_at_sql(entries_table()),
+    )
+}
+
+/// Computes the migrations creating the audit table, if it doesn't exist yet.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn plan_audit_migrations(conn: &Connection) -> duckdb::Result<Vec<PendingMigration>> {
+    let table = audit_table_name(entries_table());
+    let exists: bool = conn.query_row(
+        "SELECT count(*) > 0 FROM information_schema.tables WHERE table_name = ?",
+        [&table],
+        |row| row.get(0),
+    )?;
+    if exists {
+        return Ok(Vec::new());
+    }
+
+    Ok(vec![
+        PendingMigration::new(
+            format!("Create the {table} id sequence"),
+            audit_sequence_sql(&table),
+        ),
+        PendingMigration::new(format!("Create the {table} table"), audit_table_sql(&table)),
+    ])
+}
+
+/// Computes the migration creating a table that doesn't depend on the
+/// season, if it doesn't exist yet.
+///
+/// # Arguments
+///
+/// * `table` - The name of the table.
+/// * `sql` - The SQL creating the table.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn plan_table_migrations(
+    conn: &Connection,
+    table: &str,
+    sql: &str,
+) -> duckdb::Result<Vec<PendingMigration>> {
+    let exists: bool = conn.query_row(
+        "SELECT count(*) > 0 FROM information_schema.tables WHERE table_name = ?",
+        [table],
+        |row| row.get(0),
+    )?;
+    if exists {
+        return plan_team_column_migrations(conn, table, sql);
     }
+
+    Ok(vec![PendingMigration::new(
+        format!("Create the {table} table"),
+        sql.to_owned(),
+    )])
+}
+
+/// Computes the migrations storing the teams of a table as text, so they can
+/// have a letter like `254B`. The table is rebuilt, since the teams are part
+/// of its primary key.
+///
+/// # Arguments
+///
+/// * `table` - The name of the table.
+/// * `sql` - The SQL creating the table.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn plan_team_column_migrations(
+    conn: &Connection,
+    table: &str,
+    sql: &str,
+) -> duckdb::Result<Vec<PendingMigration>> {
+    let current_type: Option<String> = conn
+        .query_row(
+            "SELECT data_type FROM information_schema.columns WHERE table_name = ? AND column_name = 'team_number'",
+            [table],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if current_type.is_none_or(|current_type| is_same_sql_type(&current_type, "VARCHAR")) {
+        return Ok(Vec::new());
+    }
+
+    let old_table = format!("{table}_old");
+    Ok(vec![
+        PendingMigration::new(
+            format!("Copy the {table} table to {old_table}"),
+            format!("CREATE TABLE {old_table} AS SELECT * FROM {table}"),
+        ),
+        PendingMigration::new(
+            format!("Drop the {table} table"),
+            format!("DROP TABLE {table}"),
+        ),
+        PendingMigration::new(
+            format!("Create the {table} table with text teams"),
+            sql.to_owned(),
+        ),
+        PendingMigration::new(
+            format!("Copy {old_table} back to the {table} table"),
+            format!(
+                "INSERT INTO {table} SELECT * REPLACE (CAST(team_number AS VARCHAR) AS team_number) FROM {old_table}"
+            ),
+        ),
+        PendingMigration::new(
+            format!("Drop the {old_table} table"),
+            format!("DROP TABLE {old_table}"),
+        ),
+    ])
+}
+
+/// The tables that don't depend on the season, with the SQL creating them.
+const TABLES: &[(&str, &str)] = &[
+    ("pick_list", PICK_LIST_TABLE_SQL),
+    ("alliance_selection", ALLIANCE_SELECTION_TABLE_SQL),
+    ("tba_cache", TBA_CACHE_TABLE_SQL),
+    ("team_names", TEAM_NAMES_TABLE_SQL),
+];
+
+/// Computes the migrations creating the tables of `TABLES` that don't exist
+/// yet.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn plan_tables_migrations(conn: &Connection) -> duckdb::Result<Vec<PendingMigration>> {
+    let mut migrations = Vec::new();
+    for &(table, sql) in TABLES {
+        migrations.extend(plan_table_migrations(conn, table, sql)?);
+    }
+    Ok(migrations)
+}
+
+/// Lists the migrations that would be applied by `migrate_db`.
+///
+/// # Returns
+///
+/// The latest migration applied to the database, if any, and the pending
+/// migrations.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn pending_migrations(
+) -> duckdb::Result<(Option<AppliedMigration>, Vec<PendingMigration>)> {
+    let conn = get_conn().await;
+    let latest = latest_migration(&conn)?;
+    let pending = plan_migrations(&conn)?;
+    drop(conn);
+
+    Ok((latest, pending))
+}
+
+/// Migrates the database schema to match the expected structure.
+///
+/// The pending migrations are applied in a single transaction and recorded in
+/// the `schema_migrations` table with the `SCHEMA_VERSION` and checksum of this
+/// build.
+///
+/// # Returns
+///
+/// A `Result<()>` indicating the success or failure of the operation.
+///
+/// # Errors
+///
+/// Returns an error if the database schema is newer than the one of this
+/// build, if it was created by a build with the same schema version but a
+/// different schema, or if there is an issue with the database operations.
+pub async fn migrate_db() -> anyhow::Result<()> {
+    let mut conn = get_conn().await;
+    migrate(&mut conn)?;
     drop(conn);
 
     Ok(())
 }
 
-/// Initializes the database connection and schema.
+/// Migrates the schema of a database, see `migrate_db`.
+///
+/// # Errors
+///
+/// Returns an error if the database schema is newer than the one of this
+/// build, if it was created by a build with the same schema version but a
+/// different schema, or if there is an issue with the database operations.
+fn migrate(conn: &mut Connection) -> anyhow::Result<()> {
+    let latest = latest_migration(conn)?;
+    check_schema_version(latest.as_ref())?;
+
+    let migrations = plan_migrations(conn)?;
+    let up_to_date = latest
+        .as_ref()
+        .is_some_and(|latest| latest.version == SCHEMA_VERSION);
+    if migrations.is_empty() && up_to_date {
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+    tx.execute(SCHEMA_MIGRATIONS_TABLE_SQL, [])?;
+    for migration in &migrations {
+        tracing::info!("Applying migration: {}", migration.description);
+        tx.execute(&migration.sql, [])?;
+    }
+    if !up_to_date {
+        let description = if migrations.is_empty() {
+            "No changes".to_owned()
+        } else {
+            migrations
+                .iter()
+                .map(|migration| migration.description.as_str())
+                .collect::<Vec<_>>()
+                .join("; ")
+        };
+        tx.execute(
+            "INSERT INTO schema_migrations (version, checksum, description) VALUES (?, ?, ?)",
+            duckdb::params![SCHEMA_VERSION, schema_checksum(), description],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Opens the database connection without touching the schema.
 ///
 /// # Returns
 ///
@@ -93,43 +843,115 @@ pub async fn migrate_db() -> duckdb::Result<()> {
 /// # Panics
 ///
 /// Panics if the database is already initialized.
-pub async fn init_db() -> duckdb::Result<()> {
+pub fn open_db() -> duckdb::Result<()> {
     let conn = Connection::open("scouting_data.db")?;
 
     conn.execute("INSTALL excel;", [])?;
     conn.execute("LOAD excel;", [])?;
 
-    conn.execute(
-        "CREATE SEQUENCE IF NOT EXISTS scout_entries_id_seq START 1;",
-        [],
-    )?;
+    assert!(DB.set(Mutex::new(conn)).is_ok(), "DB already initialized");
 
-    conn.execute(DataPoint::get_create_table_sql(), [])?;
+    Ok(())
+}
 
-    assert!(DB.set(Mutex::new(conn)).is_ok(), "DB already initialized");
+/// Initializes the database connection and schema.
+///
+/// # Returns
+///
+/// A `Result<()>` indicating the success or failure of the operation.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations or if
+/// the database schema can't be migrated.
+///
+/// # Panics
+///
+/// Panics if the database is already initialized.
+pub async fn init_db() -> anyhow::Result<()> {
+    open_db()?;
 
     migrate_db().await
 }
 
-/// Retrieves all data points from the database.
+/// Returns the columns of the entries table expected by `ScoutEntry::map_entry`
+/// and `DataPoint::map_datapoint`: the id followed by the `DataPoint` fields
+/// and the fields added by the season schema.
+///
+/// Selecting the columns explicitly instead of using `*` keeps the mapping
+/// correct when the table has columns that are not part of the schema.
+#[must_use]
+pub fn entry_columns() -> String {
+    let mut columns = format!("id, {}", DataPoint::get_select_list());
+    for field in season_schema().extra_fields() {
+        columns.push_str(", ");
+        columns.push_str(&field.name);
+    }
+    columns
+}
+
+/// Retrieves the data points recorded at an event from the database.
+///
+/// Deleted entries are not returned.
+///
+/// # Arguments
+///
+/// * `event_key` - The TBA key of the event to filter by, or `None` to return
+///   the data points of all events.
 ///
 /// # Returns
 ///
-/// A `Result` containing a vector of `DataPoint` or an `anyhow::Error`.
+/// A `Result` containing a vector of `ScoutEntry` or an `anyhow::Error`.
 ///
 /// # Errors
 ///
 /// Returns an error if there is an issue with the database operations.
-pub async fn get_data() -> Result<Vec<DataPoint>, anyhow::Error> {
+pub async fn get_data(event_key: Option<&str>) -> Result<Vec<ScoutEntry>, anyhow::Error> {
     let conn = get_conn().await;
-    let mut stmt = conn.prepare("SELECT * FROM scout_entries")?;
-    let entry_iter = stmt.query_map([], DataPoint::map_datapoint)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM {} WHERE deleted_at IS NULL AND (?1 IS NULL OR event_key = ?1)",
+        entry_columns(),
+        entries_table()
+    ))?;
+    let entry_iter = stmt.query_map([event_key], ScoutEntry::map_entry)?;
 
-    let data_points = entry_iter.collect::<Result<Vec<DataPoint>, _>>()?;
+    let entries = entry_iter.collect::<Result<Vec<ScoutEntry>, _>>()?;
 
     drop(conn);
 
-    Ok(data_points)
+    Ok(entries)
+}
+
+/// Computes the metrics of every team with scouting data.
+///
+/// # Arguments
+///
+/// * `event_key` - The TBA key of the event to filter by, or `None` to use the
+///   data points of all events.
+///
+/// # Returns
+///
+/// A `Result` containing the team numbers and their metrics, sorted by team
+/// number, or an `anyhow::Error`.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn get_team_metrics(
+    event_key: Option<&str>,
+) -> Result<Vec<(TeamNumber, Vec<MetricValue>)>, anyhow::Error> {
+    let mut team_data: BTreeMap<TeamNumber, Vec<ScoutEntry>> = BTreeMap::new();
+    for entry in get_data(event_key).await? {
+        team_data
+            .entry(entry.data.team_number)
+            .or_default()
+            .push(entry);
+    }
+
+    Ok(team_data
+        .into_iter()
+        .map(|(team_number, entries)| (team_number, team_metrics(&entries)))
+        .collect())
 }
 
 /// Extracts a boolean value from an optional string.
@@ -147,11 +969,112 @@ pub fn extract_checkbox(value: Option<String>) -> bool {
     value.is_some_and(|x| x == "on")
 }
 
+/// Retrieves the data of an entry that has not been deleted, with the values
+/// of the fields added by the season schema.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn fetch_entry(conn: &Connection, id: i32) -> duckdb::Result<Option<EntryData>> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM {} WHERE id = ? AND deleted_at IS NULL",
+            entry_columns(),
+            entries_table()
+        ),
+        [id],
+        ScoutEntry::map_entry,
+    )
+    .optional()
+    .map(|entry| entry.map(EntryData::from))
+}
+
+/// Writes the values of the fields added by the season schema to an entry.
+/// Fields without a value in `extra` are left unchanged.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn write_extra(
+    conn: &Connection,
+    id: i32,
+    extra: &BTreeMap<String, DataType>,
+) -> duckdb::Result<()> {
+    for field in season_schema().extra_fields() {
+        if let Some(value) = extra.get(&field.name) {
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET {} = ? WHERE id = ?",
+                    entries_table(),
+                    field.name
+                ),
+                duckdb::params![value, id],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces the data of an entry without recording the change.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn write_entry(conn: &Connection, id: i32, entry: &EntryData) -> duckdb::Result<()> {
+    conn.execute(
+        &DataPoint::get_update_sql(entries_table()),
+        entry.data.to_sql_with_id(&id),
+    )?;
+    write_extra(conn, id, &entry.extra)
+}
+
+/// Records a change made to an entry in the audit table.
+///
+/// # Arguments
+///
+/// * `conn` - The connection or transaction the change was made with.
+/// * `entry_id` - The id of the changed entry.
+/// * `action` - The kind of change.
+/// * `old` - The entry before the change, if it was visible.
+/// * `new` - The entry after the change, if it is visible.
+/// * `actor` - The name of the scout who made the change.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+fn record_audit(
+    conn: &Connection,
+    entry_id: i32,
+    action: AuditAction,
+    old: Option<&EntryData>,
+    new: Option<&EntryData>,
+    actor: &str,
+) -> duckdb::Result<()> {
+    let to_json = |entry: Option<&EntryData>| {
+        entry.map(|entry| serde_json::to_string(entry).expect("EntryData should be serializable"))
+    };
+
+    conn.execute(
+        &format!(
+            "INSERT INTO {} (entry_id, action, old_row, new_row, actor) VALUES (?, ?, ?, ?, ?)",
+            audit_table_name(entries_table())
+        ),
+        duckdb::params![entry_id, action.to_string(), to_json(old), to_json(new), actor],
+    )?;
+
+    Ok(())
+}
+
 /// Inserts form data into the `SQLite` database.
 ///
+/// The insert is recorded in the audit table, with the scout name of the
+/// entry as the actor.
+///
 /// # Arguments
 ///
 /// * `data_point` - The `DataPoint` to be inserted.
+/// * `extra` - The values of the fields added by the season schema.
 ///
 /// # Returns
 ///
@@ -164,15 +1087,736 @@ pub fn extract_checkbox(value: Option<String>) -> bool {
 /// # Panics
 ///
 /// Panics if the database is not initialized.
-pub async fn insert_form_data(data_point: DataPoint) -> duckdb::Result<()> {
+pub async fn insert_form_data(
+    data_point: DataPoint,
+    extra: &BTreeMap<String, DataType>,
+) -> duckdb::Result<()> {
     let db = DB.get().expect("Database not initialized");
-    let conn = db.lock().await;
+    let mut conn = db.lock().await;
+
+    let tx = conn.transaction()?;
+    let id: i32 = tx.query_row(
+        &DataPoint::get_insert_sql(entries_table()),
+        data_point.to_sql(),
+        |row| row.get(0),
+    )?;
+    write_extra(&tx, id, extra)?;
+    let new = EntryData {
+        extra: extra.clone(),
+        data: data_point,
+    };
+    record_audit(
+        &tx,
+        id,
+        AuditAction::Insert,
+        None,
+        Some(&new),
+        &new.data.name,
+    )?;
+    tx.commit()?;
+
+    drop(conn);
+
+    Ok(())
+}
+
+/// Replaces the data of an entry in the database.
+///
+/// The change is recorded in the audit table.
+///
+/// # Arguments
+///
+/// * `id` - The id of the entry to update.
+/// * `entry` - The new data of the entry. Fields added by the season schema
+///   without a value are left unchanged.
+/// * `actor` - The name of the scout making the change.
+///
+/// # Returns
+///
+/// A `duckdb::Result<bool>` indicating whether an entry with the given id
+/// exists and has not been deleted.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn update_entry(id: i32, entry: EntryData, actor: &str) -> duckdb::Result<bool> {
+    let mut conn = get_conn().await;
+
+    let tx = conn.transaction()?;
+    let Some(old) = fetch_entry(&tx, id)? else {
+        return Ok(false);
+    };
+    write_entry(&tx, id, &entry)?;
+    let new = fetch_entry(&tx, id)?;
+    record_audit(
+        &tx,
+        id,
+        AuditAction::Update,
+        Some(&old),
+        new.as_ref(),
+        actor,
+    )?;
+    tx.commit()?;
+
+    drop(conn);
+
+    Ok(true)
+}
+
+/// Soft deletes an entry from the database by setting its `deleted_at`
+/// column. Deleted entries are hidden from `get_data` and can be restored with
+/// `restore_entry`.
+///
+/// The change is recorded in the audit table.
+///
+/// # Arguments
+///
+/// * `id` - The id of the entry to delete.
+/// * `actor` - The name of the scout making the change.
+///
+/// # Returns
+///
+/// A `duckdb::Result<bool>` indicating whether an entry with the given id
+/// existed and had not been deleted yet.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn delete_entry(id: i32, actor: &str) -> duckdb::Result<bool> {
+    let mut conn = get_conn().await;
+
+    let tx = conn.transaction()?;
+    let Some(old) = fetch_entry(&tx, id)? else {
+        return Ok(false);
+    };
+    tx.execute(
+        &format!(
+            "UPDATE {} SET deleted_at = current_timestamp WHERE id = ?",
+            entries_table()
+        ),
+        [id],
+    )?;
+    record_audit(&tx, id, AuditAction::Delete, Some(&old), None, actor)?;
+    tx.commit()?;
+
+    drop(conn);
+
+    Ok(true)
+}
+
+/// Undoes the change recorded in the audit table with the given id.
+///
+/// A deleted entry is made visible again, and an updated entry gets back the
+/// data it had before the update, including the values of the fields added by
+/// the season schema. The restore is itself recorded in the audit table.
+///
+/// # Arguments
+///
+/// * `audit_id` - The id of the change in the audit table.
+/// * `actor` - The name of the scout making the change.
+///
+/// # Errors
+///
+/// Returns an error if the change doesn't exist or can't be undone, if the
+/// entry is not in the state the change left it in, if the restored data
+/// doesn't pass the constraints of the season schema, or if there is an issue
+/// with the database operations.
+pub async fn restore_entry(audit_id: i32, actor: &str) -> anyhow::Result<()> {
+    let mut conn = get_conn().await;
+
+    let tx = conn.transaction()?;
+    let Some(record) = tx
+        .query_row(
+            &format!(
+                "SELECT id, entry_id, action, old_row, new_row, actor, CAST(changed_at AS VARCHAR) FROM {} WHERE id = ?",
+                audit_table_name(entries_table())
+            ),
+            [audit_id],
+            map_audit_record,
+        )
+        .optional()?
+    else {
+        anyhow::bail!("Change {audit_id} does not exist");
+    };
+    let entry_id = record.entry_id;
+
+    match record.action {
+        AuditAction::Delete => {
+            let restored = tx.execute(
+                &format!(
+                    "UPDATE {} SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+                    entries_table()
+                ),
+                [entry_id],
+            )?;
+            if restored == 0 {
+                anyhow::bail!("Entry {entry_id} is not deleted");
+            }
+            let new = fetch_entry(&tx, entry_id)?;
+            record_audit(
+                &tx,
+                entry_id,
+                AuditAction::Restore,
+                None,
+                new.as_ref(),
+                actor,
+            )?;
+        }
+        AuditAction::Update => {
+            let Some(old) = record.old else {
+                anyhow::bail!("Change {audit_id} has no previous data");
+            };
+            let Some(current) = fetch_entry(&tx, entry_id)? else {
+                anyhow::bail!("Entry {entry_id} has been deleted, restore it first");
+            };
+            // The constraints may have changed since the data was written
+            season_schema()
+                .validate(&old.data, &old.extra)
+                .map_err(|err| anyhow::anyhow!("Change {audit_id} can't be undone: {err}"))?;
+            write_entry(&tx, entry_id, &old)?;
+            let new = fetch_entry(&tx, entry_id)?;
+            record_audit(
+                &tx,
+                entry_id,
+                AuditAction::Restore,
+                Some(&current),
+                new.as_ref(),
+                actor,
+            )?;
+        }
+        AuditAction::Insert | AuditAction::Restore => {
+            anyhow::bail!("Change {audit_id} can't be undone, edit or delete the entry instead");
+        }
+    }
+    tx.commit()?;
+
+    drop(conn);
+
+    Ok(())
+}
+
+/// Maps a row of the audit table to a new `AuditRecord`.
+///
+/// The columns are expected in the order of the `AuditRecord` fields, with
+/// `changed_at` cast to `VARCHAR`.
+///
+/// # Errors
+///
+/// Returns an error if a column can't be converted to its field type.
+fn map_audit_record(row: &duckdb::Row<'_>) -> duckdb::Result<AuditRecord> {
+    let conversion_error = |idx: usize, err: Box<dyn core::error::Error + Send + Sync>| {
+        duckdb::Error::FromSqlConversionFailure(idx, duckdb::types::Type::Text, err)
+    };
+    let parse_row = |idx: usize| -> duckdb::Result<Option<EntryData>> {
+        row.get::<_, Option<String>>(idx)?
+            .map(|json| serde_json::from_str(&json).map_err(|err| conversion_error(idx, err.into())))
+            .transpose()
+    };
+
+    let action: String = row.get(2)?;
+    Ok(AuditRecord {
+        id: row.get(0)?,
+        entry_id: row.get(1)?,
+        action: AuditAction::from_name(&action).ok_or_else(|| {
+            conversion_error(2, format!("Unknown audit action {action}").into())
+        })?,
+        old: parse_row(3)?,
+        new: parse_row(4)?,
+        actor: row.get(5)?,
+        changed_at: row.get(6)?,
+    })
+}
+
+/// Retrieves the changes made to the entries of an event, most recent first.
+///
+/// # Arguments
+///
+/// * `event_key` - The TBA key of the event to filter by, or `None` to return
+///   the changes of all events.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `AuditRecord` or an `anyhow::Error`.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn get_audit_log(event_key: Option<&str>) -> Result<Vec<AuditRecord>, anyhow::Error> {
+    let conn = get_conn().await;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT a.id, a.entry_id, a.action, a.old_row, a.new_row, a.actor, CAST(a.changed_at AS VARCHAR) FROM {} a JOIN {} e ON e.id = a.entry_id WHERE ?1 IS NULL OR e.event_key = ?1 ORDER BY a.id DESC",
+        audit_table_name(entries_table()),
+        entries_table()
+    ))?;
+    let record_iter = stmt.query_map([event_key], map_audit_record)?;
+
+    let records = record_iter.collect::<Result<Vec<AuditRecord>, _>>()?;
+
+    drop(conn);
+
+    Ok(records)
+}
+
+/// Reads the pick list of an event.
+///
+/// # Errors
+///
+/// Returns an error if a tier is unknown or if there is an issue with the
+/// database operations.
+fn read_pick_list(conn: &Connection, event_key: &str) -> duckdb::Result<Vec<PickListEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT team_number, tier, comment FROM pick_list WHERE event_key = ? ORDER BY position",
+    )?;
+    let entries = stmt
+        .query_map([event_key], |row| {
+            let tier: String = row.get(1)?;
+            Ok(PickListEntry {
+                team_number: row.get(0)?,
+                tier: PickTier::from_name(&tier).ok_or_else(|| {
+                    duckdb::Error::FromSqlConversionFailure(
+                        1,
+                        duckdb::types::Type::Text,
+                        format!("Unknown pick list tier {tier}").into(),
+                    )
+                })?,
+                comment: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// Retrieves the pick list of an event, grouped by tier.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn get_pick_list(event_key: &str) -> duckdb::Result<Vec<PickListEntry>> {
+    let conn = get_conn().await;
+    let mut entries = read_pick_list(&conn, event_key)?;
+    drop(conn);
+
+    entries.sort_by_key(|entry| entry.tier);
+    Ok(entries)
+}
+
+/// Applies a change to the pick list of an event.
+///
+/// The list is read and written back in a single transaction, so changes made
+/// at the same time are applied one after the other.
+///
+/// # Errors
+///
+/// Returns an error if the change can't be applied to the list or if there is
+/// an issue with the database operations.
+pub async fn change_pick_list(event_key: &str, change: PickListChange) -> anyhow::Result<()> {
+    let mut conn = get_conn().await;
+
+    let tx = conn.transaction()?;
+    let mut entries = read_pick_list(&tx, event_key)?;
+    entries.sort_by_key(|entry| entry.tier);
+    change.apply(&mut entries)?;
+
+    tx.execute("DELETE FROM pick_list WHERE event_key = ?", [event_key])?;
+    for (position, entry) in entries.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO pick_list (event_key, team_number, tier, position, comment) VALUES (?, ?, ?, ?, ?)",
+            duckdb::params![
+                event_key,
+                entry.team_number,
+                entry.tier.name(),
+                u32::try_from(position).expect("The pick list should fit in a u32"),
+                entry.comment,
+            ],
+        )?;
+    }
+    tx.commit()?;
+
+    drop(conn);
+
+    Ok(())
+}
+
+/// Reads the picks and declines marked during the alliance selection of an
+/// event, in the order they were marked.
+fn read_selection_marks(conn: &Connection, event_key: &str) -> duckdb::Result<Vec<SelectionMark>> {
+    let mut stmt = conn.prepare(
+        "SELECT team_number, alliance FROM alliance_selection WHERE event_key = ? ORDER BY marked_at",
+    )?;
+    let marks = stmt
+        .query_map([event_key], |row| {
+            Ok(SelectionMark {
+                team_number: row.get(0)?,
+                status: row
+                    .get::<_, Option<u8>>(1)?
+                    .map_or(SelectionStatus::Declined, SelectionStatus::Picked),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(marks)
+}
+
+/// Retrieves the picks and declines marked during the alliance selection of an
+/// event, in the order they were marked.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn get_selection_marks(event_key: &str) -> duckdb::Result<Vec<SelectionMark>> {
+    let conn = get_conn().await;
+    let marks = read_selection_marks(&conn, event_key)?;
+    drop(conn);
+
+    Ok(marks)
+}
+
+/// Marks what happened to a team during the alliance selection of an event.
+///
+/// The marks are read and written in a single transaction, and the mark is
+/// checked by applying it to them with `mark_team`.
+///
+/// # Arguments
+///
+/// * `event_key` - The TBA key of the event.
+/// * `team_number` - The team to mark.
+/// * `status` - What happened to the team, or `None` to clear its mark.
+///
+/// # Errors
+///
+/// Returns an error if the mark is invalid or if there is an issue with the
+/// database operations.
+pub async fn mark_selection(
+    event_key: &str,
+    team_number: TeamNumber,
+    status: Option<SelectionStatus>,
+) -> anyhow::Result<()> {
+    let mut conn = get_conn().await;
+
+    let tx = conn.transaction()?;
+    let mut marks = read_selection_marks(&tx, event_key)?;
+    mark_team(&mut marks, team_number, status)?;
+
+    match status {
+        Some(status) => {
+            let alliance = match status {
+                SelectionStatus::Picked(alliance) => Some(alliance),
+                SelectionStatus::Declined => None,
+            };
+            tx.execute(
+                "INSERT OR REPLACE INTO alliance_selection (event_key, team_number, alliance) VALUES (?, ?, ?)",
+                duckdb::params![event_key, team_number, alliance],
+            )?;
+        }
+        None => {
+            tx.execute(
+                "DELETE FROM alliance_selection WHERE event_key = ? AND team_number = ?",
+                duckdb::params![event_key, team_number],
+            )?;
+        }
+    }
+    tx.commit()?;
+
+    drop(conn);
+
+    Ok(())
+}
+
+/// Retrieves the cached response of a path of the TBA API.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn get_cached_response(path: &str) -> duckdb::Result<Option<CachedResponse>> {
+    let conn = get_conn().await;
+    let cached = conn
+        .query_row(
+            "SELECT etag, body, fetched_at, max_age FROM tba_cache WHERE path = ?",
+            [path],
+            |row| {
+                Ok(CachedResponse {
+                    etag: row.get(0)?,
+                    body: row.get(1)?,
+                    fetched_at: row.get(2)?,
+                    max_age: row.get(3)?,
+                })
+            },
+        )
+        .optional()?;
+    drop(conn);
+
+    Ok(cached)
+}
+
+/// Stores the response of a path of the TBA API, replacing the cached one.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn store_cached_response(path: &str, response: &CachedResponse) -> duckdb::Result<()> {
+    let conn = get_conn().await;
+    conn.execute(
+        "INSERT OR REPLACE INTO tba_cache (path, etag, body, fetched_at, max_age) VALUES (?, ?, ?, ?, ?)",
+        duckdb::params![
+            path,
+            response.etag,
+            response.body,
+            response.fetched_at,
+            response.max_age,
+        ],
+    )?;
+    drop(conn);
 
-    let mut stmt = conn.prepare("INSERT INTO scout_entries (name, match_number, team_number, auto_algae, auto_coral, auto_leave, algae_clear, l1_coral, l2_coral, l3_coral, l4_coral, dropped_coral, algae_barge, algae_floor_hole, climb, defense_bot, notes) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")?;
+    Ok(())
+}
 
-    stmt.execute(data_point.to_sql())?;
+/// Retrieves the team names stored in the `team_names` table.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn get_team_names() -> duckdb::Result<BTreeMap<TeamNumber, String>> {
+    let conn = get_conn().await;
+    let mut stmt = conn.prepare("SELECT team_number, nickname FROM team_names")?;
+    let names = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<duckdb::Result<_>>()?;
+    drop(stmt);
+    drop(conn);
+
+    Ok(names)
+}
+
+/// Replaces the team names stored in the `team_names` table.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations.
+pub async fn store_team_names(names: &BTreeMap<TeamNumber, String>) -> duckdb::Result<()> {
+    let mut conn = get_conn().await;
+
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM team_names", [])?;
+    {
+        let mut stmt = tx.prepare("INSERT INTO team_names (team_number, nickname) VALUES (?, ?)")?;
+        for (team_number, nickname) in names {
+            stmt.execute(duckdb::params![team_number, nickname])?;
+        }
+    }
+    tx.commit()?;
 
     drop(conn);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use duckdb::Connection;
+
+    use super::{
+        migrate, plan_migrations, schema_checksum, PICK_LIST_TABLE_SQL,
+        SCHEMA_MIGRATIONS_TABLE_SQL,
+    };
+    use crate::{
+        data::{DataPoint, SCHEMA_VERSION},
+        game::reefscape::ClimbResult,
+        team::TeamNumber,
+    };
+
+    /// Reads the names and types of the columns of a table.
+    fn column_types(conn: &Connection, table: &str) -> Vec<(String, String)> {
+        conn.prepare(
+            "SELECT column_name, data_type FROM information_schema.columns WHERE table_name = ?",
+        )
+        .expect("The query should be valid")
+        .query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))
+        .expect("The columns should be read")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("The columns should be read")
+    }
+
+    /// Reads the versions and checksums recorded in `schema_migrations`.
+    fn applied_migrations(conn: &Connection) -> Vec<(u32, String)> {
+        conn.prepare("SELECT version, checksum FROM schema_migrations ORDER BY version")
+            .expect("The query should be valid")
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .expect("The migrations should be read")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("The migrations should be read")
+    }
+
+    /// Opens an in-memory database with the entries table declared with
+    /// `DataPoint`.
+    fn open_entries_table() -> Connection {
+        let conn = Connection::open_in_memory().expect("The database should open");
+        let table = DataPoint::table_name();
+        for (_, sql) in DataPoint::enum_types_sql() {
+            conn.execute_batch(&sql)
+                .expect("The enum types should be created");
+        }
+        conn.execute_batch(&DataPoint::get_create_sequence_sql(table))
+            .expect("The sequence should be created");
+        conn.execute_batch(&DataPoint::get_create_table_sql(table))
+            .expect("The table should be created");
+        conn
+    }
+
+    #[test]
+    fn upserts_entries_by_id() {
+        let conn = open_entries_table();
+        let table = DataPoint::table_name();
+        let upsert = DataPoint::get_upsert_sql(table);
+
+        let mut entry = DataPoint::empty();
+        conn.execute(&upsert, entry.to_sql_with_id(&7))
+            .expect("The entry should be inserted");
+        entry.notes = "Fixed the scoring".to_owned();
+        entry.l4_coral = 3;
+        conn.execute(&upsert, entry.to_sql_with_id(&7))
+            .expect("The entry should be replaced");
+
+        let rows = conn
+            .prepare(&format!(
+                "SELECT id, {} FROM {table}",
+                DataPoint::get_select_list()
+            ))
+            .expect("The query should be valid")
+            .query_map([], |row| {
+                Ok((row.get::<_, i32>(0)?, DataPoint::map_datapoint(row)?))
+            })
+            .expect("The entries should be read")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("The entries should be read");
+        assert_eq!(rows, vec![(7, entry)]);
+    }
+
+    #[test]
+    fn records_migrations_with_version_and_checksum() {
+        let mut conn = Connection::open_in_memory().expect("The database should open");
+
+        migrate(&mut conn).expect("A new database should be migrated");
+
+        assert_eq!(
+            applied_migrations(&conn),
+            vec![(SCHEMA_VERSION, schema_checksum())]
+        );
+    }
+
+    #[test]
+    fn migrating_twice_is_a_no_op() {
+        let mut conn = Connection::open_in_memory().expect("The database should open");
+        migrate(&mut conn).expect("A new database should be migrated");
+
+        assert_eq!(
+            plan_migrations(&conn).expect("The migrations should be planned"),
+            []
+        );
+        migrate(&mut conn).expect("A migrated database should be migrated again");
+        assert_eq!(applied_migrations(&conn).len(), 1);
+    }
+
+    #[test]
+    fn refuses_newer_schemas() {
+        let mut conn = Connection::open_in_memory().expect("The database should open");
+        conn.execute_batch(SCHEMA_MIGRATIONS_TABLE_SQL)
+            .expect("The migrations table should be created");
+        conn.execute(
+            "INSERT INTO schema_migrations (version, checksum, description) VALUES (?, ?, ?)",
+            duckdb::params![SCHEMA_VERSION + 1, "0000000000000000", "From a newer build"],
+        )
+        .expect("The migration should be recorded");
+
+        let error = migrate(&mut conn).expect_err("A newer schema should be refused");
+        assert!(error.to_string().contains("newer"), "{error}");
+        assert_eq!(applied_migrations(&conn).len(), 1);
+    }
+
+    #[test]
+    fn carries_renamed_and_retyped_columns_over() {
+        let mut conn = Connection::open_in_memory().expect("The database should open");
+        // The entries table before teams could have letters and before the
+        // auto levels were scouted
+        conn.execute_batch(
+            "CREATE SEQUENCE scout_entries_id_seq START 2;
+            CREATE TABLE scout_entries (
+                id INTEGER PRIMARY KEY DEFAULT nextval('scout_entries_id_seq'),
+                name VARCHAR, match_number USMALLINT, team_number UINTEGER,
+                auto_coral USMALLINT, auto_algae USMALLINT, auto_leave BOOLEAN,
+                algae_clear BOOLEAN, l1_coral USMALLINT, l2_coral USMALLINT,
+                l3_coral USMALLINT, l4_coral USMALLINT, dropped_coral USMALLINT,
+                algae_barge USMALLINT, algae_floor_hole USMALLINT, climb VARCHAR,
+                defense_bot BOOLEAN, notes VARCHAR, event_key VARCHAR
+            );
+            INSERT INTO scout_entries VALUES (
+                1, 'Scout', 12, 4682, 3, 1, true, false, 1, 2, 3, 4, 0, 2, 0, 'Deep',
+                false, '', '2025casj'
+            );",
+        )
+        .expect("The old table should be created");
+
+        migrate(&mut conn).expect("The old table should be migrated");
+
+        let entry = conn
+            .query_row(
+                &format!(
+                    "SELECT id, {} FROM scout_entries WHERE id = 1",
+                    DataPoint::get_select_list()
+                ),
+                [],
+                DataPoint::map_datapoint,
+            )
+            .expect("The entry should be kept");
+        assert_eq!(entry.team_number, TeamNumber::new(4682));
+        assert_eq!(entry.auto_l1_coral, Some(3));
+        assert_eq!(entry.auto_l2_coral, None);
+        assert_eq!(entry.auto_algae_barge, Some(1));
+        assert_eq!(entry.climb, ClimbResult::Deep);
+
+        let columns = column_types(&conn, "scout_entries");
+        for old_column in ["auto_coral", "auto_algae"] {
+            assert!(
+                !columns.iter().any(|column| column.0 == old_column),
+                "{old_column} should have been renamed"
+            );
+        }
+        assert!(columns.contains(&("team_number".to_owned(), "VARCHAR".to_owned())));
+    }
+
+    #[test]
+    fn rebuilds_tables_with_numeric_teams() {
+        let mut conn = Connection::open_in_memory().expect("The database should open");
+        conn.execute_batch(
+            &PICK_LIST_TABLE_SQL.replace("team_number VARCHAR", "team_number UINTEGER"),
+        )
+        .expect("The old table should be created");
+        conn.execute(
+            "INSERT INTO pick_list VALUES ('2025casj', 254, 'first_pick', 0, 'Fast cycles')",
+            [],
+        )
+        .expect("The team should be added");
+
+        migrate(&mut conn).expect("The old table should be migrated");
+
+        let row: (String, String, i32, String) = conn
+            .query_row(
+                "SELECT team_number, tier, position, comment FROM pick_list",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .expect("The team should be kept");
+        assert_eq!(
+            row,
+            (
+                "254".to_owned(),
+                "first_pick".to_owned(),
+                0,
+                "Fast cycles".to_owned()
+            )
+        );
+        assert!(column_types(&conn, "pick_list")
+            .contains(&("team_number".to_owned(), "VARCHAR".to_owned())));
+        assert_eq!(column_types(&conn, "pick_list_old"), []);
+    }
+}
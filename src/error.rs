@@ -9,7 +9,7 @@ use thiserror::Error;
 ///
 /// It encapsulates different types of errors that can occur within the
 /// application, such as server function errors, database errors, API errors,
-/// and custom errors.
+/// validation errors, and custom errors.
 #[derive(Debug, Clone, Error, Deserialize, Serialize)]
 #[non_exhaustive]
 pub enum BlueScoutError {
@@ -22,6 +22,14 @@ pub enum BlueScoutError {
     /// Represents an error that occurs during API calls.
     #[error("API Error: {0}")]
     ApiError(String),
+    /// Represents a field value violating its declared constraints.
+    #[error("Validation Error: {message}")]
+    Validation {
+        /// The name of the invalid field.
+        field: String,
+        /// A description of the violated constraint.
+        message: String,
+    },
     /// Represents a custom error with a specific message.
     #[error("Error: {0}")]
     Custom(String),
@@ -66,6 +74,20 @@ impl BlueScoutError {
         Self::ApiError(err.to_string())
     }
 
+    /// Creates a `BlueScoutError::Validation` for a field.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The name of the invalid field.
+    /// * `message` - A description of the violated constraint.
+    #[must_use]
+    pub fn validation(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Validation {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
     /// Creates a `BlueScoutError::Custom` from any error that implements
     /// `Display`.
     ///
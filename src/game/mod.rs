@@ -0,0 +1,314 @@
+//! The rules of the FRC seasons the application can scout.
+//!
+//! Every season is a module implementing `Game`, which bundles what changes
+//! from one year to the next: the fields it adds to the season schema, the
+//! points of its scoring actions, the team metrics and how the TBA score
+//! breakdown maps to scouted fields. Every season module stays compiled and
+//! registered in `GAMES`, so the analysis code of older seasons is kept. The
+//! server picks the season given by the `SEASON` environment variable at
+//! startup, or the latest one.
+//!
+//! The compiled `DataPoint` is declared by the module of the first season and
+//! re-exported from `data`. Later seasons add their own fields with
+//! `Game::schema`, and every season stores its entries in its own table, so
+//! the data of older seasons is kept too.
+
+pub mod reefscape;
+
+#[cfg(feature = "ssr")]
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::data::DataType;
+use crate::{
+    data::ScoutEntry,
+    schema::{MetricValue, SeasonSchemaFile},
+};
+
+/// The environment variable holding the year of the season to scout.
+pub const SEASON_VAR: &str = "SEASON";
+
+/// The seasons the application can scout.
+pub static GAMES: &[&dyn Game] = &[&reefscape::Reefscape];
+
+/// One of the two alliances of a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Alliance {
+    Red,
+    Blue,
+}
+
+/// The period of a match a scoring action happens in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Period {
+    Auto,
+    Teleop,
+    Endgame,
+}
+
+/// A way of scoring points, with its value from the game manual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ScoringAction {
+    /// The name of the action.
+    pub name: &'static str,
+    /// The display name of the action.
+    pub pretty_name: &'static str,
+    /// The period of the match the action happens in.
+    pub period: Period,
+    /// The points scored by the action.
+    pub points: u32,
+}
+
+impl ScoringAction {
+    /// Creates a new `ScoringAction`.
+    #[must_use]
+    pub const fn new(
+        name: &'static str,
+        pretty_name: &'static str,
+        period: Period,
+        points: u32,
+    ) -> Self {
+        Self {
+            name,
+            pretty_name,
+            period,
+            points,
+        }
+    }
+}
+
+/// A value derived from the fields of an entry, like the points it scored.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct EntryMetric {
+    /// The name of the metric.
+    pub name: &'static str,
+    /// The display name of the metric.
+    pub pretty_name: &'static str,
+    /// Computes the metric from an entry.
+    pub compute: fn(&ScoutEntry) -> f32,
+}
+
+impl EntryMetric {
+    /// Creates a new `EntryMetric`.
+    #[must_use]
+    pub const fn new(
+        name: &'static str,
+        pretty_name: &'static str,
+        compute: fn(&ScoutEntry) -> f32,
+    ) -> Self {
+        Self {
+            name,
+            pretty_name,
+            compute,
+        }
+    }
+}
+
+/// How the matches or game pieces of a team split between outcomes, like the
+/// levels its coral was scored on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Breakdown {
+    /// The display name of the breakdown.
+    pub pretty_name: String,
+    /// The outcomes and their share, from 0 to 1. Empty if nothing has been
+    /// recorded.
+    pub shares: Vec<(String, f32)>,
+}
+
+impl Breakdown {
+    /// Creates a breakdown from the number of times every outcome happened.
+    #[must_use]
+    pub fn from_counts(
+        pretty_name: impl Into<String>,
+        counts: impl IntoIterator<Item = (impl Into<String>, u32)>,
+    ) -> Self {
+        let counts = counts
+            .into_iter()
+            .map(|(outcome, count)| (outcome.into(), count))
+            .collect::<Vec<_>>();
+        let total = counts.iter().map(|&(_, count)| count).sum::<u32>();
+        let shares = if total == 0 {
+            Vec::new()
+        } else {
+            counts
+                .into_iter()
+                .map(|(outcome, count)| (outcome, count as f32 / total as f32))
+                .collect()
+        };
+
+        Self {
+            pretty_name: pretty_name.into(),
+            shares,
+        }
+    }
+}
+
+/// The rules of an FRC season.
+pub trait Game: Send + Sync {
+    /// The year of the season.
+    fn year(&self) -> i32;
+
+    /// The name of the game.
+    fn name(&self) -> &'static str;
+
+    /// The name of the database table storing the entries of the season.
+    fn table(&self) -> &'static str;
+
+    /// The fields and team metrics the season adds to `DataPoint`, applied
+    /// before the season schema file.
+    fn schema(&self) -> SeasonSchemaFile {
+        SeasonSchemaFile::default()
+    }
+
+    /// The points of the scoring actions of the season.
+    fn point_values(&self) -> &'static [ScoringAction];
+
+    /// Estimates the points the robot of an entry contributed to its alliance
+    /// in the match, from the point values of the season.
+    fn estimated_points(&self, entry: &ScoutEntry) -> u32;
+
+    /// The values derived from every entry that can be plotted along with the
+    /// numeric fields.
+    fn entry_metrics(&self) -> &'static [EntryMetric] {
+        &[]
+    }
+
+    /// Computes the metrics of a team from its entries, in match order.
+    fn team_metrics(&self, entries: &[ScoutEntry]) -> Vec<MetricValue>;
+
+    /// Whether a lower value of a team metric is the better one, like for the
+    /// number of failed climbs. Higher values are better by default.
+    fn lower_is_better(&self, _metric: &str) -> bool {
+        false
+    }
+
+    /// Splits the entries of a team between the outcomes of the game, shown
+    /// when comparing teams.
+    fn team_breakdowns(&self, _entries: &[ScoutEntry]) -> Vec<Breakdown> {
+        Vec::new()
+    }
+
+    /// Maps the TBA score breakdown of a match to the values of the fields
+    /// TBA records for a robot.
+    ///
+    /// # Arguments
+    ///
+    /// * `breakdown` - The score breakdown of the match.
+    /// * `alliance` - The alliance of the robot.
+    /// * `station` - The driver station of the robot, from 1 to 3.
+    ///
+    /// # Returns
+    ///
+    /// The names and values of the fields, or an empty list if the breakdown
+    /// is not one of this season.
+    #[cfg(feature = "ssr")]
+    fn robot_results(
+        &self,
+        breakdown: &tbaapi::models::MatchScoreBreakdown,
+        alliance: Alliance,
+        station: usize,
+    ) -> Vec<(&'static str, DataType)>;
+}
+
+/// Returns the season of the given year.
+#[must_use]
+pub fn find_game(year: i32) -> Option<&'static dyn Game> {
+    GAMES.iter().copied().find(|game| game.year() == year)
+}
+
+/// Returns the latest season.
+///
+/// # Panics
+///
+/// Panics if no season is registered in `GAMES`.
+#[must_use]
+pub fn latest_game() -> &'static dyn Game {
+    GAMES
+        .iter()
+        .copied()
+        .max_by_key(|game| game.year())
+        .expect("At least one game should be registered")
+}
+
+/// The season picked at startup.
+#[cfg(feature = "ssr")]
+static GAME: OnceCell<&'static dyn Game> = OnceCell::new();
+
+/// Picks the season given by the `SEASON` environment variable, or the latest
+/// one if it is not set.
+///
+/// # Errors
+///
+/// Returns an error if the variable is not the year of a known season, or if
+/// the season has already been picked.
+#[cfg(feature = "ssr")]
+pub fn load_game() -> anyhow::Result<()> {
+    use anyhow::Context as _;
+
+    let game = match std::env::var(SEASON_VAR) {
+        Ok(year) => {
+            let year = year
+                .trim()
+                .parse()
+                .with_context(|| format!("{SEASON_VAR} should be a year, found {year:?}"))?;
+            find_game(year).with_context(|| {
+                let known = GAMES
+                    .iter()
+                    .map(|game| game.year().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("No game for the {year} season, known seasons: {known}")
+            })?
+        }
+        Err(_) => latest_game(),
+    };
+    GAME.set(game)
+        .map_err(|_| anyhow::anyhow!("The season has already been picked"))?;
+    tracing::info!("Scouting {} ({})", game.name(), game.year());
+
+    Ok(())
+}
+
+/// Returns the season picked at startup, or the latest one if none has been
+/// picked.
+#[cfg(feature = "ssr")]
+pub fn game() -> &'static dyn Game {
+    *GAME.get_or_init(latest_game)
+}
+
+/// Averages the points a team contributed per match.
+///
+/// # Returns
+///
+/// The average, or `None` if the team has no entries.
+#[cfg(feature = "ssr")]
+#[must_use]
+pub fn average_points(entries: &[ScoutEntry]) -> Option<f32> {
+    let total = entries
+        .iter()
+        .map(|entry| game().estimated_points(entry))
+        .sum::<u32>();
+    (!entries.is_empty()).then(|| total as f32 / entries.len() as f32)
+}
+
+/// Computes the metrics of a team: the ones of the season followed by the
+/// ones of the season schema.
+#[cfg(feature = "ssr")]
+#[must_use]
+pub fn team_metrics(entries: &[ScoutEntry]) -> Vec<MetricValue> {
+    // The statistics of the latest matches expect the entries in match order,
+    // which is not the order they were recorded in when matches are scouted
+    // late
+    let mut entries = entries.to_vec();
+    ScoutEntry::sort_by_match(&mut entries);
+
+    let mut metrics = game().team_metrics(&entries);
+    metrics.extend(crate::schema::season_schema().compute_metrics(&entries));
+    metrics
+}
@@ -0,0 +1,654 @@
+//! 2025 REEFSCAPE: coral scored on the four levels of the reef, algae scored
+//! in the net and the processor, and a climb on the cages at the end of the
+//! match.
+
+use blue_scout_macros::{define_enum, define_reduced_columns, define_struct, define_team_data};
+
+#[cfg(feature = "ssr")]
+use super::Alliance;
+use super::{Breakdown, EntryMetric, Game, Period, ScoringAction};
+use crate::{
+    data::{
+        average, DataType, DataTypeName, Distribution, FieldConstraints, FilterType, FormField,
+        MetricInfo, ScoutEntry, SqlEnum, Statistic, Widget,
+    },
+    schema::MetricValue,
+    team::TeamNumber,
+    BlueScoutError,
+};
+
+define_enum!(
+    ClimbResult,
+    None,
+    Park,
+    Shallow,
+    Deep,
+    NotAttempted => "Not Attempted",
+);
+
+// PLEASE NOTE:
+// Changing the display name will not affect the column name in the database
+// but if you change the field name, it will affect the column name in
+// the database. To keep the existing data when renaming a field, declare its
+// old name above it with `#[previously(old_name)]`. When changing the type of
+// a field, declare its old type with `#[previously(name: OldType)]` (or
+// `#[previously(old_name: OldType)]` when renaming it at the same time).
+// Previous names are tried from the last declared one to the first one.
+// Fields with an `Option<T>` type are nullable: a missing value is stored as
+// NULL instead of a zero or empty default, and left blank in forms and
+// exports. Fields can also use enums defined with `define_enum!`, marked with
+// `#[sql_enum]`, which are stored as DuckDB enums and picked from their
+// options in forms, and types marked with `#[text]`, which are stored as text
+// with their `Display` and `FromStr` implementations, like `TeamNumber` so
+// teams can have a letter, like `254B`. Any other type is rejected.
+// Constraints on a field are declared with `#[validate(...)]`: `min` and `max`
+// (the length for strings), `required`, `regex = "..."` and
+// `allowed = ["a", "b"]`. They are checked by `DataPoint::validate` before an
+// entry is written and missing values of nullable fields are not checked.
+// The scouting form is generated from this declaration too: `#[form(...)]`
+// sets the `widget` of a field (`text`, `number`, `counter`, `checkbox`,
+// `radio`, `select`, `textarea` or `hidden`, defaulting to one matching the
+// type), its `section`, which also applies to the following fields, a `group`
+// of neighbouring fields shown in one row, and a `placeholder`.
+// New fields should be added at the end, since `migrate_db` appends missing
+// columns to the table. The table declared here stores the entries of this
+// season; the statements creating, inserting and updating rows are generated
+// from this declaration for the table of any season, see `Game::table`.
+define_struct!(
+    DataPoint,
+    table = "scout_entries",
+    #[validate(required, max = 64)]
+    #[form(section = "Match", placeholder = "Enter your name")]
+    name: String => "Name" @ Normal,
+    #[validate(min = 1, max = 200)]
+    #[form(placeholder = "Enter match number")]
+    match_number: u16 => "Match" @ Normal,
+    #[previously(team_number: u32)]
+    #[text]
+    #[form(placeholder = "Enter team number, like 254 or 254B")]
+    team_number: TeamNumber => "Team" @ Normal,
+    // Entries recorded before the levels were scouted keep their auto coral
+    // on L1 and their auto algae in the barge, the actions scoring the least
+    #[previously(auto_coral)]
+    #[validate(max = 12)]
+    #[form(section = "Auto", group = "Auto Coral", placeholder = "Not attempted")]
+    auto_l1_coral: Option<u16> => "Auto L1" @ Normal,
+    #[validate(max = 12)]
+    #[form(group = "Auto Coral", placeholder = "Not attempted")]
+    auto_l2_coral: Option<u16> => "Auto L2" @ Normal,
+    #[validate(max = 12)]
+    #[form(group = "Auto Coral", placeholder = "Not attempted")]
+    auto_l3_coral: Option<u16> => "Auto L3" @ Normal,
+    #[validate(max = 12)]
+    #[form(group = "Auto Coral", placeholder = "Not attempted")]
+    auto_l4_coral: Option<u16> => "Auto L4" @ Normal,
+    #[previously(auto_algae)]
+    #[validate(max = 9)]
+    #[form(group = "Auto Algae", placeholder = "Not attempted")]
+    auto_algae_barge: Option<u16> => "Auto Algae Barge" @ Normal,
+    #[validate(max = 9)]
+    #[form(group = "Auto Algae", placeholder = "Not attempted")]
+    auto_algae_floor_hole: Option<u16> => "Auto Algae Floor Hole" @ Normal,
+    auto_leave: bool => "Auto Leave" @ Select,
+    algae_clear: bool => "Algae Clear" @ Select,
+    #[validate(max = 12)]
+    #[form(widget = counter, section = "Teleop", group = "Coral")]
+    l1_coral: u16 => "L1" @ Normal,
+    #[validate(max = 12)]
+    #[form(widget = counter, group = "Coral")]
+    l2_coral: u16 => "L2" @ Normal,
+    #[validate(max = 12)]
+    #[form(widget = counter, group = "Coral")]
+    l3_coral: u16 => "L3" @ Normal,
+    #[validate(max = 12)]
+    #[form(widget = counter, group = "Coral")]
+    l4_coral: u16 => "L4" @ Normal,
+    #[form(widget = counter, group = "Coral")]
+    dropped_coral: u16 => "Dropped" @ Normal,
+    #[form(widget = counter, group = "Algae")]
+    algae_barge: u16 => "Algae Barge" @ Normal,
+    #[form(widget = counter, group = "Algae")]
+    algae_floor_hole: u16 => "Algae Floor Hole" @ Normal,
+    #[previously(climb: String)]
+    #[form(section = "Endgame")]
+    #[sql_enum]
+    climb: ClimbResult => "Climb",
+    defense_bot: bool => "Defense" @ Select,
+    #[validate(max = 1000)]
+    #[form(widget = textarea, section = "Notes", placeholder = "Additional notes")]
+    notes: String => "Notes" @ None,
+    #[validate(regex = "^$|^[0-9]{4}[a-z0-9]+$")]
+    // Set from the event selected in the settings
+    #[form(widget = hidden)]
+    event_key: String => "Event" @ Select,
+);
+
+define_reduced_columns!(
+    DataPoint,
+    "Match" @ Normal => |s: &Self| s.match_number.to_string(),
+    "Team" @ Normal => |s: &Self| s.team_number.to_string(),
+    "Auto Coral" @ Normal => |s: &Self| s.auto_coral().map_or_else(String::new, |x| x.to_string()),
+    "Auto Leave" @ Select => |s: &Self| if s.auto_leave { "Yes".to_owned() } else { "No".to_owned() },
+    "Algae Clear" @ Select => |s: &Self| if s.algae_clear { "Yes".to_owned() } else { "No".to_owned() },
+    "Teleop Coral" @ Normal => |s: &Self| (s.l1_coral + s.l2_coral + s.l3_coral + s.l4_coral).to_string(),
+    "Teleop Algae" @ Normal => |s: &Self| (s.algae_barge + s.algae_floor_hole).to_string(),
+    "Climb" @ Checklist => |s: &Self| s.climb.to_string(),
+    "Defense" @ Select => |s: &Self| if s.defense_bot { "Yes".to_owned() } else { "No".to_owned() },
+    "Points" @ Normal => |s: &Self| s.estimated_points().to_string(),
+);
+
+impl DataPoint {
+    /// Returns the coral scored on every level in auto, or `None` if auto was
+    /// not attempted.
+    #[must_use]
+    pub fn auto_coral(&self) -> Option<u16> {
+        [
+            self.auto_l1_coral,
+            self.auto_l2_coral,
+            self.auto_l3_coral,
+            self.auto_l4_coral,
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(|a, b| a + b)
+    }
+
+    /// Estimates the points the robot scored in a period of the match from the
+    /// point values of the game manual. Fields left blank score no points.
+    #[must_use]
+    pub fn period_points(&self, period: Period) -> u32 {
+        // The processor is the hole at the floor of the field
+        let scored = match period {
+            Period::Auto => vec![
+                (AUTO_LEAVE, u16::from(self.auto_leave)),
+                (AUTO_L1_CORAL, self.auto_l1_coral.unwrap_or(0)),
+                (AUTO_L2_CORAL, self.auto_l2_coral.unwrap_or(0)),
+                (AUTO_L3_CORAL, self.auto_l3_coral.unwrap_or(0)),
+                (AUTO_L4_CORAL, self.auto_l4_coral.unwrap_or(0)),
+                (
+                    AUTO_PROCESSOR_ALGAE,
+                    self.auto_algae_floor_hole.unwrap_or(0),
+                ),
+                (AUTO_NET_ALGAE, self.auto_algae_barge.unwrap_or(0)),
+            ],
+            Period::Teleop => vec![
+                (L1_CORAL, self.l1_coral),
+                (L2_CORAL, self.l2_coral),
+                (L3_CORAL, self.l3_coral),
+                (L4_CORAL, self.l4_coral),
+                (PROCESSOR_ALGAE, self.algae_floor_hole),
+                (NET_ALGAE, self.algae_barge),
+            ],
+            Period::Endgame => match self.climb {
+                ClimbResult::Park => vec![(PARK, 1)],
+                ClimbResult::Shallow => vec![(SHALLOW_CLIMB, 1)],
+                ClimbResult::Deep => vec![(DEEP_CLIMB, 1)],
+                ClimbResult::None | ClimbResult::NotAttempted => Vec::new(),
+            },
+        };
+
+        scored
+            .into_iter()
+            .map(|(action, count)| action.points * u32::from(count))
+            .sum()
+    }
+
+    /// Estimates the points the robot contributed to its alliance in the match.
+    #[must_use]
+    pub fn estimated_points(&self) -> u32 {
+        [Period::Auto, Period::Teleop, Period::Endgame]
+            .into_iter()
+            .map(|period| self.period_points(period))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+impl DataPoint {
+    /// Returns an entry of a robot that scored nothing, for tests to fill in.
+    pub(crate) fn empty() -> Self {
+        Self {
+            name: "Scout".to_owned(),
+            match_number: 1,
+            team_number: TeamNumber::new(4682),
+            auto_l1_coral: None,
+            auto_l2_coral: None,
+            auto_l3_coral: None,
+            auto_l4_coral: None,
+            auto_algae_barge: None,
+            auto_algae_floor_hole: None,
+            auto_leave: false,
+            algae_clear: false,
+            l1_coral: 0,
+            l2_coral: 0,
+            l3_coral: 0,
+            l4_coral: 0,
+            dropped_coral: 0,
+            algae_barge: 0,
+            algae_floor_hole: 0,
+            climb: ClimbResult::None,
+            defense_bot: false,
+            notes: String::new(),
+            event_key: "2025casj".to_owned(),
+        }
+    }
+}
+
+/// Averages the points the robots scored in a period.
+fn average_points(data: &[DataPoint], period: Period) -> f32 {
+    data.iter().map(|x| x.period_points(period)).sum::<u32>() as f32 / data.len() as f32
+}
+
+/// Counts the entries matching a predicate.
+fn count_where(data: &[DataPoint], predicate: impl Fn(&DataPoint) -> bool) -> u32 {
+    u32::try_from(data.iter().filter(|&x| predicate(x)).count())
+        .expect("This should not be bigger than u32::MAX")
+}
+
+define_team_data!(
+    DataPoint,
+    TeamSummary,
+    #[stats(mean, median, min, max, std_dev, recent = 3)]
+    coral: f32 => "Coral" = |x: &DataPoint| f32::from(x.l4_coral + x.l3_coral + x.l2_coral + x.l1_coral),
+    avg_auto_coral: Option<f32> => "Avg Auto Coral" = |v: &[DataPoint]| average(v.iter().map(DataPoint::auto_coral)),
+    avg_barge_algae: f32 => "Avg Barge Algae" = |v: &[DataPoint]| {
+        v.iter()
+            .map(|x| u32::from(x.algae_barge))
+            .sum::<u32>() as f32
+            / v.len() as f32
+    },
+    scoring_locations: String => "Scoring Locations" = |v: &[DataPoint]| {
+        let locations = [
+            ("L1", count_where(v, |x| x.l1_coral > 0)),
+            ("L2", count_where(v, |x| x.l2_coral > 0)),
+            ("L3", count_where(v, |x| x.l3_coral > 0)),
+            ("L4", count_where(v, |x| x.l4_coral > 0)),
+        ]
+            .iter()
+            .filter_map(|x| (x.1 > 0).then_some(x.0))
+            .collect::<Vec<&str>>()
+            .join(", ");
+        if locations.is_empty() { "None".to_owned() } else { locations }
+    },
+    deep_climbs: u32 => "Sum of Deep Climbs" = |v: &[DataPoint]| count_where(v, |x| x.climb == ClimbResult::Deep),
+    not_attempted_climbs: u32 => "Sum of Not Attempted" = |v: &[DataPoint]| {
+        count_where(v, |x| x.climb == ClimbResult::NotAttempted)
+    },
+    avg_auto_points: f32 => "Avg Auto Points" = |v: &[DataPoint]| average_points(v, Period::Auto),
+    avg_teleop_points: f32 => "Avg Teleop Points" = |v: &[DataPoint]| average_points(v, Period::Teleop),
+    avg_endgame_points: f32 => "Avg Endgame Points" = |v: &[DataPoint]| average_points(v, Period::Endgame),
+    #[stats(mean, median, min, max, std_dev, recent = 3)]
+    points: f32 => "Points" = |x: &DataPoint| x.estimated_points() as f32,
+);
+
+/// 2025 REEFSCAPE.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct Reefscape;
+
+/// Leaving the starting line in auto.
+const AUTO_LEAVE: ScoringAction = ScoringAction::new("auto_leave", "Leave", Period::Auto, 3);
+/// Coral scored on L1 of the reef in auto.
+const AUTO_L1_CORAL: ScoringAction =
+    ScoringAction::new("auto_l1_coral", "Auto Coral L1", Period::Auto, 3);
+/// Coral scored on L2 of the reef in auto.
+const AUTO_L2_CORAL: ScoringAction =
+    ScoringAction::new("auto_l2_coral", "Auto Coral L2", Period::Auto, 4);
+/// Coral scored on L3 of the reef in auto.
+const AUTO_L3_CORAL: ScoringAction =
+    ScoringAction::new("auto_l3_coral", "Auto Coral L3", Period::Auto, 6);
+/// Coral scored on L4 of the reef in auto.
+const AUTO_L4_CORAL: ScoringAction =
+    ScoringAction::new("auto_l4_coral", "Auto Coral L4", Period::Auto, 7);
+/// Algae scored in the processor in auto.
+const AUTO_PROCESSOR_ALGAE: ScoringAction = ScoringAction::new(
+    "auto_processor_algae",
+    "Auto Processor Algae",
+    Period::Auto,
+    6,
+);
+/// Algae scored in the net in auto.
+const AUTO_NET_ALGAE: ScoringAction =
+    ScoringAction::new("auto_net_algae", "Auto Net Algae", Period::Auto, 4);
+/// Coral scored on L1 of the reef in teleop.
+const L1_CORAL: ScoringAction = ScoringAction::new("l1_coral", "Coral L1", Period::Teleop, 2);
+/// Coral scored on L2 of the reef in teleop.
+const L2_CORAL: ScoringAction = ScoringAction::new("l2_coral", "Coral L2", Period::Teleop, 3);
+/// Coral scored on L3 of the reef in teleop.
+const L3_CORAL: ScoringAction = ScoringAction::new("l3_coral", "Coral L3", Period::Teleop, 4);
+/// Coral scored on L4 of the reef in teleop.
+const L4_CORAL: ScoringAction = ScoringAction::new("l4_coral", "Coral L4", Period::Teleop, 5);
+/// Algae scored in the processor in teleop.
+const PROCESSOR_ALGAE: ScoringAction =
+    ScoringAction::new("processor_algae", "Processor Algae", Period::Teleop, 6);
+/// Algae scored in the net in teleop.
+const NET_ALGAE: ScoringAction = ScoringAction::new("net_algae", "Net Algae", Period::Teleop, 4);
+/// Parking in the barge zone at the end of the match.
+const PARK: ScoringAction = ScoringAction::new("park", "Park", Period::Endgame, 2);
+/// Hanging from a shallow cage at the end of the match.
+const SHALLOW_CLIMB: ScoringAction =
+    ScoringAction::new("shallow_climb", "Shallow Climb", Period::Endgame, 6);
+/// Hanging from a deep cage at the end of the match.
+const DEEP_CLIMB: ScoringAction =
+    ScoringAction::new("deep_climb", "Deep Climb", Period::Endgame, 12);
+
+/// The points of the scoring actions of REEFSCAPE, from the game manual.
+/// Algae is worth the same in auto and teleop.
+const POINT_VALUES: &[ScoringAction] = &[
+    AUTO_LEAVE,
+    AUTO_L1_CORAL,
+    AUTO_L2_CORAL,
+    AUTO_L3_CORAL,
+    AUTO_L4_CORAL,
+    AUTO_PROCESSOR_ALGAE,
+    AUTO_NET_ALGAE,
+    L1_CORAL,
+    L2_CORAL,
+    L3_CORAL,
+    L4_CORAL,
+    PROCESSOR_ALGAE,
+    NET_ALGAE,
+    PARK,
+    SHALLOW_CLIMB,
+    DEEP_CLIMB,
+];
+
+/// The values derived from an entry, plotted on the team page.
+const ENTRY_METRICS: &[EntryMetric] = &[
+    EntryMetric::new("points", "Points", |entry| {
+        entry.data.estimated_points() as f32
+    }),
+    EntryMetric::new("auto_points", "Auto Points", |entry| {
+        entry.data.period_points(Period::Auto) as f32
+    }),
+    EntryMetric::new("teleop_points", "Teleop Points", |entry| {
+        entry.data.period_points(Period::Teleop) as f32
+    }),
+    EntryMetric::new("endgame_points", "Endgame Points", |entry| {
+        entry.data.period_points(Period::Endgame) as f32
+    }),
+    EntryMetric::new("coral", "Teleop Coral", |entry| {
+        let data = &entry.data;
+        f32::from(data.l1_coral + data.l2_coral + data.l3_coral + data.l4_coral)
+    }),
+];
+
+/// The team metrics where a lower value is better: skipped climbs and the
+/// spread of the scores.
+const LOWER_IS_BETTER: &[&str] = &["not_attempted_climbs", "coral_std_dev", "points_std_dev"];
+
+impl Game for Reefscape {
+    fn year(&self) -> i32 {
+        2025
+    }
+
+    fn name(&self) -> &'static str {
+        "REEFSCAPE"
+    }
+
+    fn table(&self) -> &'static str {
+        DataPoint::table_name()
+    }
+
+    fn point_values(&self) -> &'static [ScoringAction] {
+        POINT_VALUES
+    }
+
+    fn estimated_points(&self, entry: &ScoutEntry) -> u32 {
+        entry.data.estimated_points()
+    }
+
+    fn entry_metrics(&self) -> &'static [EntryMetric] {
+        ENTRY_METRICS
+    }
+
+    fn team_metrics(&self, entries: &[ScoutEntry]) -> Vec<MetricValue> {
+        let data = entries
+            .iter()
+            .map(|entry| entry.data.clone())
+            .collect::<Vec<_>>();
+        DataPoint::summarize(&data)
+            .metric_values()
+            .into_iter()
+            .map(|(metric, value)| MetricValue {
+                name: metric.name.to_owned(),
+                pretty_name: metric.pretty_name.to_owned(),
+                value,
+            })
+            .collect()
+    }
+
+    fn lower_is_better(&self, metric: &str) -> bool {
+        LOWER_IS_BETTER.contains(&metric)
+    }
+
+    fn team_breakdowns(&self, entries: &[ScoutEntry]) -> Vec<Breakdown> {
+        let data = entries
+            .iter()
+            .map(|entry| entry.data.clone())
+            .collect::<Vec<_>>();
+
+        // Coral scored in auto and teleop, by level
+        let levels = data.iter().fold([0_u32; 4], |mut levels, x| {
+            let scored = [
+                (x.auto_l1_coral, x.l1_coral),
+                (x.auto_l2_coral, x.l2_coral),
+                (x.auto_l3_coral, x.l3_coral),
+                (x.auto_l4_coral, x.l4_coral),
+            ];
+            for (level, (auto, teleop)) in levels.iter_mut().zip(scored) {
+                *level += u32::from(auto.unwrap_or(0) + teleop);
+            }
+            levels
+        });
+        let climbs = ClimbResult::VARIANTS
+            .iter()
+            .map(|&result| (result.label(), count_where(&data, |x| x.climb == result)));
+
+        vec![
+            Breakdown::from_counts(
+                "Coral Levels",
+                ["L1", "L2", "L3", "L4"].into_iter().zip(levels),
+            ),
+            Breakdown::from_counts("Climbs", climbs),
+        ]
+    }
+
+    #[cfg(feature = "ssr")]
+    fn robot_results(
+        &self,
+        breakdown: &tbaapi::models::MatchScoreBreakdown,
+        alliance: Alliance,
+        station: usize,
+    ) -> Vec<(&'static str, DataType)> {
+        use tbaapi::models::{
+            match_score_breakdown_2025_alliance::{
+                AutoLineRobot1, AutoLineRobot2, AutoLineRobot3, EndGameRobot1, EndGameRobot2,
+                EndGameRobot3,
+            },
+            MatchScoreBreakdown,
+        };
+
+        let MatchScoreBreakdown::MatchScoreBreakdown2025(ref breakdown) = *breakdown else {
+            return Vec::new();
+        };
+        let alliance = match alliance {
+            Alliance::Red => &breakdown.red,
+            Alliance::Blue => &breakdown.blue,
+        };
+
+        // TBA has a separate type for every driver station
+        let (auto_leave, climb) = match station {
+            1 => (
+                alliance
+                    .auto_line_robot1
+                    .map(|line| line == AutoLineRobot1::Yes),
+                alliance.end_game_robot1.map(|end_game| match end_game {
+                    EndGameRobot1::None => ClimbResult::None,
+                    EndGameRobot1::Parked => ClimbResult::Park,
+                    EndGameRobot1::ShallowCage => ClimbResult::Shallow,
+                    EndGameRobot1::DeepCage => ClimbResult::Deep,
+                }),
+            ),
+            2 => (
+                alliance
+                    .auto_line_robot2
+                    .map(|line| line == AutoLineRobot2::Yes),
+                alliance.end_game_robot2.map(|end_game| match end_game {
+                    EndGameRobot2::None => ClimbResult::None,
+                    EndGameRobot2::Parked => ClimbResult::Park,
+                    EndGameRobot2::ShallowCage => ClimbResult::Shallow,
+                    EndGameRobot2::DeepCage => ClimbResult::Deep,
+                }),
+            ),
+            3 => (
+                alliance
+                    .auto_line_robot3
+                    .map(|line| line == AutoLineRobot3::Yes),
+                alliance.end_game_robot3.map(|end_game| match end_game {
+                    EndGameRobot3::None => ClimbResult::None,
+                    EndGameRobot3::Parked => ClimbResult::Park,
+                    EndGameRobot3::ShallowCage => ClimbResult::Shallow,
+                    EndGameRobot3::DeepCage => ClimbResult::Deep,
+                }),
+            ),
+            _ => return Vec::new(),
+        };
+
+        auto_leave
+            .map(|auto_leave| ("auto_leave", DataType::Bool(auto_leave)))
+            .into_iter()
+            .chain(climb.map(|climb| ("climb", DataType::Enum(climb.label().to_owned()))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use blue_scout_macros::define_struct;
+
+    use super::{ClimbResult, DataPoint, Reefscape};
+    use crate::{
+        data::{
+            DataType, DataTypeName, FieldConstraints, FilterType, FormField, ScoutEntry, Widget,
+        },
+        game::{Game as _, Period},
+        team::TeamNumber,
+        BlueScoutError,
+    };
+
+    // None of the fields of `DataPoint` restricts its values to a list
+    define_struct!(
+        Observation,
+        table = "observations",
+        #[validate(allowed = ["Red", "Blue"])]
+        alliance: String => "Alliance" @ Select,
+    );
+
+    /// Returns the field a validation error is about, or `None` if the
+    /// result is not a validation error.
+    fn invalid_field<T>(result: Result<T, BlueScoutError>) -> Option<String> {
+        match result {
+            Err(BlueScoutError::Validation { field, .. }) => Some(field),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn validates_entries() {
+        assert!(DataPoint::empty().validate().is_ok());
+
+        let assert_invalid = |field: &str, change: fn(&mut DataPoint)| {
+            let mut entry = DataPoint::empty();
+            change(&mut entry);
+            assert_eq!(invalid_field(entry.validate()), Some(field.to_owned()));
+        };
+        assert_invalid("name", |entry| entry.name = "  ".to_owned());
+        assert_invalid("name", |entry| entry.name = "a".repeat(65));
+        assert_invalid("match_number", |entry| entry.match_number = 0);
+        assert_invalid("match_number", |entry| entry.match_number = 9999);
+        assert_invalid("auto_l1_coral", |entry| entry.auto_l1_coral = Some(13));
+        assert_invalid("l4_coral", |entry| entry.l4_coral = 13);
+        assert_invalid("event_key", |entry| entry.event_key = "casj".to_owned());
+    }
+
+    #[test]
+    fn skips_missing_values_of_nullable_fields() {
+        let entry = DataPoint {
+            auto_l1_coral: None,
+            auto_l4_coral: Some(12),
+            event_key: String::new(),
+            ..DataPoint::empty()
+        };
+        assert!(entry.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_team_zero() {
+        assert_eq!(
+            invalid_field("0".parse::<TeamNumber>()),
+            Some("team_number".to_owned())
+        );
+    }
+
+    #[test]
+    fn validates_allowed_values() {
+        let observation = |alliance: &str| Observation {
+            alliance: alliance.to_owned(),
+        };
+
+        assert!(observation("Red").validate().is_ok());
+        assert_eq!(
+            invalid_field(observation("Green").validate()),
+            Some("alliance".to_owned())
+        );
+    }
+
+    #[test]
+    fn estimates_points_from_the_point_values() {
+        let data = DataPoint {
+            auto_leave: true,
+            auto_l1_coral: Some(1),
+            auto_l2_coral: Some(1),
+            auto_l3_coral: Some(1),
+            auto_l4_coral: Some(1),
+            l1_coral: 1,
+            l2_coral: 1,
+            l3_coral: 1,
+            l4_coral: 1,
+            algae_barge: 1,
+            algae_floor_hole: 1,
+            climb: ClimbResult::Deep,
+            ..DataPoint::empty()
+        };
+
+        // Leave, then one coral on every level
+        assert_eq!(data.period_points(Period::Auto), 3 + 3 + 4 + 6 + 7);
+        // One coral on every level, then the net and the processor
+        assert_eq!(data.period_points(Period::Teleop), 2 + 3 + 4 + 5 + 4 + 6);
+        assert_eq!(data.period_points(Period::Endgame), 12);
+        assert_eq!(data.estimated_points(), 23 + 24 + 12);
+
+        let entry = ScoutEntry {
+            id: 1,
+            data,
+            extra: BTreeMap::new(),
+        };
+        assert_eq!(Reefscape.estimated_points(&entry), 59);
+    }
+
+    #[test]
+    fn scores_no_points_for_blank_fields() {
+        let data = DataPoint {
+            auto_algae_barge: Some(2),
+            climb: ClimbResult::NotAttempted,
+            ..DataPoint::empty()
+        };
+        assert_eq!(data.period_points(Period::Auto), 2 * 4);
+        assert_eq!(data.period_points(Period::Teleop), 0);
+        assert_eq!(data.period_points(Period::Endgame), 0);
+        assert_eq!(DataPoint::empty().estimated_points(), 0);
+    }
+}
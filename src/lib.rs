@@ -3,21 +3,34 @@
 #![feature(let_chains, extern_types)]
 #![recursion_limit = "256"]
 
-#[cfg(feature = "ssr")]
-include!(concat!(env!("OUT_DIR"), "/generated.rs"));
-
 use core::sync::atomic::AtomicBool;
 
 use chrono::NaiveDate;
-use data::DataPoint;
+use data::{DataType, ScoutEntry};
+use game::Breakdown;
+use match_key::MatchKey;
+use pick_list::PickListEntry;
+use schema::MetricValue;
 use serde::{Deserialize, Serialize};
+use team::TeamNumber;
+use trend::Trend;
+pub mod alliance_selection;
 pub mod api;
 pub mod app;
 pub mod components;
 pub mod data;
 pub mod db;
 mod error;
+pub mod game;
+pub mod match_key;
+pub mod pick_list;
+pub mod schema;
 mod tablefilterjs;
+pub mod tba_cache;
+pub mod tba_fixtures;
+pub mod team;
+pub mod team_names;
+pub mod trend;
 pub use error::BlueScoutError;
 
 #[cfg(feature = "ssr")]
@@ -83,22 +96,174 @@ pub fn hydrate() {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TeamInfo {
     /// The team number.
-    team_number: u32,
+    team_number: TeamNumber,
     /// The team name (optional).
     team_name: Option<String>,
-    /// The team's data points (optional).
-    team_data: Option<Vec<DataPoint>>,
+    /// The metrics computed from the team's entries, or `None` if the team
+    /// has not been scouted.
+    metrics: Option<Vec<MetricValue>>,
+    /// The average points the team contributed per match, or `None` if the
+    /// team has not been scouted.
+    estimated_points: Option<f32>,
+    /// The trend of the first series of `trend::trend_series` over the
+    /// matches of the team, or `None` if the team has not been scouted.
+    trend: Option<Trend>,
+    /// Whether the team plays the match as a surrogate, which doesn't count
+    /// toward its ranking.
+    surrogate: bool,
+    /// Whether the team has been disqualified from the match.
+    disqualified: bool,
 }
 
 /// Represents information about a match.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MatchInfo {
+    /// The match.
+    match_key: MatchKey,
+    /// The match for display, like `Qualification 12` or `Playoff 5`.
+    label: String,
     /// The predicted time of the match.
     predicted_time: i64,
     /// Information about the red alliance teams.
     red: [TeamInfo; 3],
     /// Information about the blue alliance teams.
     blue: [TeamInfo; 3],
+    /// The playoff alliance of the red teams, numbered from 1, or `None` in
+    /// qualifications.
+    red_alliance: Option<u8>,
+    /// The playoff alliance of the blue teams, numbered from 1, or `None` in
+    /// qualifications.
+    blue_alliance: Option<u8>,
+    /// How old the schedule of the event is, or `None` if it has not been
+    /// fetched.
+    schedule_age: Option<CacheAge>,
+}
+
+/// How old data fetched from TBA is.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CacheAge {
+    /// When the data was fetched from TBA, in seconds since the Unix epoch.
+    fetched_at: i64,
+    /// Whether TBA couldn't be reached, so the data may be out of date.
+    offline: bool,
+}
+
+impl CacheAge {
+    /// Creates a new `CacheAge`.
+    #[must_use]
+    pub const fn new(fetched_at: i64, offline: bool) -> Self {
+        Self {
+            fetched_at,
+            offline,
+        }
+    }
+
+    /// Describes the age for display, like `Updated 5 min ago`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let age = (chrono::Utc::now().timestamp() - self.fetched_at).max(0);
+        let age = match age {
+            0..60 => "just now".to_owned(),
+            60..3600 => format!("{} min ago", age / 60),
+            3600..86400 => format!("{} h ago", age / 3600),
+            _ => format!("{} days ago", age / 86400),
+        };
+        if self.offline {
+            format!("Offline, updated {age}")
+        } else {
+            format!("Updated {age}")
+        }
+    }
+}
+
+/// The standing of a team at an event, reported by TBA.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TeamEventRecord {
+    /// The event key.
+    event_key: String,
+    /// The qualification rank of the team, if it has been ranked.
+    rank: Option<i32>,
+    /// The number of teams ranked at the event.
+    num_teams: Option<i32>,
+    /// The wins, losses and ties of the team in qualification matches.
+    record: Option<(i32, i32, i32)>,
+    /// A summary of the status of the team at the event.
+    status: Option<String>,
+}
+
+/// Represents the scouting data of a single team.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TeamDetails {
+    /// The team number.
+    team_number: TeamNumber,
+    /// The team name (optional).
+    team_name: Option<String>,
+    /// The entries of the team, sorted by event and match number.
+    entries: Vec<ScoutEntry>,
+    /// The metrics computed from the team's entries, or `None` if the team
+    /// has not been scouted.
+    metrics: Option<Vec<MetricValue>>,
+    /// The standing of the team at the selected event, or `None` if TBA has
+    /// none.
+    event_record: Option<TeamEventRecord>,
+}
+
+/// A team metric of the compared teams, aligned in one row.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ComparisonRow {
+    /// The name of the metric.
+    name: String,
+    /// The display name of the metric.
+    pretty_name: String,
+    /// The value of the metric for every team, in the order of the teams.
+    values: Vec<DataType>,
+    /// Whether the value of every team is the best of the row. Nothing is
+    /// highlighted when all the values are the same.
+    best: Vec<bool>,
+}
+
+/// Represents a team in a comparison.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ComparedTeam {
+    /// The team number.
+    team_number: TeamNumber,
+    /// The team name (optional).
+    team_name: Option<String>,
+    /// The number of scouted matches of the team.
+    matches: usize,
+    /// How the matches and game pieces of the team split between outcomes.
+    breakdowns: Vec<Breakdown>,
+    /// The match numbers and notes of the entries with notes.
+    notes: Vec<(u16, String)>,
+}
+
+/// Represents the scouting data of the teams compared side by side.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TeamComparison {
+    /// The compared teams, in the order they were picked.
+    teams: Vec<ComparedTeam>,
+    /// The team metrics, with a value for every team.
+    rows: Vec<ComparisonRow>,
+}
+
+/// Represents a team in the pick list, with a summary of its scouting data.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PickListRow {
+    /// The team and its place in the pick list.
+    entry: PickListEntry,
+    /// The team name (optional).
+    team_name: Option<String>,
+    /// The number of scouted matches of the team at the event.
+    matches: usize,
+    /// The average points the team contributed per match, or `None` if the
+    /// team has not been scouted.
+    estimated_points: Option<f32>,
+    /// The metrics computed from the team's entries, or `None` if the team
+    /// has not been scouted.
+    metrics: Option<Vec<MetricValue>>,
+    /// Whether the team joined an alliance or declined an invitation during
+    /// alliance selection.
+    unavailable: bool,
 }
 
 /// Represents information about an event.
@@ -114,22 +279,35 @@ pub struct EventInfo {
     end_date: NaiveDate,
 }
 
+impl TeamInfo {
+    /// Creates the `TeamInfo` of a team that has not been scouted.
+    #[must_use]
+    pub const fn unscouted(team_number: TeamNumber) -> Self {
+        Self {
+            team_number,
+            team_name: None,
+            metrics: None,
+            estimated_points: None,
+            trend: None,
+            surrogate: false,
+            disqualified: false,
+        }
+    }
+}
+
 impl MatchInfo {
-    /// Creates an empty `MatchInfo` struct with default values.
+    /// Creates a `MatchInfo` struct with default values for the given teams.
     #[must_use]
-    pub fn empty() -> Self {
+    pub fn empty(match_key: MatchKey, red: [TeamNumber; 3], blue: [TeamNumber; 3]) -> Self {
         Self {
+            match_key,
+            label: match_key.label(false),
             predicted_time: 0,
-            red: core::array::from_fn(|_| TeamInfo {
-                team_number: 0,
-                team_name: None,
-                team_data: None,
-            }),
-            blue: core::array::from_fn(|_| TeamInfo {
-                team_number: 0,
-                team_name: None,
-                team_data: None,
-            }),
+            red: red.map(TeamInfo::unscouted),
+            blue: blue.map(TeamInfo::unscouted),
+            red_alliance: None,
+            blue_alliance: None,
+            schedule_age: None,
         }
     }
 }
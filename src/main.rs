@@ -6,52 +6,27 @@ use axum::response::IntoResponse;
 use blue_scout::db::get_conn;
 use tbaapi::apis::configuration::Configuration;
 
-/// Initializes the team names by fetching data from the TBA API and storing it
-/// in a global variable.
-///
-/// This function is only compiled and executed if the `ssr` feature is enabled.
-///
-/// # Errors
-///
-/// Returns an `anyhow::Error` if there is an issue with the API request or data
-/// processing.
+/// Query parameters accepted by the `/download-xlsx` route.
 #[cfg(feature = "ssr")]
-async fn init_team_names() -> anyhow::Result<()> {
-    //     use blue_scout::{api_config, TEAM_NAMES};
-    //     use tbaapi::apis::default_api::get_search_index;
-    //
-    //     let res = get_search_index(api_config()).await?;
-    //
-    //     let info: Vec<(u32, String)> = res
-    //         .teams
-    //         .into_iter()
-    //         .filter_map(|team| match team.key.trim_start_matches("frc").parse() {
-    //             Ok(val) => Some((val, team.nickname)),
-    //             Err(err) => {
-    //                 tracing::error!("Error parsing team key: {err}");
-    //                 None
-    //             }
-    //         })
-    //         .collect();
-    //     if info.is_empty() {
-    //         return Err(anyhow::anyhow!("Team keys is empty"));
-    //     }
-    //
-    //     let team_names = FzScalarMap::new(info);
-    //
-    //     TEAM_NAMES
-    //         .set(team_names)
-    //         .expect("TEAM_NAMES should not be set yet");
-    //
-    Ok(())
+#[derive(serde::Deserialize)]
+struct XlsxQuery {
+    /// The event to export the data of. All events are exported when missing.
+    event: Option<String>,
 }
 
-/// Generates an XLSX file containing data from the `scout_entries` table in the
+/// Generates an XLSX file containing data from the entries table in the
 /// database.
 ///
-/// This function queries all columns (excluding `id`) from the `scout_entries`
-/// table, formats the data, and writes it to an XLSX file. The file is then
-/// returned as an HTTP response with the appropriate content type.
+/// This function queries the `DataPoint` columns of the entries that have not
+/// been deleted, formats the data, and writes it to an XLSX file along with the
+/// estimated points of every entry. The file is then returned as an HTTP
+/// response with the appropriate content type. A second worksheet holds the
+/// metrics of every team.
+///
+/// # Arguments
+///
+/// * `event_key` - The event to export the data of, or `None` to export the
+///   data of all events.
 ///
 /// # Errors
 ///
@@ -74,18 +49,31 @@ async fn init_team_names() -> anyhow::Result<()> {
 /// Returns a `Result` containing an `impl IntoResponse`, which represents the
 /// HTTP response with the XLSX file as the body.
 #[cfg(feature = "ssr")]
-pub async fn generate_xlsx() -> anyhow::Result<impl IntoResponse> {
+pub async fn generate_xlsx(event_key: Option<String>) -> anyhow::Result<impl IntoResponse> {
     use std::io::Cursor;
 
     use axum::response::Response;
-    use blue_scout::data::DataPoint;
-    use duckdb::arrow::datatypes::DataType;
+    use blue_scout::{
+        data::{DataType as MetricValue, ScoutEntry},
+        db::{entries_table, entry_columns, get_team_metrics},
+        game::game,
+        schema::season_schema,
+    };
+    use duckdb::{arrow::datatypes::DataType, types::ValueRef};
     use reqwest::header::CONTENT_TYPE;
     use rust_xlsxwriter::{workbook::Workbook, Format};
 
+    let schema = season_schema();
+    // Fetched first, since the statement below can't be held across an await
+    let summaries = get_team_metrics(event_key.as_deref()).await?;
+
     let conn = get_conn().await;
 
-    let mut stmt = conn.prepare("SELECT * EXCLUDE(id) FROM scout_entries")?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM {} WHERE deleted_at IS NULL AND (?1 IS NULL OR event_key = ?1)",
+        entry_columns(),
+        entries_table()
+    ))?;
 
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet().set_name("Data")?;
@@ -95,21 +83,27 @@ pub async fn generate_xlsx() -> anyhow::Result<impl IntoResponse> {
     worksheet.write_row_with_format(
         0,
         0,
-        DataPoint::field_pretty_names()
+        schema
+            .fields
             .iter()
-            .map(|&(_, x)| x.to_owned()),
+            .map(|field| field.pretty_name.as_str())
+            .chain(core::iter::once("Points")),
         &bold,
     )?;
 
     let mut current_row = 1;
-    stmt.query_map([], |row| {
-        for i in 0..row.as_ref().column_count() {
+    stmt.query_map([event_key.as_deref()], |row| {
+        let column_count = row.as_ref().column_count();
+        // The first column is the id, which is not exported
+        for i in 1..column_count {
             let t: DataType = row.as_ref().column_type(i);
-            let current_column = u16::try_from(i).expect("Current column should be u16");
+            let current_column = u16::try_from(i - 1).expect("Current column should be u16");
+            // Missing values of nullable fields are left blank
+            if matches!(row.get_ref(i)?, ValueRef::Null) {
+                continue;
+            }
             match t {
-                DataType::Null => worksheet
-                    .write_string(current_row, current_column, "NULL")
-                    .expect("Writing to excel file should not fail"),
+                DataType::Null => continue,
                 DataType::Boolean => worksheet
                     .write_string(
                         current_row,
@@ -134,15 +128,71 @@ pub async fn generate_xlsx() -> anyhow::Result<impl IntoResponse> {
                 _ => unimplemented!("Unsupported data type: {:?}", t),
             };
         }
+        let points = game().estimated_points(&ScoutEntry::map_entry(row)?);
+        worksheet
+            .write_number(
+                current_row,
+                u16::try_from(column_count - 1).expect("Current column should be u16"),
+                points,
+            )
+            .expect("Writing to excel file should not fail");
         current_row += 1;
         Ok(())
     })?
     .count();
 
+    drop(stmt);
     drop(conn);
 
     worksheet.autofit_to_max_width(300);
 
+    let summary_sheet = workbook.add_worksheet().set_name("Team Summaries")?;
+
+    // Every team has the same metrics
+    let metric_names = summaries
+        .first()
+        .map(|(_, metrics)| {
+            metrics
+                .iter()
+                .map(|metric| metric.pretty_name.clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    summary_sheet.write_row_with_format(
+        0,
+        0,
+        core::iter::once("Team").chain(metric_names.iter().map(String::as_str)),
+        &bold,
+    )?;
+
+    for (current_row, (team_number, metrics)) in (1..).zip(summaries) {
+        summary_sheet.write_string(current_row, 0, team_number.to_string())?;
+        let values = metrics.into_iter().map(|metric| metric.value);
+        for (current_column, value) in (1..).zip(values) {
+            match value {
+                // Metrics without a value are left blank
+                MetricValue::Null => {}
+                MetricValue::Bool(value) => {
+                    summary_sheet.write_string(
+                        current_row,
+                        current_column,
+                        if value { "Yes" } else { "No" },
+                    )?;
+                }
+                value => match value.as_f64() {
+                    Some(number) => {
+                        summary_sheet.write_number(current_row, current_column, number)?;
+                    }
+                    None => {
+                        summary_sheet.write_string(current_row, current_column, value.to_string())?;
+                    }
+                },
+            }
+        }
+    }
+
+    summary_sheet.autofit_to_max_width(300);
+
     let mut buf = Cursor::new(Vec::new());
     workbook.save_to_writer(&mut buf)?;
 
@@ -154,6 +204,64 @@ pub async fn generate_xlsx() -> anyhow::Result<impl IntoResponse> {
         .body(Body::from(buf.into_inner()))?)
 }
 
+/// Prints the schema version of the database and the migrations that would be
+/// applied on startup, without applying them.
+///
+/// # Panics
+///
+/// Panics if the database can't be opened or read.
+#[cfg(feature = "ssr")]
+async fn list_migrations() {
+    use blue_scout::{
+        data::SCHEMA_VERSION,
+        db::{open_db, pending_migrations, schema_checksum},
+    };
+
+    open_db().expect("DB should be able to be opened");
+    let (latest, pending) = pending_migrations()
+        .await
+        .expect("Pending migrations should be readable");
+
+    match latest {
+        Some(latest) => println!(
+            "Database schema version: {} (checksum {})",
+            latest.version, latest.checksum
+        ),
+        None => println!("Database schema version: none"),
+    }
+    println!(
+        "Application schema version: {SCHEMA_VERSION} (checksum {})",
+        schema_checksum()
+    );
+
+    if pending.is_empty() {
+        println!("No pending migrations");
+    } else {
+        println!("Pending migrations:");
+        for migration in pending {
+            println!("  {}\n    {}", migration.description, migration.sql);
+        }
+    }
+}
+
+/// Fetches the team names from TBA and writes them to the snapshot bundled
+/// with the server, used when they have never been fetched.
+///
+/// # Panics
+///
+/// Panics if the team names can't be fetched or the snapshot can't be
+/// written.
+#[cfg(feature = "ssr")]
+async fn update_team_names_snapshot() {
+    use blue_scout::team_names::{refresh_team_names, write_snapshot, SNAPSHOT_PATH};
+
+    refresh_team_names()
+        .await
+        .expect("Team names should be fetched from TBA");
+    write_snapshot(std::path::Path::new(SNAPSHOT_PATH)).expect("Snapshot should be written");
+    println!("Team names written to {SNAPSHOT_PATH}");
+}
+
 #[cfg(feature = "ssr")]
 #[tokio::main]
 async fn main() {
@@ -164,10 +272,14 @@ async fn main() {
         )
     }
 
-    use axum::{error_handling::HandleError, Router};
+    use axum::{error_handling::HandleError, extract::Query, Router};
     use blue_scout::{
         app::{shell, App},
         db::init_db,
+        game::load_game,
+        schema::load_season_schema,
+        tba_fixtures::{load_tba_mode, TbaMode},
+        team_names::init_team_names,
         API_CONFIG,
     };
     use dotenv::dotenv;
@@ -187,22 +299,47 @@ async fn main() {
         tracing::warn!("No .env file found");
     }
 
-    let config = Configuration {
-        api_key: Some(ApiKey {
-            prefix: None,
-            key: std::env::var("TBA_API_KEY").expect("TBA_API_KEY must be set"),
-        }),
+    let tba_mode = load_tba_mode().expect("TBA_MODE should be a known mode");
+
+    // TBA is never contacted when replaying, so no key is needed
+    let api_key = match std::env::var("TBA_API_KEY") {
+        Ok(key) => Some(ApiKey { prefix: None, key }),
+        Err(_) if tba_mode == TbaMode::Replay => None,
+        Err(_) => panic!("TBA_API_KEY must be set unless TBA_MODE is replay"),
+    };
+    let mut config = Configuration {
+        api_key,
         ..Configuration::default()
     };
+    // Lets the server talk to a local stand-in of TBA
+    if let Ok(base_path) = std::env::var("TBA_BASE_PATH") {
+        config.base_path = base_path;
+    }
 
     API_CONFIG.set(config).expect("This should not be set yet");
 
-    // init_team_names().await.expect("TODO: Handle error");
+    // The database columns depend on the season schema, so it is loaded first
+    load_game().expect("SEASON should be a known season");
+    load_season_schema().expect("Season schema should be valid");
+
+    if std::env::args().any(|arg| arg == "--list-migrations") {
+        list_migrations().await;
+        return;
+    }
 
     init_db()
         .await
         .expect("DB should be able to be initialized");
 
+    if std::env::args().any(|arg| arg == "--update-team-names") {
+        update_team_names_snapshot().await;
+        return;
+    }
+
+    init_team_names()
+        .await
+        .expect("Team names should be able to be loaded");
+
     let conf = get_configuration(None).expect("Configuration should be set");
     let addr = conf.leptos_options.site_addr;
     let leptos_options = conf.leptos_options;
@@ -219,8 +356,9 @@ async fn main() {
         .route_service(
             "/download-xlsx",
             HandleError::new(
-                tower::service_fn(|_req| async {
-                    let res = generate_xlsx().await?;
+                tower::service_fn(|req: axum::extract::Request| async move {
+                    let Query(query) = Query::<XlsxQuery>::try_from_uri(req.uri())?;
+                    let res = generate_xlsx(query.event).await?;
                     Ok::<_, anyhow::Error>(res)
                 }),
                 handle_anyhow_error,
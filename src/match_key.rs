@@ -0,0 +1,230 @@
+//! Identifies the matches of an event the way TBA does: by competition level,
+//! set and match number, like `qm12`, `qf2m1` or `sf5m1`.
+//!
+//! Since 2023, playoffs are double elimination brackets: TBA numbers their
+//! matches as the sets of the `sf` level, from `sf1m1` to `sf13m1`, followed
+//! by the finals `f1m1` to `f1m3`.
+
+use core::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::BlueScoutError;
+
+/// The competition level of a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum MatchLevel {
+    Qualification,
+    EighthFinal,
+    QuarterFinal,
+    SemiFinal,
+    Final,
+}
+
+impl MatchLevel {
+    /// The levels, in the order they are played.
+    pub const ALL: [Self; 5] = [
+        Self::Qualification,
+        Self::EighthFinal,
+        Self::QuarterFinal,
+        Self::SemiFinal,
+        Self::Final,
+    ];
+
+    /// Returns the code of the level in TBA match keys.
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::Qualification => "qm",
+            Self::EighthFinal => "ef",
+            Self::QuarterFinal => "qf",
+            Self::SemiFinal => "sf",
+            Self::Final => "f",
+        }
+    }
+
+    /// Parses a level from its code in TBA match keys.
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|level| level.code() == code)
+    }
+}
+
+impl Display for MatchLevel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Qualification => write!(f, "Qualification"),
+            Self::EighthFinal => write!(f, "Eighth-final"),
+            Self::QuarterFinal => write!(f, "Quarterfinal"),
+            Self::SemiFinal => write!(f, "Semifinal"),
+            Self::Final => write!(f, "Final"),
+        }
+    }
+}
+
+/// A match of an event, without the event.
+///
+/// The key is written like in TBA, `qm12` or `sf5m1`, and also parsed from a
+/// full TBA match key like `2025casj_sf5m1`, a bare qualification number like
+/// `12`, or a playoff set without its match like `sf5`, which is its first
+/// match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct MatchKey {
+    /// The competition level.
+    pub level: MatchLevel,
+    /// The set of the match, always 1 for qualifications.
+    pub set_number: u32,
+    /// The number of the match in its set.
+    pub match_number: u32,
+}
+
+impl MatchKey {
+    /// Creates a new `MatchKey`.
+    #[must_use]
+    pub const fn new(level: MatchLevel, set_number: u32, match_number: u32) -> Self {
+        Self {
+            level,
+            set_number,
+            match_number,
+        }
+    }
+
+    /// Creates the key of a qualification match.
+    #[must_use]
+    pub const fn qualification(match_number: u32) -> Self {
+        Self::new(MatchLevel::Qualification, 1, match_number)
+    }
+
+    /// Describes the match for display, like `Qualification 12` or
+    /// `Playoff 5`.
+    ///
+    /// # Arguments
+    ///
+    /// * `double_elimination` - Whether the playoffs of the event are a
+    ///   double elimination bracket, whose matches are numbered by set.
+    #[must_use]
+    pub fn label(&self, double_elimination: bool) -> String {
+        match self.level {
+            MatchLevel::Qualification => format!("Qualification {}", self.match_number),
+            MatchLevel::SemiFinal if double_elimination && self.match_number == 1 => {
+                format!("Playoff {}", self.set_number)
+            }
+            MatchLevel::SemiFinal if double_elimination => {
+                format!("Playoff {} Replay {}", self.set_number, self.match_number - 1)
+            }
+            MatchLevel::Final if self.set_number == 1 => format!("Final {}", self.match_number),
+            level => format!("{level} {} Match {}", self.set_number, self.match_number),
+        }
+    }
+}
+
+impl Display for MatchKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.level {
+            MatchLevel::Qualification => write!(f, "qm{}", self.match_number),
+            level => write!(f, "{}{}m{}", level.code(), self.set_number, self.match_number),
+        }
+    }
+}
+
+impl FromStr for MatchKey {
+    type Err = BlueScoutError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            BlueScoutError::validation(
+                "match",
+                format!("{key:?} is not a match, like 12, qm12, sf5m1 or 2025casj_f1m2"),
+            )
+        };
+        let number = |number: &str| number.parse::<u32>().ok().filter(|&number| number > 0);
+
+        // Full keys start with the event
+        let short = key
+            .trim()
+            .rsplit_once('_')
+            .map_or(key.trim(), |(_, short)| short)
+            .to_ascii_lowercase();
+        if let Some(match_number) = number(&short) {
+            return Ok(Self::qualification(match_number));
+        }
+
+        let code_len = short
+            .find(|c: char| c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (code, numbers) = short.split_at(code_len);
+        let level = MatchLevel::from_code(code).ok_or_else(invalid)?;
+
+        if level == MatchLevel::Qualification {
+            return Ok(Self::qualification(number(numbers).ok_or_else(invalid)?));
+        }
+        let (set_number, match_number) = numbers.split_once('m').unwrap_or((numbers, "1"));
+        Ok(Self::new(
+            level,
+            number(set_number).ok_or_else(invalid)?,
+            number(match_number).ok_or_else(invalid)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MatchKey, MatchLevel};
+
+    #[test]
+    fn parses_full_keys() {
+        assert_eq!(
+            "2025casj_qm12".parse::<MatchKey>().ok(),
+            Some(MatchKey::qualification(12))
+        );
+        assert_eq!(
+            "2025casj_sf5m1".parse::<MatchKey>().ok(),
+            Some(MatchKey::new(MatchLevel::SemiFinal, 5, 1))
+        );
+        assert_eq!(
+            "2025casj_f1m2".parse::<MatchKey>().ok(),
+            Some(MatchKey::new(MatchLevel::Final, 1, 2))
+        );
+    }
+
+    #[test]
+    fn parses_short_keys() {
+        assert_eq!("12".parse::<MatchKey>().ok(), Some(MatchKey::qualification(12)));
+        assert_eq!("QM12".parse::<MatchKey>().ok(), Some(MatchKey::qualification(12)));
+        assert_eq!(
+            "sf5".parse::<MatchKey>().ok(),
+            Some(MatchKey::new(MatchLevel::SemiFinal, 5, 1))
+        );
+        assert_eq!(
+            "f1m2".parse::<MatchKey>().ok(),
+            Some(MatchKey::new(MatchLevel::Final, 1, 2))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        for key in ["", "0", "qm", "qm0", "sf5m1m2", "sf0m1", "xx3", "-1"] {
+            assert!(key.parse::<MatchKey>().is_err(), "{key:?} should be invalid");
+        }
+    }
+
+    #[test]
+    fn displays_like_tba() {
+        for key in ["qm12", "sf5m1", "f1m2"] {
+            assert_eq!(
+                key.parse::<MatchKey>().map(|key| key.to_string()).ok().as_deref(),
+                Some(key)
+            );
+        }
+    }
+
+    #[test]
+    fn orders_by_level() {
+        assert!(MatchKey::qualification(80) < MatchKey::new(MatchLevel::SemiFinal, 1, 1));
+        assert!(
+            MatchKey::new(MatchLevel::SemiFinal, 13, 1) < MatchKey::new(MatchLevel::Final, 1, 1)
+        );
+    }
+}
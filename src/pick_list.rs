@@ -0,0 +1,274 @@
+//! The pick list of an event: the teams the alliance would pick, in order,
+//! split between first picks, second picks and teams not to pick.
+//!
+//! The list is stored in the `pick_list` table and changed one step at a time
+//! with `PickListChange`, so scouts editing it at the same time don't
+//! overwrite each other's changes.
+
+use core::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{team::TeamNumber, BlueScoutError};
+
+/// The longest comment on a team of the pick list.
+pub const MAX_COMMENT_LENGTH: usize = 1000;
+
+/// The tier of a team in the pick list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum PickTier {
+    FirstPick,
+    SecondPick,
+    DoNotPick,
+}
+
+impl PickTier {
+    /// The tiers, in the order they are listed.
+    pub const ALL: [Self; 3] = [Self::FirstPick, Self::SecondPick, Self::DoNotPick];
+
+    /// Returns the name stored in the `pick_list` table.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::FirstPick => "first_pick",
+            Self::SecondPick => "second_pick",
+            Self::DoNotPick => "do_not_pick",
+        }
+    }
+
+    /// Parses a tier from the name stored in the `pick_list` table.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tier| tier.name() == name)
+    }
+}
+
+impl Display for PickTier {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::FirstPick => write!(f, "First Pick"),
+            Self::SecondPick => write!(f, "Second Pick"),
+            Self::DoNotPick => write!(f, "Do Not Pick"),
+        }
+    }
+}
+
+/// A team in the pick list of an event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct PickListEntry {
+    /// The team number.
+    pub team_number: TeamNumber,
+    /// The tier of the team.
+    pub tier: PickTier,
+    /// A comment on the team, empty if there is none.
+    pub comment: String,
+}
+
+/// A change to the pick list of an event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum PickListChange {
+    /// Adds a team at the end of a tier.
+    Add(TeamNumber, PickTier),
+    /// Removes a team.
+    Remove(TeamNumber),
+    /// Moves a team to the end of another tier.
+    SetTier(TeamNumber, PickTier),
+    /// Moves a team one place up in its tier.
+    MoveUp(TeamNumber),
+    /// Moves a team one place down in its tier.
+    MoveDown(TeamNumber),
+    /// Replaces the comment on a team.
+    Comment(TeamNumber, String),
+}
+
+impl PickListChange {
+    /// Applies the change to a pick list, whose entries are grouped by tier in
+    /// the order of `PickTier::ALL`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the team is already in the list when adding it, if
+    /// it is not in the list otherwise, or if the comment is too long.
+    pub fn apply(self, entries: &mut Vec<PickListEntry>) -> Result<(), BlueScoutError> {
+        let position = |entries: &[PickListEntry], team_number: TeamNumber| {
+            entries
+                .iter()
+                .position(|entry| entry.team_number == team_number)
+                .ok_or_else(|| {
+                    BlueScoutError::validation(
+                        "team_number",
+                        format!("Team {team_number} is not in the pick list"),
+                    )
+                })
+        };
+
+        match self {
+            Self::Add(team_number, tier) => {
+                if position(entries, team_number).is_ok() {
+                    return Err(BlueScoutError::validation(
+                        "team_number",
+                        format!("Team {team_number} is already in the pick list"),
+                    ));
+                }
+                entries.push(PickListEntry {
+                    team_number,
+                    tier,
+                    comment: String::new(),
+                });
+            }
+            Self::Remove(team_number) => {
+                entries.remove(position(entries, team_number)?);
+            }
+            Self::SetTier(team_number, tier) => {
+                let mut entry = entries.remove(position(entries, team_number)?);
+                entry.tier = tier;
+                entries.push(entry);
+            }
+            Self::MoveUp(team_number) => {
+                let index = position(entries, team_number)?;
+                if index > 0 && entries[index - 1].tier == entries[index].tier {
+                    entries.swap(index - 1, index);
+                }
+            }
+            Self::MoveDown(team_number) => {
+                let index = position(entries, team_number)?;
+                if index + 1 < entries.len() && entries[index + 1].tier == entries[index].tier {
+                    entries.swap(index, index + 1);
+                }
+            }
+            Self::Comment(team_number, comment) => {
+                if comment.chars().count() > MAX_COMMENT_LENGTH {
+                    return Err(BlueScoutError::validation(
+                        "comment",
+                        format!("The comment should be at most {MAX_COMMENT_LENGTH} characters"),
+                    ));
+                }
+                let index = position(entries, team_number)?;
+                entries[index].comment = comment.trim().to_owned();
+            }
+        }
+
+        // Added and moved teams go to the end of their tier
+        entries.sort_by_key(|entry| entry.tier);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PickListChange, PickListEntry, PickTier};
+    use crate::team::TeamNumber;
+
+    /// Returns the teams of a pick list with their tiers, in order.
+    fn teams(entries: &[PickListEntry]) -> Vec<(u32, PickTier)> {
+        entries
+            .iter()
+            .map(|entry| (entry.team_number.number, entry.tier))
+            .collect()
+    }
+
+    /// Builds a pick list by adding teams in order.
+    fn pick_list(teams: &[(u32, PickTier)]) -> Vec<PickListEntry> {
+        let mut entries = Vec::new();
+        for &(number, tier) in teams {
+            PickListChange::Add(TeamNumber::new(number), tier)
+                .apply(&mut entries)
+                .expect("Teams should be added");
+        }
+        entries
+    }
+
+    #[test]
+    fn adds_teams_at_the_end_of_their_tier() {
+        let mut entries = pick_list(&[
+            (254, PickTier::SecondPick),
+            (1678, PickTier::FirstPick),
+            (971, PickTier::FirstPick),
+        ]);
+
+        assert_eq!(
+            teams(&entries),
+            [
+                (1678, PickTier::FirstPick),
+                (971, PickTier::FirstPick),
+                (254, PickTier::SecondPick),
+            ]
+        );
+        assert!(PickListChange::Add(TeamNumber::new(254), PickTier::DoNotPick)
+            .apply(&mut entries)
+            .is_err());
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn moves_teams_within_their_tier() {
+        let mut entries = pick_list(&[
+            (1678, PickTier::FirstPick),
+            (971, PickTier::FirstPick),
+            (254, PickTier::SecondPick),
+        ]);
+
+        PickListChange::MoveUp(TeamNumber::new(971))
+            .apply(&mut entries)
+            .expect("Team should be moved");
+        assert_eq!(teams(&entries)[..2], [(971, PickTier::FirstPick), (1678, PickTier::FirstPick)]);
+
+        // Teams don't move past the end of their tier
+        PickListChange::MoveDown(TeamNumber::new(1678))
+            .apply(&mut entries)
+            .expect("Team should be moved");
+        PickListChange::MoveUp(TeamNumber::new(254))
+            .apply(&mut entries)
+            .expect("Team should be moved");
+        assert_eq!(
+            teams(&entries),
+            [
+                (971, PickTier::FirstPick),
+                (1678, PickTier::FirstPick),
+                (254, PickTier::SecondPick),
+            ]
+        );
+    }
+
+    #[test]
+    fn changes_tiers_and_removes_teams() {
+        let mut entries = pick_list(&[
+            (1678, PickTier::FirstPick),
+            (971, PickTier::SecondPick),
+            (254, PickTier::SecondPick),
+        ]);
+
+        PickListChange::SetTier(TeamNumber::new(1678), PickTier::SecondPick)
+            .apply(&mut entries)
+            .expect("Tier should be changed");
+        PickListChange::Remove(TeamNumber::new(971))
+            .apply(&mut entries)
+            .expect("Team should be removed");
+        assert_eq!(
+            teams(&entries),
+            [(254, PickTier::SecondPick), (1678, PickTier::SecondPick)]
+        );
+        assert!(PickListChange::Remove(TeamNumber::new(971))
+            .apply(&mut entries)
+            .is_err());
+    }
+
+    #[test]
+    fn comments_on_teams() {
+        let mut entries = pick_list(&[(254, PickTier::FirstPick)]);
+
+        PickListChange::Comment(TeamNumber::new(254), "  Fast cycles ".to_owned())
+            .apply(&mut entries)
+            .expect("Comment should be set");
+        assert_eq!(entries[0].comment, "Fast cycles");
+        assert!(PickListChange::Comment(TeamNumber::new(254), "x".repeat(1001))
+            .apply(&mut entries)
+            .is_err());
+        assert!(PickListChange::Comment(TeamNumber::new(604), String::new())
+            .apply(&mut entries)
+            .is_err());
+    }
+}
@@ -0,0 +1,1152 @@
+//! The fields and team metrics of the season, loaded at runtime.
+//!
+//! `DataPoint` is compiled into the application, but a season schema file can
+//! change how its fields are shown and validated, add fields and define more
+//! team metrics without rebuilding it. The server loads the JSON or TOML file
+//! named by the `SEASON_SCHEMA` environment variable at startup. The database
+//! columns, the scouting form, the data table and the XLSX export all follow
+//! the resulting `SeasonSchema`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{
+        DataPoint, DataType, DataTypeName, Distribution, FilterType, ScoutEntry, Statistic, Widget,
+    },
+    BlueScoutError,
+};
+
+#[cfg(feature = "ssr")]
+use once_cell::sync::OnceCell;
+
+/// The environment variable holding the path of the season schema file.
+pub const SEASON_SCHEMA_VAR: &str = "SEASON_SCHEMA";
+
+/// The columns of the entries table that fields can't be named after.
+const RESERVED_COLUMNS: &[&str] = &["id", "deleted_at"];
+
+/// The contents of a season schema file.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct SeasonSchemaFile {
+    /// Changes to the fields of `DataPoint`, and new fields.
+    pub fields: Vec<FieldConfig>,
+    /// Team metrics computed in addition to the ones of the season.
+    pub metrics: Vec<MetricConfig>,
+}
+
+/// A field of a season schema file.
+///
+/// When `name` is a field of `DataPoint`, the other settings replace the
+/// compiled ones and `type` has to match the compiled type if given.
+/// Otherwise a new nullable column is added, and `type` is required.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct FieldConfig {
+    /// The name of the field, also the name of its column.
+    pub name: String,
+    /// The display name of the field.
+    pub pretty_name: Option<String>,
+    /// The type of the values of the field.
+    #[serde(rename = "type")]
+    pub data_type: Option<DataTypeName>,
+    /// The filter of the column of the field in the data table.
+    pub filter: Option<FilterType>,
+    /// The input used for the field on the scouting form.
+    pub widget: Option<Widget>,
+    /// The heading of the section of the form the field is in.
+    pub section: Option<String>,
+    /// The label of the row of inputs the field is shown in.
+    pub group: Option<String>,
+    /// The placeholder of the input.
+    pub placeholder: Option<String>,
+    /// The smallest allowed value (or length for strings).
+    pub min: Option<f64>,
+    /// The largest allowed value (or length for strings).
+    pub max: Option<f64>,
+    /// Whether the value must be present and not blank.
+    pub required: Option<bool>,
+    /// A regex the whole value must match.
+    pub regex: Option<String>,
+    /// The options of a new enum field, or the allowed values of any other
+    /// field.
+    pub options: Vec<String>,
+}
+
+/// How the values of a metric are combined over the entries of a team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Aggregate {
+    /// The average of the values, skipping missing ones.
+    Average,
+    /// The sum of the values.
+    Sum,
+    /// The smallest value.
+    Min,
+    /// The largest value.
+    Max,
+    /// The number of entries with a matching value.
+    Count,
+    /// The middle value, or the average of the two middle values.
+    Median,
+    /// The population standard deviation of the values.
+    #[serde(rename = "std_dev")]
+    StdDev,
+    /// The average of the values of the latest `matches` entries.
+    Recent,
+}
+
+/// A team metric of a season schema file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct MetricConfig {
+    /// The name of the metric.
+    pub name: String,
+    /// The display name of the metric.
+    pub pretty_name: String,
+    /// How the values are combined over the entries of a team.
+    pub aggregate: Aggregate,
+    /// The fields the metric is computed from. The values of numeric fields
+    /// are added up per entry, `bool` fields count as 1 when set.
+    pub fields: Vec<String>,
+    /// For `count`, the value a field must have for the entry to be counted.
+    /// Without it, entries with any set, non-zero value are counted.
+    #[serde(default)]
+    pub equals: Option<String>,
+    /// For `recent`, the number of latest entries averaged.
+    #[serde(default)]
+    pub matches: Option<usize>,
+}
+
+/// The regex of a field, compiled once when the schema is loaded. It is sent
+/// to the client as its pattern.
+#[derive(Debug, Clone)]
+pub struct FieldRegex {
+    /// The compiled regex.
+    regex: regex::Regex,
+}
+
+impl FieldRegex {
+    /// Compiles the regex of a field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is not a valid regex.
+    pub fn new(field: &str, pattern: &str) -> Result<Self, BlueScoutError> {
+        let regex = regex::Regex::new(pattern).map_err(|err| {
+            BlueScoutError::custom(format!(
+                "The regex of the field {field} is not valid: {err}"
+            ))
+        })?;
+        Ok(Self { regex })
+    }
+
+    /// Returns the pattern the regex was compiled from.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// Checks if the regex matches the text.
+    #[must_use]
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for FieldRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for FieldRegex {}
+
+impl Serialize for FieldRegex {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldRegex {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        let regex = regex::Regex::new(&pattern).map_err(serde::de::Error::custom)?;
+        Ok(Self { regex })
+    }
+}
+
+/// A field of the season, resolved from `DataPoint` and the schema file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SchemaField {
+    /// The name of the field, also the name of its column.
+    pub name: String,
+    /// The display name of the field.
+    pub pretty_name: String,
+    /// The type of the values of the field.
+    pub data_type: DataTypeName,
+    /// Whether the value can be missing.
+    pub nullable: bool,
+    /// The filter of the column of the field in the data table.
+    pub filter_type: FilterType,
+    /// The input used for the field on the scouting form.
+    pub widget: Widget,
+    /// The heading of the section of the form the field is in.
+    pub section: Option<String>,
+    /// The label of the row of inputs the field is shown in.
+    pub group: Option<String>,
+    /// The placeholder of the input.
+    pub placeholder: Option<String>,
+    /// The smallest allowed value (or length), as text for form inputs.
+    pub min: Option<String>,
+    /// The largest allowed value (or length), as text for form inputs.
+    pub max: Option<String>,
+    /// Whether the value must be present and not blank.
+    pub required: bool,
+    /// A regex the whole value must match.
+    pub regex: Option<FieldRegex>,
+    /// The options the value is picked from, for enum fields.
+    pub options: Vec<String>,
+    /// The allowed values, if restricted to a fixed list.
+    pub allowed: Vec<String>,
+    /// Whether the field is not part of `DataPoint` and is stored in
+    /// `ScoutEntry::extra`.
+    pub extra: bool,
+}
+
+impl SchemaField {
+    /// Creates the field of a `DataPoint` field from its compiled metadata.
+    fn compiled(name: &'static str) -> Self {
+        let form = DataPoint::form_fields()
+            .iter()
+            .find(|form| form.name == name)
+            .expect("Every field should have form metadata");
+        let constraints = DataPoint::field_constraints(name).unwrap_or_default();
+        Self {
+            name: name.to_owned(),
+            pretty_name: form.pretty_name.to_owned(),
+            data_type: DataPoint::get_field_type(name).expect("Every field should have a type"),
+            nullable: DataPoint::is_field_nullable(name),
+            filter_type: DataPoint::get_field_filter_type(name)
+                .expect("Every field should have a filter type"),
+            widget: form.widget,
+            section: form.section.map(ToOwned::to_owned),
+            group: form.group.map(ToOwned::to_owned),
+            placeholder: form.placeholder.map(ToOwned::to_owned),
+            min: constraints.min.map(ToOwned::to_owned),
+            max: constraints.max.map(ToOwned::to_owned),
+            required: constraints.required,
+            regex: constraints.regex.map(|regex| {
+                FieldRegex::new(name, regex).expect("Compiled regexes should be valid")
+            }),
+            options: DataPoint::field_options(name)
+                .unwrap_or_default()
+                .iter()
+                .map(|&option| option.to_owned())
+                .collect(),
+            allowed: constraints
+                .allowed
+                .iter()
+                .map(|&value| value.to_owned())
+                .collect(),
+            extra: false,
+        }
+    }
+
+    /// Creates a field that is not part of `DataPoint`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a valid column name or the type is
+    /// missing.
+    fn extra(config: &FieldConfig) -> Result<Self, BlueScoutError> {
+        let name = &config.name;
+        let is_identifier = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !is_identifier {
+            return Err(BlueScoutError::custom(format!(
+                "Invalid field name {name:?}: use lowercase letters, digits and underscores"
+            )));
+        }
+        if RESERVED_COLUMNS.contains(&name.as_str()) {
+            return Err(BlueScoutError::custom(format!(
+                "The field name {name} is reserved"
+            )));
+        }
+        let data_type = config
+            .data_type
+            .ok_or_else(|| BlueScoutError::custom(format!("The new field {name} needs a type")))?;
+        if data_type == DataTypeName::Enum && config.options.is_empty() {
+            return Err(BlueScoutError::custom(format!(
+                "The enum field {name} needs options"
+            )));
+        }
+
+        let mut field = Self {
+            name: name.clone(),
+            pretty_name: name.clone(),
+            data_type,
+            nullable: true,
+            filter_type: match data_type {
+                DataTypeName::Bool | DataTypeName::Enum => FilterType::Select,
+                _ => FilterType::Normal,
+            },
+            widget: match data_type {
+                DataTypeName::Bool => Widget::Checkbox,
+                DataTypeName::Enum => Widget::Select,
+                DataTypeName::String => Widget::Text,
+                _ => Widget::Number,
+            },
+            section: None,
+            group: None,
+            placeholder: None,
+            min: None,
+            max: None,
+            required: false,
+            regex: None,
+            options: Vec::new(),
+            allowed: Vec::new(),
+            extra: true,
+        };
+        if data_type == DataTypeName::Enum {
+            field.options.clone_from(&config.options);
+        }
+        field.apply(config)?;
+        Ok(field)
+    }
+
+    /// Replaces the settings of the field with the ones given in the schema
+    /// file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the regex of the file is not valid.
+    fn apply(&mut self, config: &FieldConfig) -> Result<(), BlueScoutError> {
+        let format_bound = |bound: f64| bound.to_string();
+        if let Some(ref pretty_name) = config.pretty_name {
+            pretty_name.clone_into(&mut self.pretty_name);
+        }
+        if let Some(filter_type) = config.filter {
+            self.filter_type = filter_type;
+        }
+        if let Some(widget) = config.widget {
+            self.widget = widget;
+        }
+        if config.section.is_some() {
+            self.section.clone_from(&config.section);
+        }
+        if config.group.is_some() {
+            self.group.clone_from(&config.group);
+        }
+        if config.placeholder.is_some() {
+            self.placeholder.clone_from(&config.placeholder);
+        }
+        if let Some(min) = config.min {
+            self.min = Some(format_bound(min));
+        }
+        if let Some(max) = config.max {
+            self.max = Some(format_bound(max));
+        }
+        if let Some(required) = config.required {
+            self.required = required;
+        }
+        if let Some(ref regex) = config.regex {
+            self.regex = Some(FieldRegex::new(&self.name, regex)?);
+        }
+        if !config.options.is_empty() {
+            self.allowed.clone_from(&config.options);
+        }
+
+        Ok(())
+    }
+
+    /// The name of the input of the field on the scouting form. `DataPoint`
+    /// fields match the fields of `InsertDataArgs`, the others are sent in the
+    /// `extra` map of `insert_data`.
+    #[must_use]
+    pub fn input_name(&self) -> String {
+        if self.extra {
+            format!("extra[{}]", self.name)
+        } else {
+            format!("args[{}]", self.name)
+        }
+    }
+
+    /// Returns the SQL type of the column of the field.
+    #[must_use]
+    pub const fn sql_type(&self) -> &'static str {
+        match self.data_type {
+            DataTypeName::U16 => "USMALLINT",
+            DataTypeName::U32 => "UINTEGER",
+            DataTypeName::U64 => "UBIGINT",
+            DataTypeName::I16 => "SMALLINT",
+            DataTypeName::I32 => "INTEGER",
+            DataTypeName::I64 => "BIGINT",
+            DataTypeName::Bool => "BOOLEAN",
+            DataTypeName::Float => "FLOAT",
+            // The options of new enum fields are checked by `check` instead of
+            // a `DuckDB` enum type, so they can change between seasons
+            DataTypeName::String | DataTypeName::Enum => "VARCHAR",
+        }
+    }
+
+    /// Parses the value of the field from the text of its form input.
+    ///
+    /// # Returns
+    ///
+    /// The value, `DataType::Null` for a blank input of a nullable field, or
+    /// `None` if the text is not a valid value.
+    #[must_use]
+    pub fn parse_input(&self, text: Option<&str>) -> Option<DataType> {
+        let text = text.map(str::trim).unwrap_or_default();
+        match self.data_type {
+            // Unchecked checkboxes are not sent
+            DataTypeName::Bool if self.widget == Widget::Checkbox => {
+                Some(DataType::Bool(text == "on"))
+            }
+            DataTypeName::Bool if text == "off" => Some(DataType::Bool(false)),
+            _ if text.is_empty() && self.nullable => Some(DataType::Null),
+            data_type => data_type.parse_value(text),
+        }
+    }
+
+    /// Checks a value of the field against its constraints.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BlueScoutError::Validation` for the first constraint that is
+    /// violated. Missing values are only checked for `required`.
+    pub fn check(&self, value: &DataType) -> Result<(), BlueScoutError> {
+        let pretty_name = &self.pretty_name;
+        let error = |message: String| Err(BlueScoutError::validation(&self.name, message));
+        let text = match *value {
+            DataType::String(ref text) | DataType::Enum(ref text) => Some(text.as_str()),
+            DataType::Null if self.required => return error(format!("{pretty_name} is required")),
+            DataType::Null => return Ok(()),
+            _ => None,
+        };
+        if self.required && text.is_some_and(|text| text.trim().is_empty()) {
+            return error(format!("{pretty_name} is required"));
+        }
+
+        let is_string = self.data_type == DataTypeName::String;
+        let measure = if is_string {
+            text.map(|text| text.chars().count() as f64)
+        } else {
+            value.as_f64()
+        };
+        let unit = if is_string { " characters long" } else { "" };
+        let bound =
+            |bound: &Option<String>| bound.as_deref().and_then(|bound| bound.parse::<f64>().ok());
+        if let Some(measure) = measure {
+            if let Some(min) = bound(&self.min)
+                && measure < min
+            {
+                return error(format!("{pretty_name} must be at least {min}{unit}"));
+            }
+            if let Some(max) = bound(&self.max)
+                && measure > max
+            {
+                return error(format!("{pretty_name} must be at most {max}{unit}"));
+            }
+        }
+
+        if let Some(ref regex) = self.regex
+            && let Some(text) = text
+            && !regex.is_match(text)
+        {
+            return error(format!("{pretty_name} has an invalid format"));
+        }
+
+        let choices = if self.extra && self.data_type == DataTypeName::Enum {
+            &self.options
+        } else {
+            &self.allowed
+        };
+        if !choices.is_empty() && !choices.contains(&value.to_string()) {
+            return error(format!(
+                "{pretty_name} must be one of: {}",
+                choices.join(", ")
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// The value of a runtime team metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct MetricValue {
+    /// The name of the metric.
+    pub name: String,
+    /// The display name of the metric.
+    pub pretty_name: String,
+    /// The value of the metric.
+    pub value: DataType,
+}
+
+/// The fields and team metrics of the season.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SeasonSchema {
+    /// The fields of `DataPoint` followed by the new fields, in form order.
+    pub fields: Vec<SchemaField>,
+    /// Team metrics computed in addition to the ones of the season.
+    pub metrics: Vec<MetricConfig>,
+}
+
+impl SeasonSchema {
+    /// Creates the schema described by `DataPoint` alone.
+    #[must_use]
+    pub fn compiled() -> Self {
+        Self {
+            fields: DataPoint::field_names()
+                .iter()
+                .map(|&name| SchemaField::compiled(name))
+                .collect(),
+            metrics: Vec::new(),
+        }
+    }
+
+    /// Creates the schema described by `DataPoint` and a schema file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is not valid, see `apply`.
+    pub fn from_file(file: SeasonSchemaFile) -> Result<Self, BlueScoutError> {
+        let mut schema = Self::compiled();
+        schema.apply(file)?;
+        Ok(schema)
+    }
+
+    /// Applies a schema file over the schema.
+    ///
+    /// Fields already in the schema get the settings of the file. New fields
+    /// are added after the existing ones, and a new field without a section is
+    /// shown in the section of the field before it. Metrics replace the
+    /// existing metrics of the same name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field is declared twice, changes the type of an
+    /// existing field or is not a valid new field, or if a metric uses an
+    /// unknown field.
+    pub fn apply(&mut self, file: SeasonSchemaFile) -> Result<(), BlueScoutError> {
+        for (i, config) in file.fields.iter().enumerate() {
+            if file.fields[..i]
+                .iter()
+                .any(|other| other.name == config.name)
+            {
+                return Err(BlueScoutError::custom(format!(
+                    "The field {} is declared twice",
+                    config.name
+                )));
+            }
+
+            if let Some(field) = self
+                .fields
+                .iter_mut()
+                .find(|field| field.name == config.name)
+            {
+                if let Some(data_type) = config.data_type
+                    && data_type != field.data_type
+                {
+                    let type_name =
+                        |data_type: DataTypeName| format!("{data_type:?}").to_lowercase();
+                    return Err(BlueScoutError::custom(format!(
+                        "The field {} has type {}, it can't be changed to {}",
+                        field.name,
+                        type_name(field.data_type),
+                        type_name(data_type)
+                    )));
+                }
+                field.apply(config)?;
+                continue;
+            }
+
+            let mut field = SchemaField::extra(config)?;
+            if field.section.is_none() {
+                field.section = self.fields.last().and_then(|last| last.section.clone());
+            }
+            self.fields.push(field);
+        }
+
+        for (i, metric) in file.metrics.iter().enumerate() {
+            if file.metrics[..i]
+                .iter()
+                .any(|other| other.name == metric.name)
+            {
+                return Err(BlueScoutError::custom(format!(
+                    "The metric {} is declared twice",
+                    metric.name
+                )));
+            }
+            if metric.fields.is_empty() {
+                return Err(BlueScoutError::custom(format!(
+                    "The metric {} needs at least one field",
+                    metric.name
+                )));
+            }
+            if let Some(name) = metric
+                .fields
+                .iter()
+                .find(|&name| self.field(name).is_none())
+            {
+                return Err(BlueScoutError::custom(format!(
+                    "The metric {} uses the unknown field {name}",
+                    metric.name
+                )));
+            }
+            if metric.aggregate == Aggregate::Recent
+                && metric.matches.is_none_or(|matches| matches == 0)
+            {
+                return Err(BlueScoutError::custom(format!(
+                    "The metric {} needs the number of matches to average",
+                    metric.name
+                )));
+            }
+        }
+        for metric in file.metrics {
+            match self
+                .metrics
+                .iter_mut()
+                .find(|other| other.name == metric.name)
+            {
+                Some(other) => *other = metric,
+                None => self.metrics.push(metric),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the field with the given name.
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&SchemaField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Returns the fields that are not part of `DataPoint`.
+    pub fn extra_fields(&self) -> impl Iterator<Item = &SchemaField> {
+        self.fields.iter().filter(|field| field.extra)
+    }
+
+    /// Parses the values of the new fields from the `extra` inputs of the
+    /// scouting form.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BlueScoutError::Validation` if an input is not a valid value
+    /// of its field.
+    pub fn parse_extra(
+        &self,
+        inputs: &HashMap<String, String>,
+    ) -> Result<BTreeMap<String, DataType>, BlueScoutError> {
+        self.extra_fields()
+            .map(|field| {
+                let value = field
+                    .parse_input(inputs.get(&field.name).map(String::as_str))
+                    .ok_or_else(|| {
+                        BlueScoutError::validation(
+                            &field.name,
+                            format!("{} has an invalid value", field.pretty_name),
+                        )
+                    })?;
+                Ok((field.name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Checks an entry against the constraints of the fields.
+    ///
+    /// New fields missing from `extra` are not checked, since they can't be
+    /// edited after the entry is inserted.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BlueScoutError::Validation` for the first constraint that is
+    /// violated.
+    pub fn validate(
+        &self,
+        data: &DataPoint,
+        extra: &BTreeMap<String, DataType>,
+    ) -> Result<(), BlueScoutError> {
+        for field in &self.fields {
+            let value = if field.extra {
+                extra.get(&field.name).cloned()
+            } else {
+                data.get_field(&field.name)
+            };
+            if let Some(value) = value {
+                field.check(&value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Computes the runtime metrics of a team from its entries, in match order.
+    #[must_use]
+    pub fn compute_metrics(&self, entries: &[ScoutEntry]) -> Vec<MetricValue> {
+        self.metrics
+            .iter()
+            .map(|metric| MetricValue {
+                name: metric.name.clone(),
+                pretty_name: metric.pretty_name.clone(),
+                value: self.compute_metric(metric, entries),
+            })
+            .collect()
+    }
+
+    /// Computes the value of a runtime metric from the entries of a team.
+    // Metric values are stored as `f32` and `i64`, like the compiled metrics
+    #[allow(clippy::cast_possible_truncation)]
+    fn compute_metric(&self, metric: &MetricConfig, entries: &[ScoutEntry]) -> DataType {
+        let values = || {
+            entries.iter().map(|entry| {
+                metric
+                    .fields
+                    .iter()
+                    .map(|name| entry.get_field(name).unwrap_or(DataType::Null))
+            })
+        };
+
+        if metric.aggregate == Aggregate::Count {
+            let is_counted = |value: DataType| {
+                metric.equals.as_ref().map_or_else(
+                    || match value {
+                        DataType::Null | DataType::Bool(false) => false,
+                        ref value => value.as_f64().is_none_or(|number| number != 0.0),
+                    },
+                    |expected| value.to_string() == *expected,
+                )
+            };
+            let count = values()
+                .map(|mut values| values.any(&is_counted))
+                .filter(|&counted| counted)
+                .count();
+            return DataType::U32(
+                u32::try_from(count).expect("This should not be bigger than u32::MAX"),
+            );
+        }
+
+        // The per entry totals, skipping entries without any value
+        let totals = values()
+            .filter_map(|values| {
+                values
+                    .filter_map(|value| match value {
+                        DataType::Bool(set) => Some(if set { 1.0 } else { 0.0 }),
+                        ref value => value.as_f64(),
+                    })
+                    .reduce(|total, value| total + value)
+            })
+            .collect::<Vec<_>>();
+        if totals.is_empty() {
+            return DataType::Null;
+        }
+
+        let statistic = |statistic| {
+            Distribution::new(totals.iter().map(|&total| Some(total as f32)))
+                .get(statistic)
+                .map_or(DataType::Null, DataType::Float)
+        };
+        let total = match metric.aggregate {
+            Aggregate::Average => {
+                return DataType::Float((totals.iter().sum::<f64>() / totals.len() as f64) as f32);
+            }
+            Aggregate::Median => return statistic(Statistic::Median),
+            Aggregate::StdDev => return statistic(Statistic::StdDev),
+            Aggregate::Recent => {
+                return statistic(Statistic::Recent(metric.matches.unwrap_or(1)));
+            }
+            Aggregate::Sum => totals.iter().sum(),
+            Aggregate::Min => totals.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregate::Max => totals.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregate::Count => unreachable!("Counts are computed above"),
+        };
+        let is_integer = metric.fields.iter().all(|name| {
+            self.field(name)
+                .is_some_and(|field| field.data_type != DataTypeName::Float)
+        });
+        if is_integer {
+            DataType::I64(total as i64)
+        } else {
+            DataType::Float(total as f32)
+        }
+    }
+}
+
+/// The season schema loaded at startup.
+#[cfg(feature = "ssr")]
+static SEASON_SCHEMA: OnceCell<SeasonSchema> = OnceCell::new();
+
+/// Loads the season schema: the schema of the game picked at startup, with the
+/// file named by the `SEASON_SCHEMA` environment variable applied over it if
+/// it is set.
+///
+/// Files ending in `.toml` are read as TOML, others as JSON.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or is not a valid schema.
+///
+/// # Panics
+///
+/// Panics if the schema has already been loaded.
+#[cfg(feature = "ssr")]
+pub fn load_season_schema() -> anyhow::Result<()> {
+    use anyhow::Context as _;
+
+    let mut schema = SeasonSchema::from_file(crate::game::game().schema())?;
+    if let Ok(path) = std::env::var(SEASON_SCHEMA_VAR) {
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read the season schema {path}"))?;
+        schema.apply(parse_season_schema_file(&path, &contents)?)?;
+    }
+    assert!(
+        SEASON_SCHEMA.set(schema).is_ok(),
+        "Season schema already loaded"
+    );
+
+    Ok(())
+}
+
+/// Parses the contents of a season schema file, as TOML if its path ends in
+/// `.toml` and as JSON otherwise.
+///
+/// # Errors
+///
+/// Returns an error if the contents are not a valid schema file.
+#[cfg(feature = "ssr")]
+fn parse_season_schema_file(path: &str, contents: &str) -> anyhow::Result<SeasonSchemaFile> {
+    use anyhow::Context as _;
+
+    let is_toml = std::path::Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+    if is_toml {
+        toml::from_str(contents).map_err(anyhow::Error::from)
+    } else {
+        serde_json::from_str(contents).map_err(anyhow::Error::from)
+    }
+    .with_context(|| format!("Failed to parse the season schema {path}"))
+}
+
+/// Returns the season schema loaded at startup, or the schema described by
+/// `DataPoint` alone if none has been loaded.
+#[cfg(feature = "ssr")]
+pub fn season_schema() -> &'static SeasonSchema {
+    SEASON_SCHEMA.get_or_init(SeasonSchema::compiled)
+}
+
+/// Fetches the season schema loaded by the server.
+///
+/// # Returns
+///
+/// A `Result` containing the `SeasonSchema` or a `BlueScoutError`.
+#[server(endpoint = "fetch_season_schema")]
+pub async fn fetch_season_schema() -> Result<SeasonSchema, BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return Ok(season_schema().clone());
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[cfg(feature = "ssr")]
+    use super::parse_season_schema_file;
+    use super::{Aggregate, FieldConfig, MetricConfig, SeasonSchema, SeasonSchemaFile};
+    use crate::{
+        data::{DataPoint, DataType, DataTypeName, ScoutEntry},
+        BlueScoutError,
+    };
+
+    /// Returns a new field of a schema file.
+    fn new_field(name: &str, data_type: DataTypeName) -> FieldConfig {
+        FieldConfig {
+            name: name.to_owned(),
+            data_type: Some(data_type),
+            ..FieldConfig::default()
+        }
+    }
+
+    /// Returns a schema with the fields of a schema file added.
+    fn schema_with(fields: Vec<FieldConfig>) -> SeasonSchema {
+        SeasonSchema::from_file(SeasonSchemaFile {
+            fields,
+            metrics: Vec::new(),
+        })
+        .expect("The schema should be valid")
+    }
+
+    /// Returns the field a validation error is about, or `None` if the
+    /// result is not a validation error.
+    fn invalid_field<T>(result: Result<T, BlueScoutError>) -> Option<String> {
+        match result {
+            Err(BlueScoutError::Validation { field, .. }) => Some(field),
+            _ => None,
+        }
+    }
+
+    /// Returns a metric combining the values of fields.
+    fn metric(aggregate: Aggregate, fields: &[&str]) -> MetricConfig {
+        MetricConfig {
+            name: "metric".to_owned(),
+            pretty_name: "Metric".to_owned(),
+            aggregate,
+            fields: fields.iter().map(|&field| field.to_owned()).collect(),
+            equals: None,
+            matches: None,
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn parses_toml_schema_files() {
+        let contents = r#"
+            [[fields]]
+            name = "penalties"
+            type = "u16"
+            max = 10
+
+            [[metrics]]
+            name = "avg_penalties"
+            pretty_name = "Avg Penalties"
+            aggregate = "average"
+            fields = ["penalties"]
+        "#;
+
+        let file = parse_season_schema_file("season.toml", contents)
+            .expect("The TOML file should be parsed");
+        assert_eq!(file.fields[0].name, "penalties");
+        assert_eq!(file.fields[0].data_type, Some(DataTypeName::U16));
+        assert_eq!(file.fields[0].max, Some(10.0));
+        assert_eq!(file.metrics[0].aggregate, Aggregate::Average);
+        assert!(parse_season_schema_file("season.json", contents).is_err());
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn parses_json_schema_files() {
+        let contents = r#"{
+            "fields": [{ "name": "notes", "max": 200, "required": true }],
+            "metrics": [{
+                "name": "recent_penalties",
+                "pretty_name": "Recent Penalties",
+                "aggregate": "recent",
+                "fields": ["dropped_coral"],
+                "matches": 3
+            }]
+        }"#;
+
+        let file = parse_season_schema_file("season.json", contents)
+            .expect("The JSON file should be parsed");
+        assert_eq!(file.fields[0].required, Some(true));
+        assert_eq!(file.metrics[0].aggregate, Aggregate::Recent);
+        assert_eq!(file.metrics[0].matches, Some(3));
+        assert!(parse_season_schema_file("season.json", r#"{ "colors": [] }"#).is_err());
+    }
+
+    #[test]
+    fn parses_form_inputs() {
+        let schema = schema_with(vec![
+            new_field("penalties", DataTypeName::U16),
+            FieldConfig {
+                options: vec!["Low".to_owned(), "High".to_owned()],
+                ..new_field("intake", DataTypeName::Enum)
+            },
+        ]);
+        let penalties = schema.field("penalties").expect("The field should exist");
+        let intake = schema.field("intake").expect("The field should exist");
+        let match_number = schema
+            .field("match_number")
+            .expect("The field should exist");
+
+        assert_eq!(penalties.parse_input(Some(" 12 ")), Some(DataType::U16(12)));
+        assert_eq!(penalties.parse_input(Some("  ")), Some(DataType::Null));
+        assert_eq!(penalties.parse_input(None), Some(DataType::Null));
+        assert_eq!(penalties.parse_input(Some("twelve")), None);
+        assert_eq!(penalties.parse_input(Some("-1")), None);
+        assert_eq!(match_number.parse_input(Some("")), None);
+        assert_eq!(
+            intake.parse_input(Some("High")),
+            Some(DataType::Enum("High".to_owned()))
+        );
+        assert!(intake.check(&DataType::Enum("High".to_owned())).is_ok());
+        assert_eq!(
+            invalid_field(intake.check(&DataType::Enum("Medium".to_owned()))),
+            Some("intake".to_owned())
+        );
+    }
+
+    #[test]
+    fn checks_constraints() {
+        let schema = schema_with(vec![
+            FieldConfig {
+                min: Some(1.0),
+                max: Some(10.0),
+                ..new_field("penalties", DataTypeName::U16)
+            },
+            FieldConfig {
+                required: Some(true),
+                regex: Some("^[A-Z]+$".to_owned()),
+                ..new_field("driver", DataTypeName::String)
+            },
+        ]);
+        let penalties = schema.field("penalties").expect("The field should exist");
+        let driver = schema.field("driver").expect("The field should exist");
+        let notes = schema.field("notes").expect("The field should exist");
+
+        assert!(penalties.check(&DataType::U16(5)).is_ok());
+        assert!(penalties.check(&DataType::Null).is_ok());
+        for value in [0, 11] {
+            assert_eq!(
+                invalid_field(penalties.check(&DataType::U16(value))),
+                Some("penalties".to_owned())
+            );
+        }
+
+        assert!(driver.check(&DataType::String("ABC".to_owned())).is_ok());
+        for value in [
+            DataType::String("abc".to_owned()),
+            DataType::String("  ".to_owned()),
+            DataType::Null,
+        ] {
+            assert_eq!(
+                invalid_field(driver.check(&value)),
+                Some("driver".to_owned())
+            );
+        }
+
+        assert_eq!(
+            invalid_field(notes.check(&DataType::String("a".repeat(1001)))),
+            Some("notes".to_owned())
+        );
+    }
+
+    #[test]
+    fn parses_and_validates_extra_fields() {
+        let schema = schema_with(vec![
+            FieldConfig {
+                max: Some(10.0),
+                ..new_field("penalties", DataTypeName::U16)
+            },
+            new_field("driver", DataTypeName::String),
+        ]);
+
+        let inputs = HashMap::from([("penalties".to_owned(), "3".to_owned())]);
+        let extra = schema
+            .parse_extra(&inputs)
+            .expect("The inputs should be parsed");
+        assert_eq!(
+            extra,
+            BTreeMap::from([
+                ("driver".to_owned(), DataType::Null),
+                ("penalties".to_owned(), DataType::U16(3)),
+            ])
+        );
+        let inputs = HashMap::from([("penalties".to_owned(), "many".to_owned())]);
+        assert_eq!(
+            invalid_field(schema.parse_extra(&inputs)),
+            Some("penalties".to_owned())
+        );
+
+        let data = DataPoint::empty();
+        assert!(schema.validate(&data, &extra).is_ok());
+        assert!(schema.validate(&data, &BTreeMap::new()).is_ok());
+        let too_many = BTreeMap::from([("penalties".to_owned(), DataType::U16(11))]);
+        assert_eq!(
+            invalid_field(schema.validate(&data, &too_many)),
+            Some("penalties".to_owned())
+        );
+        let data = DataPoint {
+            match_number: 0,
+            ..DataPoint::empty()
+        };
+        assert_eq!(
+            invalid_field(schema.validate(&data, &extra)),
+            Some("match_number".to_owned())
+        );
+    }
+
+    #[test]
+    fn computes_metrics_for_every_aggregate() {
+        let schema = schema_with(vec![new_field("penalties", DataTypeName::U16)]);
+        let entries = [Some(3), None, Some(5), Some(2)]
+            .into_iter()
+            .zip(1..)
+            .map(|(penalties, id)| ScoutEntry {
+                id,
+                data: DataPoint {
+                    match_number: u16::try_from(id).expect("The match should fit in a u16"),
+                    auto_leave: penalties.is_some(),
+                    l1_coral: 1,
+                    l2_coral: 2,
+                    ..DataPoint::empty()
+                },
+                extra: penalties
+                    .map(|penalties| ("penalties".to_owned(), DataType::U16(penalties)))
+                    .into_iter()
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        let compute = |config: &MetricConfig| schema.compute_metric(config, &entries);
+        let penalties = |aggregate| metric(aggregate, &["penalties"]);
+
+        assert_eq!(
+            compute(&penalties(Aggregate::Average)),
+            DataType::Float((10.0_f64 / 3.0) as f32)
+        );
+        assert_eq!(compute(&penalties(Aggregate::Sum)), DataType::I64(10));
+        assert_eq!(compute(&penalties(Aggregate::Min)), DataType::I64(2));
+        assert_eq!(compute(&penalties(Aggregate::Max)), DataType::I64(5));
+        assert_eq!(compute(&penalties(Aggregate::Median)), DataType::Float(3.0));
+        let std_dev = compute(&penalties(Aggregate::StdDev))
+            .as_f64()
+            .expect("The standard deviation should be a number");
+        assert!((std_dev - (14.0_f64 / 9.0).sqrt()).abs() < 1e-5);
+        let recent = MetricConfig {
+            matches: Some(2),
+            ..penalties(Aggregate::Recent)
+        };
+        assert_eq!(compute(&recent), DataType::Float(3.5));
+
+        assert_eq!(compute(&penalties(Aggregate::Count)), DataType::U32(3));
+        let fives = MetricConfig {
+            equals: Some("5".to_owned()),
+            ..penalties(Aggregate::Count)
+        };
+        assert_eq!(compute(&fives), DataType::U32(1));
+
+        // Fields are added up per entry, and set `bool` fields count as 1
+        assert_eq!(
+            compute(&metric(Aggregate::Sum, &["l1_coral", "l2_coral"])),
+            DataType::I64(12)
+        );
+        assert_eq!(
+            compute(&metric(Aggregate::Sum, &["auto_leave"])),
+            DataType::I64(3)
+        );
+        assert_eq!(
+            schema.compute_metric(&penalties(Aggregate::Average), &[]),
+            DataType::Null
+        );
+    }
+}
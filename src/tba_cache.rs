@@ -0,0 +1,225 @@
+//! Caches the responses of the TBA API in the `tba_cache` table.
+//!
+//! A cached response is used without asking TBA until the `max-age` of its
+//! `Cache-Control` header runs out. It is then revalidated with its `ETag`,
+//! so TBA only sends the body again when it has changed. When TBA can't be
+//! reached, the cached response is used however old it is.
+//!
+//! In the replay mode of `tba_fixtures`, the recorded responses are served
+//! instead and the cache is left untouched.
+
+#![cfg(feature = "ssr")]
+
+use reqwest::{header, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    api_config,
+    db::{get_cached_response, store_cached_response},
+    tba_fixtures::{read_fixture, tba_mode, write_fixture, TbaMode},
+    CacheAge,
+};
+
+/// A response of TBA stored in the `tba_cache` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CachedResponse {
+    /// The `ETag` header of the response, if any.
+    pub etag: Option<String>,
+    /// The body of the response.
+    pub body: String,
+    /// When the response was last fetched or revalidated, in seconds since
+    /// the Unix epoch.
+    pub fetched_at: i64,
+    /// How long the response can be used without revalidating it, in seconds,
+    /// from the `Cache-Control` header.
+    pub max_age: Option<i64>,
+}
+
+impl CachedResponse {
+    /// Whether the response can still be used without revalidating it.
+    fn is_fresh(&self, now: i64) -> bool {
+        self.max_age
+            .is_some_and(|max_age| now < self.fetched_at.saturating_add(max_age))
+    }
+}
+
+/// Parses the `max-age` directive of a `Cache-Control` header.
+fn parse_max_age(headers: &header::HeaderMap) -> Option<i64> {
+    headers
+        .get(header::CACHE_CONTROL)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .find_map(|directive| directive.trim().strip_prefix("max-age=")?.parse().ok())
+}
+
+/// Sends a request to TBA, revalidating the cached response if there is one.
+///
+/// # Returns
+///
+/// The new response, or `None` if the cached one has not changed, along with
+/// its `ETag` and `max-age`.
+///
+/// # Errors
+///
+/// Returns an error if TBA can't be reached or doesn't return a success.
+async fn request(
+    path: &str,
+    cached: Option<&CachedResponse>,
+) -> anyhow::Result<(Option<String>, Option<String>, Option<i64>)> {
+    let config = api_config();
+    let mut request = config
+        .client
+        .get(format!("{}{path}", config.base_path));
+    if let Some(ref user_agent) = config.user_agent {
+        request = request.header(header::USER_AGENT, user_agent);
+    }
+    if let Some(ref api_key) = config.api_key {
+        let value = match api_key.prefix {
+            Some(ref prefix) => format!("{prefix} {}", api_key.key),
+            None => api_key.key.clone(),
+        };
+        request = request.header("X-TBA-Auth-Key", value);
+    }
+    if let Some(etag) = cached.and_then(|cached| cached.etag.as_ref()) {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    let max_age = parse_max_age(response.headers());
+    let etag = response
+        .headers()
+        .get(header::ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .map(ToOwned::to_owned);
+
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok((None, etag, max_age));
+    }
+    if !status.is_success() {
+        anyhow::bail!("TBA returned {status} for {path}");
+    }
+    Ok((Some(response.text().await?), etag, max_age))
+}
+
+/// Fetches a path of the TBA API, like `/events/2025`, through the cache.
+///
+/// # Returns
+///
+/// The parsed response and how old it is.
+///
+/// # Errors
+///
+/// Returns an error if TBA can't be reached and the response has never been
+/// cached, if the response has not been recorded in replay mode, if the
+/// response can't be parsed, or if there is an issue with the database
+/// operations.
+pub async fn cached_get<T: DeserializeOwned>(path: &str) -> anyhow::Result<(T, CacheAge)> {
+    if tba_mode() == TbaMode::Replay {
+        let (body, recorded_at) = read_fixture(path)?;
+        return Ok((serde_json::from_str(&body)?, CacheAge::new(recorded_at, true)));
+    }
+
+    let cached = get_cached_response(path).await?;
+    let now = chrono::Utc::now().timestamp();
+
+    // Every response is revalidated while recording, so it is written to the
+    // fixtures
+    if let Some(cached) = cached.as_ref()
+        && cached.is_fresh(now)
+        && tba_mode() != TbaMode::Record
+    {
+        return Ok((
+            serde_json::from_str(&cached.body)?,
+            CacheAge::new(cached.fetched_at, false),
+        ));
+    }
+
+    match request(path, cached.as_ref()).await {
+        Ok((body, etag, max_age)) => {
+            // TBA may leave out the `ETag` of a response that has not changed
+            let etag = etag.or_else(|| cached.as_ref().and_then(|cached| cached.etag.clone()));
+            let Some(body) = body.or_else(|| cached.map(|cached| cached.body)) else {
+                anyhow::bail!("TBA returned no changes for {path}, which is not cached");
+            };
+            let value = serde_json::from_str(&body)?;
+            if tba_mode() == TbaMode::Record
+                && let Err(err) = write_fixture(path, &body)
+            {
+                tracing::warn!("Failed to record the response for {path}: {err}");
+            }
+            store_cached_response(
+                path,
+                &CachedResponse {
+                    etag,
+                    body,
+                    fetched_at: now,
+                    max_age,
+                },
+            )
+            .await?;
+            Ok((value, CacheAge::new(now, false)))
+        }
+        Err(err) => {
+            let Some(cached) = cached else {
+                return Err(err);
+            };
+            tracing::warn!("Using the cached response for {path}, TBA can't be reached: {err}");
+            Ok((
+                serde_json::from_str(&cached.body)?,
+                CacheAge::new(cached.fetched_at, true),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tbaapi::models::{Event, MatchSimple, SearchIndex};
+
+    use super::cached_get;
+    use crate::tba_fixtures::replay_from;
+
+    #[tokio::test]
+    async fn replays_recorded_responses() {
+        replay_from(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/tba"));
+
+        let (matches, age) = cached_get::<Vec<MatchSimple>>("/event/2025casj/matches/simple")
+            .await
+            .expect("Event matches should be recorded");
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[0].key, "2025casj_qm1");
+        assert!(matches[2].actual_time.is_none());
+        assert!(age.describe().starts_with("Offline"));
+
+        let (team_matches, _) =
+            cached_get::<Vec<MatchSimple>>("/team/frc4682/event/2025casj/matches/simple")
+                .await
+                .expect("Team matches should be recorded");
+        assert!(team_matches.iter().all(|x| {
+            x.alliances.red.team_keys.contains(&"frc4682".to_owned())
+                || x.alliances.blue.team_keys.contains(&"frc4682".to_owned())
+        }));
+
+        let (events, _) = cached_get::<Vec<Event>>("/events/2025")
+            .await
+            .expect("Events should be recorded");
+        assert_eq!(events[0].key, "2025casj");
+
+        let (index, _) = cached_get::<SearchIndex>("/search_index")
+            .await
+            .expect("Search index should be recorded");
+        assert!(index
+            .teams
+            .iter()
+            .any(|team| team.key == "frc4682" && team.nickname == "CyBears"));
+
+        assert!(cached_get::<SearchIndex>("/event/2025casj/teams")
+            .await
+            .is_err());
+    }
+}
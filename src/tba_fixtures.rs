@@ -0,0 +1,151 @@
+//! Records the responses of the TBA API to fixture files and replays them, so
+//! the server can run without internet or a TBA key.
+//!
+//! The mode is picked at startup with the `TBA_MODE` environment variable:
+//!
+//! - `live` (the default) fetches every response from TBA.
+//! - `record` fetches every response from TBA and also writes it to the
+//!   fixtures directory.
+//! - `replay` serves the recorded responses and never contacts TBA.
+//!
+//! The fixtures are stored in the directory given by `TBA_FIXTURES`, or
+//! `fixtures/tba` if it is not set, with one JSON file per path of the API:
+//! the response of `/event/2025casj/matches/simple` is stored in
+//! `event/2025casj/matches/simple.json`.
+
+#![cfg(feature = "ssr")]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use once_cell::sync::OnceCell;
+
+/// The environment variable picking the mode.
+const MODE_VAR: &str = "TBA_MODE";
+
+/// The environment variable giving the fixtures directory.
+const FIXTURES_VAR: &str = "TBA_FIXTURES";
+
+/// The fixtures directory used when `TBA_FIXTURES` is not set.
+const DEFAULT_FIXTURES_DIR: &str = "fixtures/tba";
+
+/// How the responses of TBA are fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TbaMode {
+    /// Every response is fetched from TBA.
+    Live,
+    /// Every response is fetched from TBA and written to the fixtures
+    /// directory.
+    Record,
+    /// The recorded responses are served, TBA is never contacted.
+    Replay,
+}
+
+/// The mode and fixtures directory picked at startup.
+#[derive(Debug)]
+struct Fixtures {
+    /// How the responses of TBA are fetched.
+    mode: TbaMode,
+    /// The directory the responses are recorded to and replayed from.
+    dir: PathBuf,
+}
+
+/// The mode and fixtures directory picked at startup.
+static FIXTURES: OnceCell<Fixtures> = OnceCell::new();
+
+/// Picks the mode given by the `TBA_MODE` environment variable and the
+/// fixtures directory given by `TBA_FIXTURES`.
+///
+/// # Errors
+///
+/// Returns an error if the mode is not `live`, `record` or `replay`.
+///
+/// # Panics
+///
+/// Panics if the mode has already been picked.
+pub fn load_tba_mode() -> anyhow::Result<TbaMode> {
+    let mode = match std::env::var(MODE_VAR) {
+        Ok(mode) => match mode.trim() {
+            "live" => TbaMode::Live,
+            "record" => TbaMode::Record,
+            "replay" => TbaMode::Replay,
+            mode => anyhow::bail!("{MODE_VAR} should be live, record or replay, found {mode:?}"),
+        },
+        Err(_) => TbaMode::Live,
+    };
+    let dir = std::env::var_os(FIXTURES_VAR).map_or_else(|| DEFAULT_FIXTURES_DIR.into(), PathBuf::from);
+
+    if mode != TbaMode::Live {
+        tracing::info!("TBA mode: {mode:?}, fixtures in {}", dir.display());
+    }
+    assert!(
+        FIXTURES.set(Fixtures { mode, dir }).is_ok(),
+        "TBA mode already loaded"
+    );
+
+    Ok(mode)
+}
+
+/// Returns the mode picked at startup, or `TbaMode::Live` if none has been
+/// picked.
+pub fn tba_mode() -> TbaMode {
+    FIXTURES.get().map_or(TbaMode::Live, |fixtures| fixtures.mode)
+}
+
+/// Returns the fixture file of a path of the TBA API.
+fn fixture_path(path: &str) -> PathBuf {
+    let dir = FIXTURES
+        .get()
+        .map_or_else(|| Path::new(DEFAULT_FIXTURES_DIR), |fixtures| &fixtures.dir);
+    dir.join(format!("{}.json", path.trim_start_matches('/')))
+}
+
+/// Reads the recorded response of a path of the TBA API.
+///
+/// # Returns
+///
+/// The body of the response and when it was recorded, in seconds since the
+/// Unix epoch.
+///
+/// # Errors
+///
+/// Returns an error if the response has not been recorded.
+pub fn read_fixture(path: &str) -> anyhow::Result<(String, i64)> {
+    let file = fixture_path(path);
+    let body = fs::read_to_string(&file)
+        .with_context(|| format!("No fixture for {path} in {}", file.display()))?;
+    let recorded_at = fs::metadata(&file)
+        .and_then(|metadata| metadata.modified())
+        .map_or(0, |modified| chrono::DateTime::<chrono::Utc>::from(modified).timestamp());
+
+    Ok((body, recorded_at))
+}
+
+/// Writes the response of a path of the TBA API to its fixture file,
+/// replacing the recorded one.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be written.
+pub fn write_fixture(path: &str, body: &str) -> anyhow::Result<()> {
+    let file = fixture_path(path);
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&file, body).with_context(|| format!("Failed to write {}", file.display()))?;
+
+    Ok(())
+}
+
+/// Replays the responses recorded in a directory, for the tests.
+#[cfg(test)]
+pub(crate) fn replay_from(dir: PathBuf) {
+    FIXTURES.get_or_init(|| Fixtures {
+        mode: TbaMode::Replay,
+        dir,
+    });
+}
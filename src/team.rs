@@ -0,0 +1,216 @@
+//! Identifies FRC teams by their number and, at the offseason events where a
+//! team fields more than one robot, the letter of the robot, like `254B`.
+//!
+//! Teams are stored and sent as text, like TBA does in its team keys
+//! (`frc254B`).
+
+use core::{fmt::Display, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::BlueScoutError;
+
+/// The highest team number.
+const MAX_NUMBER: u32 = 99_999;
+
+/// A team, or one of the robots of a team at an offseason event.
+///
+/// Teams are ordered by number, the main robot of a team before its lettered
+/// ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct TeamNumber {
+    /// The number of the team.
+    pub number: u32,
+    /// The uppercase letter of the robot, like `B`, or `None` for the main
+    /// robot.
+    pub letter: Option<char>,
+}
+
+impl TeamNumber {
+    /// Creates the `TeamNumber` of the main robot of a team.
+    #[must_use]
+    pub const fn new(number: u32) -> Self {
+        Self {
+            number,
+            letter: None,
+        }
+    }
+
+    /// Parses a team from its TBA key, like `frc254` or `frc254B`.
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        key.strip_prefix("frc")?.parse().ok()
+    }
+
+    /// Returns the TBA key of the team, like `frc254B`.
+    #[must_use]
+    pub fn key(self) -> String {
+        format!("frc{self}")
+    }
+
+    /// Returns the main robot of the team, without its letter.
+    #[must_use]
+    pub const fn without_letter(self) -> Self {
+        Self::new(self.number)
+    }
+}
+
+impl From<u32> for TeamNumber {
+    fn from(number: u32) -> Self {
+        Self::new(number)
+    }
+}
+
+impl Display for TeamNumber {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.letter {
+            Some(letter) => write!(f, "{}{letter}", self.number),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
+
+impl FromStr for TeamNumber {
+    type Err = BlueScoutError;
+
+    fn from_str(team: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            BlueScoutError::validation(
+                "team_number",
+                format!("{team:?} is not a team, like 254 or 254B"),
+            )
+        };
+
+        let team = team.trim();
+        let (number, letter) = match team.char_indices().last() {
+            Some((index, letter)) if letter.is_ascii_alphabetic() => {
+                (&team[..index], Some(letter.to_ascii_uppercase()))
+            }
+            _ => (team, None),
+        };
+        let number = number
+            .parse::<u32>()
+            .ok()
+            .filter(|number| (1..=MAX_NUMBER).contains(number))
+            .ok_or_else(invalid)?;
+
+        Ok(Self { number, letter })
+    }
+}
+
+impl Serialize for TeamNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TeamNumber {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Accepts the teams written as text, and the numbers of the teams
+        /// sent before they could have letters.
+        struct TeamVisitor;
+
+        impl de::Visitor<'_> for TeamVisitor {
+            type Value = TeamNumber;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "a team, like 254 or 254B")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                self.visit_str(&value.to_string())
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                self.visit_str(&value.to_string())
+            }
+        }
+
+        deserializer.deserialize_any(TeamVisitor)
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl duckdb::ToSql for TeamNumber {
+    fn to_sql(&self) -> duckdb::Result<duckdb::types::ToSqlOutput<'_>> {
+        Ok(duckdb::types::ToSqlOutput::from(self.to_string()))
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl duckdb::types::FromSql for TeamNumber {
+    fn column_result(value: duckdb::types::ValueRef<'_>) -> duckdb::types::FromSqlResult<Self> {
+        let team = value.as_str()?;
+        team.parse().map_err(|_| {
+            duckdb::types::FromSqlError::Other(format!("Invalid team: {team}").into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::TeamNumber;
+
+    #[test]
+    fn parses_lettered_teams() {
+        assert_eq!("254".parse::<TeamNumber>().ok(), Some(TeamNumber::new(254)));
+        assert_eq!(
+            "254b".parse::<TeamNumber>().ok(),
+            Some(TeamNumber {
+                number: 254,
+                letter: Some('B'),
+            })
+        );
+        assert_eq!(
+            "254B".parse::<TeamNumber>().map(|team| team.to_string()).ok().as_deref(),
+            Some("254B")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_teams() {
+        for team in ["", "0", "100000", "B", "254BB", "-254", "frc254"] {
+            assert!(team.parse::<TeamNumber>().is_err(), "{team:?} should be invalid");
+        }
+        assert!("99999".parse::<TeamNumber>().is_ok());
+    }
+
+    #[test]
+    fn parses_keys() {
+        assert_eq!(TeamNumber::from_key("frc254"), Some(TeamNumber::new(254)));
+        assert_eq!(
+            TeamNumber::from_key("frc254b"),
+            Some(TeamNumber {
+                number: 254,
+                letter: Some('B'),
+            })
+        );
+        assert_eq!(TeamNumber::from_key("254"), None);
+        assert_eq!(TeamNumber::from_key("frc0"), None);
+        assert_eq!(TeamNumber::new(254).key(), "frc254");
+    }
+
+    #[test]
+    fn deserializes_legacy_numbers() {
+        #[derive(Deserialize)]
+        struct Entry {
+            team_number: TeamNumber,
+        }
+
+        let entry: Entry = serde_json::from_str(r#"{"team_number": 4682}"#)
+            .expect("Numbers should be accepted");
+        assert_eq!(entry.team_number, TeamNumber::new(4682));
+        let entry: Entry = serde_json::from_str(r#"{"team_number": "254B"}"#)
+            .expect("Text should be accepted");
+        assert_eq!(entry.team_number.to_string(), "254B");
+        assert!(serde_json::from_str::<TeamNumber>("0").is_err());
+        assert!(serde_json::from_str::<TeamNumber>("-1").is_err());
+    }
+}
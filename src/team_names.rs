@@ -0,0 +1,147 @@
+//! The names of the FRC teams, shown next to their numbers.
+//!
+//! The names are kept in memory and in the `team_names` table. On startup they
+//! are loaded from the table, or from the snapshot bundled with the server
+//! when the table is empty, then fetched again from TBA on a schedule, so
+//! teams registered after the build get their name too.
+
+#![cfg(feature = "ssr")]
+
+use core::time::Duration;
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::{PoisonError, RwLock},
+};
+
+use tbaapi::models::SearchIndex;
+
+use crate::{
+    db::{get_team_names, store_team_names},
+    tba_cache::cached_get,
+    team::TeamNumber,
+};
+
+/// How often the team names are fetched again from TBA.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// The path of the snapshot bundled with the server, from the root of the
+/// repository.
+pub const SNAPSHOT_PATH: &str = "embed/team_names.json";
+
+/// The team names bundled with the server, used when they have never been
+/// fetched from TBA.
+const SNAPSHOT: &str = include_str!("../embed/team_names.json");
+
+/// The team names, by team number.
+static TEAM_NAMES: RwLock<BTreeMap<TeamNumber, String>> = RwLock::new(BTreeMap::new());
+
+/// Returns the name of a team, if it is known. Lettered robots, like `254B`,
+/// have the name of their team unless TBA lists them separately.
+#[must_use]
+pub fn team_name(team_number: TeamNumber) -> Option<String> {
+    let names = TEAM_NAMES.read().unwrap_or_else(PoisonError::into_inner);
+    names
+        .get(&team_number)
+        .or_else(|| names.get(&team_number.without_letter()))
+        .cloned()
+}
+
+/// Replaces the team names kept in memory.
+fn set_team_names(names: BTreeMap<TeamNumber, String>) {
+    *TEAM_NAMES.write().unwrap_or_else(PoisonError::into_inner) = names;
+}
+
+/// Fetches the team names from TBA and stores them in the `team_names` table.
+///
+/// # Errors
+///
+/// Returns an error if TBA can't be reached and the names have never been
+/// cached, if TBA returns no teams, or if there is an issue with the database
+/// operations.
+pub async fn refresh_team_names() -> anyhow::Result<()> {
+    let (index, _) = cached_get::<SearchIndex>("/search_index").await?;
+    let names = index
+        .teams
+        .into_iter()
+        .filter_map(|team| Some((TeamNumber::from_key(&team.key)?, team.nickname)))
+        .collect::<BTreeMap<TeamNumber, String>>();
+    if names.is_empty() {
+        anyhow::bail!("TBA returned no teams");
+    }
+
+    store_team_names(&names).await?;
+    set_team_names(names);
+    Ok(())
+}
+
+/// Loads the team names from the `team_names` table, or from the bundled
+/// snapshot when the table is empty, and starts fetching them again from TBA
+/// every `REFRESH_INTERVAL` in the background.
+///
+/// # Errors
+///
+/// Returns an error if there is an issue with the database operations or if
+/// the bundled snapshot can't be parsed.
+pub async fn init_team_names() -> anyhow::Result<()> {
+    let stored = get_team_names().await?;
+    if stored.is_empty() {
+        tracing::info!("No team names stored yet, using the bundled snapshot");
+        set_team_names(serde_json::from_str(SNAPSHOT)?);
+    } else {
+        set_team_names(stored);
+    }
+
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = refresh_team_names().await {
+                tracing::warn!("Failed to refresh the team names: {err}");
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Writes the team names kept in memory to a snapshot file, to bundle them
+/// with the next build.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be written.
+pub fn write_snapshot(path: &Path) -> anyhow::Result<()> {
+    let names = TEAM_NAMES.read().unwrap_or_else(PoisonError::into_inner);
+    let json = serde_json::to_string_pretty(&*names)?;
+    drop(names);
+
+    std::fs::write(path, json + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::SNAPSHOT;
+    use crate::team::TeamNumber;
+
+    /// The fewest teams in a snapshot of every FRC team. TBA lists many
+    /// thousands, so a smaller snapshot wasn't generated from TBA.
+    const MIN_TEAMS: usize = 1000;
+
+    #[test]
+    fn bundles_the_names_of_every_team() {
+        let names = serde_json::from_str::<BTreeMap<TeamNumber, String>>(SNAPSHOT)
+            .expect("The snapshot should parse");
+
+        assert!(
+            names.len() >= MIN_TEAMS,
+            "The snapshot has {} teams, run the server with --update-team-names",
+            names.len()
+        );
+        assert!(names.values().all(|name| !name.trim().is_empty()));
+        assert!(names.contains_key(&TeamNumber::new(254)));
+    }
+}
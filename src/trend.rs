@@ -0,0 +1,302 @@
+//! Trends of the values of a team over its matches, plotted by the charts of
+//! the team page and the match preview.
+//!
+//! A trend can follow any numeric field of the season schema or a value the
+//! game derives from every entry, like the points it scored.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::data::{DataType, DataTypeName, ScoutEntry};
+use crate::{team::TeamNumber, BlueScoutError};
+
+/// The fields that identify an entry rather than measure the robot.
+#[cfg(feature = "ssr")]
+const IGNORED_FIELDS: &[&str] = &["match_number", "team_number"];
+
+/// A value that can be plotted against the match number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TrendSeries {
+    /// The name of the field or derived value.
+    pub name: String,
+    /// The display name of the series.
+    pub pretty_name: String,
+}
+
+/// The value of a series in a match.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TrendPoint {
+    /// The number of the match.
+    pub match_number: u16,
+    /// The value of the series in the match.
+    pub value: f32,
+}
+
+/// The values of a series over the matches of a team.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Trend {
+    /// The display name of the series.
+    pub pretty_name: String,
+    /// The values of the team, in match order. Matches without a value are
+    /// skipped.
+    pub points: Vec<TrendPoint>,
+    /// The average value of every team at the event, or `None` if no value has
+    /// been recorded.
+    pub average: Option<f32>,
+}
+
+/// Returns the series that can be plotted: the values derived by the game,
+/// followed by the numeric and `bool` fields of the season schema.
+#[cfg(feature = "ssr")]
+#[must_use]
+pub fn trend_series() -> Vec<TrendSeries> {
+    let derived = crate::game::game()
+        .entry_metrics()
+        .iter()
+        .map(|metric| TrendSeries {
+            name: metric.name.to_owned(),
+            pretty_name: metric.pretty_name.to_owned(),
+        });
+    let fields = crate::schema::season_schema()
+        .fields
+        .iter()
+        .filter(|field| {
+            !matches!(field.data_type, DataTypeName::String | DataTypeName::Enum)
+                && !IGNORED_FIELDS.contains(&field.name.as_str())
+        })
+        .map(|field| TrendSeries {
+            name: field.name.clone(),
+            pretty_name: field.pretty_name.clone(),
+        });
+
+    derived.chain(fields).collect()
+}
+
+/// Returns the value of a series in an entry, counting `bool` fields as 1 when
+/// set.
+#[cfg(feature = "ssr")]
+// Values are plotted as `f32`, like the metrics
+#[allow(clippy::cast_possible_truncation)]
+fn entry_value(entry: &ScoutEntry, series: &str) -> Option<f32> {
+    if let Some(metric) = crate::game::game()
+        .entry_metrics()
+        .iter()
+        .find(|metric| metric.name == series)
+    {
+        return Some((metric.compute)(entry));
+    }
+
+    match entry.get_field(series)? {
+        DataType::Bool(set) => Some(if set { 1.0 } else { 0.0 }),
+        value => value.as_f64().map(|value| value as f32),
+    }
+}
+
+/// Computes the trend of a series.
+///
+/// # Arguments
+///
+/// * `series` - The series to follow.
+/// * `entries` - The entries of the team, in any order.
+/// * `event_entries` - The entries of every team at the event, for the
+///   reference average.
+#[cfg(feature = "ssr")]
+#[must_use]
+pub fn compute_trend(
+    series: &TrendSeries,
+    entries: &[ScoutEntry],
+    event_entries: &[ScoutEntry],
+) -> Trend {
+    // Matches scouted late are recorded after the following ones
+    let mut entries = entries.to_vec();
+    ScoutEntry::sort_by_match(&mut entries);
+
+    let points = entries
+        .iter()
+        .filter_map(|entry| {
+            entry_value(entry, &series.name).map(|value| TrendPoint {
+                match_number: entry.data.match_number,
+                value,
+            })
+        })
+        .collect();
+    let average = crate::data::average(
+        event_entries
+            .iter()
+            .map(|entry| entry_value(entry, &series.name)),
+    );
+
+    Trend {
+        pretty_name: series.pretty_name.clone(),
+        points,
+        average,
+    }
+}
+
+/// Fetches the series that can be plotted.
+///
+/// # Returns
+///
+/// A `Result` containing the `TrendSeries` or a `BlueScoutError`.
+#[server(endpoint = "fetch_trend_series")]
+pub async fn fetch_trend_series() -> Result<Vec<TrendSeries>, BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return Ok(trend_series());
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+/// Fetches the trend of a series for a team.
+///
+/// # Arguments
+///
+/// * `team_number` - The team to fetch the trend of.
+/// * `event` - The event to fetch the trend at.
+/// * `all_events` - Whether to include the matches of every event instead of
+///   only `event`.
+/// * `series` - The name of the series, one of `fetch_trend_series`.
+///
+/// # Returns
+///
+/// A `Result` containing the `Trend` or a `BlueScoutError`.
+#[server(endpoint = "fetch_team_trend")]
+pub async fn fetch_team_trend(
+    team_number: TeamNumber,
+    event: Option<String>,
+    all_events: bool,
+    series: String,
+) -> Result<Trend, BlueScoutError> {
+    #[cfg(feature = "ssr")]
+    return {
+        use crate::db::get_data;
+
+        let series = trend_series()
+            .into_iter()
+            .find(|other| other.name == series)
+            .ok_or_else(|| BlueScoutError::custom(format!("Unknown series {series}")))?;
+        let event_entries = get_data(event.as_deref().filter(|_| !all_events))
+            .await
+            .map_err(BlueScoutError::database_error)?;
+        let entries = event_entries
+            .iter()
+            .filter(|entry| entry.data.team_number == team_number)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(compute_trend(&series, &entries, &event_entries))
+    };
+    #[cfg(not(feature = "ssr"))]
+    {
+        tracing::error!("Server function called without ssr feature enabled");
+        unreachable!("This should only be called on the server");
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{compute_trend, entry_value, TrendPoint, TrendSeries};
+    use crate::data::{DataPoint, DataType, ScoutEntry};
+
+    /// Returns an entry of a match with a value of the `penalties` field added
+    /// by the season schema.
+    fn entry(id: i32, match_number: u16, penalties: Option<u16>) -> ScoutEntry {
+        ScoutEntry {
+            id,
+            data: DataPoint {
+                match_number,
+                auto_leave: match_number.is_multiple_of(2),
+                ..DataPoint::empty()
+            },
+            extra: penalties
+                .map(|penalties| ("penalties".to_owned(), DataType::U16(penalties)))
+                .into_iter()
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    /// Returns the series of a field.
+    fn series(name: &str) -> TrendSeries {
+        TrendSeries {
+            name: name.to_owned(),
+            pretty_name: name.to_owned(),
+        }
+    }
+
+    #[test]
+    fn reads_the_values_of_entries() {
+        let scouted = entry(1, 4, Some(2));
+
+        assert_eq!(entry_value(&scouted, "penalties"), Some(2.0));
+        assert_eq!(entry_value(&scouted, "auto_leave"), Some(1.0));
+        assert_eq!(entry_value(&scouted, "notes"), None);
+        assert_eq!(entry_value(&scouted, "unknown"), None);
+        assert_eq!(
+            entry_value(&scouted, "points"),
+            Some(scouted.data.estimated_points() as f32)
+        );
+    }
+
+    #[test]
+    fn plots_the_matches_in_order() {
+        // Match 3 was scouted late
+        let entries = [
+            entry(1, 1, Some(1)),
+            entry(2, 5, Some(5)),
+            entry(3, 3, Some(3)),
+        ];
+
+        let trend = compute_trend(&series("penalties"), &entries, &entries);
+        assert_eq!(
+            trend.points,
+            [(1, 1.0), (3, 3.0), (5, 5.0)].map(|(match_number, value)| TrendPoint {
+                match_number,
+                value,
+            })
+        );
+    }
+
+    #[test]
+    fn skips_missing_values() {
+        let entries = [
+            entry(1, 1, Some(4)),
+            entry(2, 2, None),
+            entry(3, 3, Some(0)),
+        ];
+
+        let trend = compute_trend(&series("penalties"), &entries, &entries);
+        let matches = trend
+            .points
+            .iter()
+            .map(|point| point.match_number)
+            .collect::<Vec<_>>();
+        assert_eq!(matches, [1, 3]);
+        assert_eq!(trend.average, Some(2.0));
+    }
+
+    #[test]
+    fn averages_every_team_at_the_event() {
+        let entries = [entry(1, 1, Some(1))];
+        let event_entries = [
+            entry(1, 1, Some(1)),
+            entry(2, 1, Some(5)),
+            entry(3, 2, None),
+            entry(4, 2, Some(6)),
+        ];
+
+        let trend = compute_trend(&series("penalties"), &entries, &event_entries);
+        assert_eq!(trend.average, Some(4.0));
+        assert_eq!(
+            compute_trend(&series("penalties"), &entries, &[]).average,
+            None
+        );
+    }
+}